@@ -2,19 +2,33 @@ use std::str::FromStr;
 
 use crate::error::ContractError;
 use crate::handlers::{
-    add_bot_tip, cancel_dca_order, create_dca_order, modify_dca_order, perform_dca_purchase,
-    update_config, update_user_config, withdraw, CreateDcaOrder, ModifyDcaOrderParameters,
+    add_bot_tip, batch_swap_reply, cancel_dca_order, cancel_pending_config, challenge_execution,
+    claim_keeper_bond, claim_ownership, create_dca_order, drop_ownership_proposal,
+    emergency_withdraw_all, execute_pending_config, keeper_swap_reply, modify_dca_order, pause,
+    perform_dca_purchase, perform_dca_purchases, propose_new_owner, register_keeper,
+    remove_fee_asset, remove_route, remove_whitelisted_tokens, set_fee_asset, set_route,
+    unbond_keeper, unpause, update_config, update_user_config, withdraw, CreateDcaOrder,
+    ModifyDcaOrderParameters, PerformDcaPurchase, BATCH_SWAP_REPLY_ID, KEEPER_SWAP_REPLY_ID,
+};
+use crate::migration::{apply_migrate_msg, migrate_state, parse_version};
+use crate::queries::{
+    estimate_tip, get_config, get_keeper, get_keeper_execution, get_keeper_stats, get_keepers,
+    get_paused, get_pending_config, get_purchase_history, get_referrer_stats, get_route,
+    get_user_config, get_user_dca_orders, simulate_dca_purchase,
+};
+use crate::state::{
+    default_allowed_pair_types, Config, UpdateConfigParameters, CONFIG, DEFAULT_CONFIG_TIMELOCK,
+    DEFAULT_MAX_ORDERS_PER_USER,
 };
-use crate::queries::{get_config, get_user_config, get_user_dca_orders};
-use crate::state::{Config, CONFIG};
 
 use astroport::asset::addr_validate_to_lower;
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Response, StdResult,
+    attr, entry_point, to_binary, Binary, Decimal, Deps, DepsMut, Env, MessageInfo, Reply,
+    Response, StdResult,
 };
 
 use astroport_dca::dca::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 
 /// Contract name that is used for migration.
 const CONTRACT_NAME: &str = "astroport-dca";
@@ -44,52 +58,127 @@ pub fn instantiate(
     // get max spread in decimal form
     let max_spread = Decimal::from_str(&msg.max_spread)?;
 
-    // validate that factory_addr and router_addr is an address
+    // validate that owner, guardian, factory_addr and router_addr is an address
+    let owner = addr_validate_to_lower(deps.api, &msg.owner)?;
+    let guardian = msg
+        .guardian
+        .map(|addr| addr_validate_to_lower(deps.api, &addr))
+        .transpose()?;
     let factory_addr = addr_validate_to_lower(deps.api, &msg.factory_addr)?;
     let router_addr = addr_validate_to_lower(deps.api, &msg.router_addr)?;
+    let fee_collector = msg
+        .fee_collector
+        .map(|addr| addr_validate_to_lower(deps.api, &addr))
+        .transpose()?;
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     let config = Config {
+        owner,
+        guardian,
         max_hops: msg.max_hops,
         whitelisted_fee_assets: msg.whitelisted_fee_assets,
         whitelisted_tokens: msg.whitelisted_tokens,
         max_spread,
         factory_addr,
         router_addr,
+        protocol_fee: msg.protocol_fee.unwrap_or_default(),
+        fee_collector,
+        max_referral_fee_bps: msg.max_referral_fee_bps,
+        keeper_config: msg.keeper_config,
+        allowed_pair_types: msg
+            .allowed_pair_types
+            .unwrap_or_else(default_allowed_pair_types),
+        pair_type_max_spreads: msg.pair_type_max_spreads.unwrap_or_default(),
+        venues: msg.venues.unwrap_or_default(),
+        config_timelock: msg.config_timelock.unwrap_or(DEFAULT_CONFIG_TIMELOCK),
+        allowed_initial_assets: msg.allowed_initial_assets.unwrap_or_default(),
+        allowed_target_assets: msg.allowed_target_assets.unwrap_or_default(),
+        min_dca_amounts: msg.min_dca_amounts.unwrap_or_default(),
+        max_orders_per_user: msg
+            .max_orders_per_user
+            .unwrap_or(DEFAULT_MAX_ORDERS_PER_USER),
+        order_deposit: msg.order_deposit.filter(|d| !d.amount.is_zero()),
     };
 
+    config.validate(deps.api)?;
+
     CONFIG.save(deps.storage, &config)?;
 
     Ok(Response::new())
 }
 
 /// ## Description
-/// Used for contract migration. Returns a default object of type [`Response`].
+/// Used for contract migration, which checks that the stored contract is this contract at the
+/// same or an older version, runs the state migration of every newer version and sets the
+/// optional configuration parameters of the [`MigrateMsg`].
+///
+/// Returns a [`Response`] with the specified attributes if the operation was successful,
+/// or a [`ContractError`] if the contract was not migrated.
 /// ## Arguments
-/// * `_deps` - A [`DepsMut`] that contains the dependencies.
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
 ///
 /// * `_env` - The [`Env`] of the blockchain.
 ///
-/// * `_msg` - The [`MigrateMsg`] to migrate the contract.
+/// * `msg` - The [`MigrateMsg`] to migrate the contract.
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn migrate(_deps: DepsMut, _env: Env, _msg: MigrateMsg) -> StdResult<Response> {
-    Ok(Response::default())
+pub fn migrate(mut deps: DepsMut, _env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let stored = get_contract_version(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        return Err(ContractError::InvalidContractName {
+            name: stored.contract,
+        });
+    }
+
+    let from = parse_version(&stored.version)?;
+    let to = parse_version(CONTRACT_VERSION)?;
+    if from > to {
+        return Err(ContractError::MigrationDowngrade {
+            from: stored.version,
+            to: CONTRACT_VERSION.to_string(),
+        });
+    }
+
+    migrate_state(deps.branch(), from, to, &msg)?;
+    apply_migrate_msg(deps.branch(), msg)?;
+
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "migrate"),
+        attr("from_version", stored.version),
+        attr("to_version", CONTRACT_VERSION),
+    ]))
 }
 
 #[cfg(test)]
 mod test {
     use astroport_dca::dca::MigrateMsg;
-    use cosmwasm_std::{testing::{mock_dependencies, mock_env}, Response};
+    use cosmwasm_std::{attr, testing::mock_env, Addr};
+
+    use crate::tests::mock_instantiate;
 
     use super::migrate;
 
     #[test]
     fn can_migrate() {
-        let mut deps = mock_dependencies();
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
-        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
-        assert_eq!(res, Response::new());
+        // migrating to the same version leaves the state as it is
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "migrate"),
+                attr("from_version", super::CONTRACT_VERSION),
+                attr("to_version", super::CONTRACT_VERSION),
+            ]
+        );
     }
 }
 
@@ -107,15 +196,42 @@ mod test {
 /// ## Execution Messages
 /// * **ExecuteMsg::AddBotTip { assets: Vec<Asset> }** Adds a bot tip to fund DCA purchases.
 ///
+/// * **ExecuteMsg::AddWhitelistedTokens { tokens }** Queues adding tokens to the whitelisted
+/// tokens behind the configuration timelock.
+///
 /// * **ExecuteMsg::CancelDcaOrder { initial_asset }** Cancels an existing DCA order.
 ///
+/// * **ExecuteMsg::CancelPendingConfig {}** Cancels the queued configuration update.
+///
+/// * **ExecuteMsg::ChallengeExecution { id }** Challenges a DCA purchase performed by a registered
+/// keeper, slashing their bond if it returned far less than the TWAP reference.
+///
+/// * **ExecuteMsg::ClaimKeeperBond {}** Claims the bond of an unbonded keeper.
+///
+/// * **ExecuteMsg::ClaimOwnership {}** Claims the ownership of the contract by the proposed owner.
+///
 /// * **ExecuteMsg::CreateDcaOrder {
 ///         initial_asset,
 ///         target_asset,
 ///         interval,
-///         dca_amount
+///         dca_amount,
+///         first_purchase,
+///         referrer,
+///         referral_fee_bps,
+///         preferred_executor,
+///         exclusivity_seconds,
+///         require_canonical_route,
+///         max_price_impact
 ///     }** Creates a new DCA order where `initial_asset` will purchase `target_asset`.
 ///
+/// * **ExecuteMsg::DropOwnershipProposal {}** Drops the current ownership transfer proposal.
+///
+/// * **ExecuteMsg::EmergencyWithdrawAll {}** Cancels all DCA orders of the sender and returns
+/// every asset held for them, even while the contract is paused.
+///
+/// * **ExecuteMsg::ExecutePendingConfig {}** Applies the queued configuration update once its
+/// timelock has passed.
+///
 /// * **ExecuteMsg::ModifyDcaOrder {
 ///         old_initial_asset,
 ///         new_initial_asset,
@@ -125,19 +241,58 @@ mod test {
 ///         should_reset_purchase_time,
 ///     }** Modifies an existing DCA order, allowing the user to change certain parameters.
 ///
-/// * **ExecuteMsg::PerformDcaPurchase { user, hops }** Performs a DCA purchase on behalf of a
-/// specified user given a hop route.
+/// * **ExecuteMsg::Pause { scope }** Pauses a scope of the contract in an emergency.
+///
+/// * **ExecuteMsg::PerformDcaPurchase { user, hops, venue }** Performs a DCA purchase on behalf
+/// of a specified user given a hop route, through a whitelisted swap venue.
+///
+/// * **ExecuteMsg::PerformDcaPurchases { purchases }** Performs several DCA purchases in a batched
+/// execution, swapping purchases with the same assets and hop route together.
+///
+/// * **ExecuteMsg::ProposeNewOwner { owner, expires_in }** Proposes a new owner of the contract,
+/// which can claim the ownership within `expires_in` seconds.
 ///
 /// * **ExecuteMsg::UpdateConfig {
 ///         max_hops,
-///         per_hop_fee,
-///         whitelisted_tokens,
-///         max_spread
-///     }** Updates the contract configuration with the specified input parameters.
+///         max_spread,
+///         protocol_fee,
+///         fee_collector,
+///         max_referral_fee_bps,
+///         keeper_config,
+///         allowed_pair_types,
+///         pair_type_max_spreads,
+///         venues,
+///         guardian,
+///         config_timelock,
+///         factory_addr,
+///         router_addr,
+///         allowed_initial_assets,
+///         allowed_target_assets,
+///         min_dca_amounts,
+///         max_orders_per_user,
+///         order_deposit
+///     }** Queues an update of the contract configuration with the specified input parameters.
+///
+/// * **ExecuteMsg::RegisterKeeper {}** Registers the sender as a keeper by bonding the keeper bond.
+///
+/// * **ExecuteMsg::RemoveFeeAsset { asset }** Removes a whitelisted fee asset.
+///
+/// * **ExecuteMsg::RemoveRoute { from, to }** Removes a canonical hop route.
+///
+/// * **ExecuteMsg::RemoveWhitelistedTokens { tokens }** Removes tokens from the whitelisted tokens.
+///
+/// * **ExecuteMsg::SetFeeAsset { fee_asset }** Whitelists a fee asset or updates its tip.
+///
+/// * **ExecuteMsg::SetRoute { from, to, hops }** Registers a canonical hop route.
+///
+/// * **ExecuteMsg::UnbondKeeper {}** Starts unbonding the bond of a keeper.
+///
+/// * **ExecuteMsg::Unpause { scope }** Unpauses a scope of the contract.
 ///
 /// * **ExecuteMsg::UpdateUserConfig {
 ///         max_hops,
 ///         max_spread,
+///         max_price_impact,
 ///     }** Updates a users configuration with the new input parameters.
 ///
 /// * **ExecuteMsg::Withdraw { tip }** Withdraws a bot tip from the contract.
@@ -151,27 +306,66 @@ pub fn execute(
     match msg {
         ExecuteMsg::UpdateConfig {
             max_hops,
-            whitelisted_tokens,
-            whitelisted_fee_assets,
             max_spread,
+            protocol_fee,
+            fee_collector,
+            max_referral_fee_bps,
+            keeper_config,
+            allowed_pair_types,
+            pair_type_max_spreads,
+            venues,
+            guardian,
+            config_timelock,
+            factory_addr,
+            router_addr,
+            allowed_initial_assets,
+            allowed_target_assets,
+            min_dca_amounts,
+            max_orders_per_user,
+            order_deposit,
         } => update_config(
             deps,
+            env,
             info,
-            max_hops,
-            whitelisted_fee_assets,
-            whitelisted_tokens,
-            max_spread,
+            UpdateConfigParameters {
+                max_hops,
+                add_whitelisted_tokens: None,
+                max_spread,
+                protocol_fee,
+                fee_collector,
+                max_referral_fee_bps,
+                keeper_config,
+                allowed_pair_types,
+                pair_type_max_spreads,
+                venues,
+                guardian,
+                config_timelock,
+                factory_addr,
+                router_addr,
+                allowed_initial_assets,
+                allowed_target_assets,
+                min_dca_amounts,
+                max_orders_per_user,
+                order_deposit,
+            },
         ),
         ExecuteMsg::UpdateUserConfig {
             max_hops,
             max_spread,
-        } => update_user_config(deps, info, max_hops, max_spread),
+            max_price_impact,
+        } => update_user_config(deps, info, max_hops, max_spread, max_price_impact),
         ExecuteMsg::CreateDcaOrder {
             initial_asset,
             target_asset,
             interval,
             dca_amount,
             first_purchase,
+            referrer,
+            referral_fee_bps,
+            preferred_executor,
+            exclusivity_seconds,
+            require_canonical_route,
+            max_price_impact,
         } => create_dca_order(
             deps,
             env,
@@ -182,17 +376,68 @@ pub fn execute(
                 interval,
                 dca_amount,
                 first_purchase,
+                referrer,
+                referral_fee_bps,
+                preferred_executor,
+                exclusivity_seconds,
+                require_canonical_route,
+                max_price_impact,
             },
         ),
         ExecuteMsg::AddBotTip { assets } => add_bot_tip(deps, env, info, assets),
+        ExecuteMsg::AddWhitelistedTokens { tokens } => update_config(
+            deps,
+            env,
+            info,
+            UpdateConfigParameters {
+                add_whitelisted_tokens: Some(tokens),
+                ..UpdateConfigParameters::default()
+            },
+        ),
+        ExecuteMsg::RemoveWhitelistedTokens { tokens } => {
+            remove_whitelisted_tokens(deps, info, tokens)
+        }
+        ExecuteMsg::SetFeeAsset { fee_asset } => set_fee_asset(deps, info, fee_asset),
+        ExecuteMsg::RemoveFeeAsset { asset } => remove_fee_asset(deps, info, asset),
         ExecuteMsg::Withdraw { assets } => withdraw(deps, info, assets),
         ExecuteMsg::PerformDcaPurchase {
             user,
             hops,
             id,
             fee_redeem,
-        } => perform_dca_purchase(deps, env, info, user, id, hops, fee_redeem),
+            venue,
+        } => perform_dca_purchase(
+            deps,
+            env,
+            info,
+            PerformDcaPurchase {
+                user,
+                id,
+                hops,
+                fee_redeem,
+                venue,
+            },
+        ),
+        ExecuteMsg::PerformDcaPurchases { purchases } => {
+            perform_dca_purchases(deps, env, info, purchases)
+        }
         ExecuteMsg::CancelDcaOrder { id } => cancel_dca_order(deps, info, id),
+        ExecuteMsg::RegisterKeeper {} => register_keeper(deps, env, info),
+        ExecuteMsg::UnbondKeeper {} => unbond_keeper(deps, env, info),
+        ExecuteMsg::ClaimKeeperBond {} => claim_keeper_bond(deps, env, info),
+        ExecuteMsg::ChallengeExecution { id } => challenge_execution(deps, env, id),
+        ExecuteMsg::SetRoute { from, to, hops } => set_route(deps, info, from, to, hops),
+        ExecuteMsg::RemoveRoute { from, to } => remove_route(deps, info, from, to),
+        ExecuteMsg::ProposeNewOwner { owner, expires_in } => {
+            propose_new_owner(deps, env, info, owner, expires_in)
+        }
+        ExecuteMsg::ClaimOwnership {} => claim_ownership(deps, env, info),
+        ExecuteMsg::DropOwnershipProposal {} => drop_ownership_proposal(deps, info),
+        ExecuteMsg::EmergencyWithdrawAll {} => emergency_withdraw_all(deps, info),
+        ExecuteMsg::ExecutePendingConfig {} => execute_pending_config(deps, env, info),
+        ExecuteMsg::CancelPendingConfig {} => cancel_pending_config(deps, info),
+        ExecuteMsg::Pause { scope } => pause(deps, info, scope),
+        ExecuteMsg::Unpause { scope } => unpause(deps, info, scope),
         ExecuteMsg::ModifyDcaOrder {
             id,
             new_initial_asset,
@@ -229,16 +474,112 @@ pub fn execute(
 /// * **QueryMsg::Config {}** Returns information about the configuration of the contract in a
 /// [`Config`] object.
 ///
+/// * **QueryMsg::PendingConfig {}** Returns the queued configuration update in a
+/// [`Option<PendingConfig>`] object.
+///
+/// * **QueryMsg::Paused {}** Returns the paused scopes of the contract in a [`Vec<PauseScope>`]
+/// object.
+///
 /// * **QueryMsg::UserConfig {}** Returns information about a specified users configuration set for
 /// DCA purchases in a [`UserConfig`] object.
 ///
 /// * **QueryMsg::UserDcaOrders {}** Returns information about a specified users current DCA orders
 /// set in a [`Vec<DcaInfo>`] object.
+///
+/// * **QueryMsg::EstimateTip { user, id, hops }** Returns the full tip paid for performing a DCA
+/// purchase with the specified amount of hops in a [`Vec<Asset>`] object.
+///
+/// * **QueryMsg::ReferrerStats { referrer }** Returns the volume originated and the fees earned by
+/// a referrer in a [`ReferrerStats`] object.
+///
+/// * **QueryMsg::Keeper { keeper }** Returns the registration of a keeper in a [`Option<Keeper>`]
+/// object.
+///
+/// * **QueryMsg::KeeperExecution { id }** Returns the record of a DCA purchase performed by a
+/// registered keeper in a [`KeeperExecution`] object.
+///
+/// * **QueryMsg::KeeperStats { keeper }** Returns the amount of DCA purchases performed and the tips
+/// earned by an address in a [`KeeperStats`] object.
+///
+/// * **QueryMsg::Keepers { start_after, limit }** Returns the statistics of every address that has
+/// performed DCA purchases in a [`Vec<KeeperInfo>`] object.
+///
+/// * **QueryMsg::Route { from, to }** Returns the canonical hop route between two assets in a
+/// [`Option<Vec<SwapOperation>>`] object.
+///
+/// * **QueryMsg::PurchaseHistory { user, id }** Returns the DCA purchases performed for a DCA
+/// order in a [`Vec<PurchaseRecord>`] object.
+///
+/// * **QueryMsg::SimulateDcaPurchase {
+///         user,
+///         id,
+///         hops,
+///         fee_redeem,
+///         venue,
+///         executor
+///     }** Dry runs a DCA purchase, returning its expected outcome in a
+/// [`SimulateDcaPurchaseResponse`] object.
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary(&get_config(deps)?),
+        QueryMsg::Paused {} => to_binary(&get_paused(deps)?),
+        QueryMsg::PendingConfig {} => to_binary(&get_pending_config(deps)?),
         QueryMsg::UserConfig { user } => to_binary(&get_user_config(deps, user)?),
         QueryMsg::UserDcaOrders { user } => to_binary(&get_user_dca_orders(deps, env, user)?),
+        QueryMsg::EstimateTip { user, id, hops } => to_binary(&estimate_tip(deps, user, id, hops)?),
+        QueryMsg::ReferrerStats { referrer } => to_binary(&get_referrer_stats(deps, referrer)?),
+        QueryMsg::Keeper { keeper } => to_binary(&get_keeper(deps, keeper)?),
+        QueryMsg::KeeperExecution { id } => to_binary(&get_keeper_execution(deps, id)?),
+        QueryMsg::KeeperStats { keeper } => to_binary(&get_keeper_stats(deps, keeper)?),
+        QueryMsg::Keepers { start_after, limit } => {
+            to_binary(&get_keepers(deps, start_after, limit)?)
+        }
+        QueryMsg::Route { from, to } => to_binary(&get_route(deps, from, to)?),
+        QueryMsg::PurchaseHistory { user, id } => to_binary(&get_purchase_history(deps, user, id)?),
+        QueryMsg::SimulateDcaPurchase {
+            user,
+            id,
+            hops,
+            fee_redeem,
+            venue,
+            executor,
+        } => to_binary(&simulate_dca_purchase(
+            deps,
+            env,
+            executor,
+            PerformDcaPurchase {
+                user,
+                id,
+                hops,
+                fee_redeem,
+                venue,
+            },
+        )?),
+    }
+}
+
+/// ## Description
+/// Exposes all the replies available in the contract.
+///
+/// Returns a [`ContractError`] if the reply ID is unknown.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `msg` - The [`Reply`] to handle.
+///
+/// ## Replies
+/// * **BATCH_SWAP_REPLY_ID** Distributes the output of a batched swap.
+///
+/// * **KEEPER_SWAP_REPLY_ID** Records the output of the swap of a DCA purchase performed by a
+/// registered keeper and sends it to the user.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn reply(deps: DepsMut, env: Env, msg: Reply) -> Result<Response, ContractError> {
+    match msg.id {
+        BATCH_SWAP_REPLY_ID => batch_swap_reply(deps, env),
+        KEEPER_SWAP_REPLY_ID => keeper_swap_reply(deps, env),
+        id => Err(ContractError::UnknownReplyId { id }),
     }
 }
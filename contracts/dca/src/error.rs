@@ -1,5 +1,9 @@
-use astroport::asset::{Asset, AssetInfo};
-use cosmwasm_std::{OverflowError, StdError, Uint128};
+use astroport::{
+    asset::{Asset, AssetInfo},
+    factory::PairType,
+};
+use astroport_dca::dca::PauseScope;
+use cosmwasm_std::{Addr, Decimal, OverflowError, StdError, Uint128};
 use thiserror::Error;
 
 /// ## Description
@@ -42,6 +46,45 @@ pub enum ContractError {
     #[error("The hop route specified was empty")]
     EmptyHopRoute {},
 
+    #[error("No route could be found to purchase the target asset")]
+    NoRouteFound {},
+
+    #[error("Route must swap from the offered asset to the target asset")]
+    InvalidRoute {},
+
+    #[error("No canonical route is registered for the order")]
+    NonexistentRoute {},
+
+    #[error("Hop route does not match the canonical route of the order")]
+    NonCanonicalRoute {},
+
+    #[error("First hop must offer the initial asset {initial_asset}")]
+    InvalidFirstHop { initial_asset: AssetInfo },
+
+    #[error("Hop {hop} must offer the asset returned by the previous hop")]
+    DisconnectedHop { hop: usize },
+
+    #[error("No Astroport pair exists for {offer} and {ask}")]
+    NonexistentPair { offer: AssetInfo, ask: AssetInfo },
+
+    #[error("Pair type {pair_type} is not allowed in hop routes")]
+    PairTypeNotAllowed { pair_type: PairType },
+
+    #[error("Swap venue {name} is not whitelisted")]
+    NonexistentVenue { name: String },
+
+    #[error("Swap venue {name} is whitelisted more than once")]
+    DuplicateVenue { name: String },
+
+    #[error("A pair venue can only perform a single swap through the pair")]
+    InvalidPairVenueRoute {},
+
+    #[error("Batched execution must contain at least one DCA purchase")]
+    EmptyBatch {},
+
+    #[error("Unknown reply ID {id}")]
+    UnknownReplyId { id: u64 },
+
     #[error("DCA purchase occurred too early")]
     PurchaseTooEarly {},
 
@@ -60,6 +103,33 @@ pub enum ContractError {
     #[error("Initial asset deposited is not divisible by the DCA amount")]
     IndivisibleDeposit {},
 
+    #[error("Asset {asset} is not allowed as the initial asset of DCA orders")]
+    InitialAssetNotAllowed { asset: AssetInfo },
+
+    #[error("Asset {asset} is not allowed as the target asset of DCA orders")]
+    TargetAssetNotAllowed { asset: AssetInfo },
+
+    #[error("DCA amount is less than the minimum of {min}")]
+    DcaAmountTooSmall { min: Asset },
+
+    #[error("User cannot have more than {max} DCA orders")]
+    TooManyOrders { max: u32 },
+
+    #[error("Maximum amount of hops has to be at least 1")]
+    InvalidMaxHops {},
+
+    #[error("Maximum spread cannot be larger than {max}")]
+    MaxSpreadTooLarge { max: Decimal },
+
+    #[error("Token {token} is whitelisted more than once")]
+    DuplicateWhitelistedToken { token: AssetInfo },
+
+    #[error("Fee asset {asset} is whitelisted more than once")]
+    DuplicateFeeAsset { asset: AssetInfo },
+
+    #[error("Fee asset {asset} does not pay a tip")]
+    ZeroFeeAsset { asset: AssetInfo },
+
     #[error("Attempt to perform tip deposit with {sent}, but only received {received}")]
     InvalidTipDeposit { received: Asset, sent: Asset },
 
@@ -72,9 +142,128 @@ pub enum ContractError {
     #[error("Attempt to withdraw asset {asset} that was not deposited")]
     TipAssetNotDeposited { asset: AssetInfo },
 
-    #[error("Tip redemption of {requested} hops, but only {performed} hops was performed")]
-    RedeemTipTooLarge {
-        requested: Uint128,
-        performed: Uint128,
+    #[error("Tip redemption of {requested} is larger than the full tip of {tip}")]
+    RedeemTipTooLarge { requested: Asset, tip: Asset },
+
+    #[error("Tip redemption of {requested} times the full tip exceeds the tip for the purchase")]
+    RedeemTipExceedsTip { requested: Decimal },
+
+    #[error("Protocol fee cannot be larger than {max}")]
+    ProtocolFeeTooLarge { max: Decimal },
+
+    #[error("A fee collector must be set to take a protocol fee")]
+    MissingFeeCollector {},
+
+    #[error("Referral fee cannot be larger than {max} basis points")]
+    ReferralFeeTooLarge { max: u16 },
+
+    #[error("A referral fee can only be set with a referrer")]
+    MissingReferrer {},
+
+    #[error("An exclusivity period can only be set with a preferred executor")]
+    MissingPreferredExecutor {},
+
+    #[error("Exclusivity period cannot be longer than the order interval")]
+    ExclusivityTooLong {},
+
+    #[error("Only {executor} can perform this DCA purchase until {until}")]
+    NotPreferredExecutor { executor: Addr, until: u64 },
+
+    #[error("Slash threshold must be less than one")]
+    InvalidSlashThreshold {},
+
+    #[error("Keeper mode is not configured")]
+    KeeperModeNotConfigured {},
+
+    #[error("Sender is not a registered keeper")]
+    KeeperNotRegistered {},
+
+    #[error("Keeper is already registered")]
+    KeeperAlreadyRegistered {},
+
+    #[error("Keeper bond is less than the bond required")]
+    InsufficientKeeperBond {},
+
+    #[error("Keeper is unbonding")]
+    KeeperUnbonding {},
+
+    #[error("Keeper has not started unbonding")]
+    KeeperNotUnbonding {},
+
+    #[error("Keeper bond can be claimed after {claimable_at}")]
+    KeeperBondLocked { claimable_at: u64 },
+
+    #[error("Keeper execution does not exist")]
+    NonexistentExecution {},
+
+    #[error("Keeper execution cannot be verified against a TWAP")]
+    UnverifiableExecution {},
+
+    #[error("Challenge period for the keeper execution is over")]
+    ChallengePeriodOver {},
+
+    #[error("Keeper execution returned {returned}, which is within the threshold of the TWAP reference of {reference}")]
+    ExecutionWithinThreshold {
+        returned: Uint128,
+        reference: Uint128,
     },
+
+    #[error(
+        "Price impact of {price_impact} exceeds the maximum price impact of {max_price_impact}"
+    )]
+    PriceImpactTooHigh {
+        price_impact: Decimal,
+        max_price_impact: Decimal,
+    },
+
+    #[error("Price impact cannot be calculated for the hop route")]
+    UnknownPriceImpact {},
+
+    #[error("Ownership proposal cannot be valid for longer than {max} seconds")]
+    ProposalTtlTooLong { max: u64 },
+
+    #[error("New owner cannot be the current owner")]
+    SameOwner {},
+
+    #[error("Ownership proposal does not exist")]
+    NonexistentOwnershipProposal {},
+
+    #[error("Ownership proposal has expired")]
+    OwnershipProposalExpired {},
+
+    #[error("{addr} is not an Astroport factory contract")]
+    InvalidFactory { addr: String },
+
+    #[error("{addr} is not an Astroport router contract of the factory")]
+    InvalidRouter { addr: String },
+
+    #[error("Pending configuration update does not exist")]
+    NonexistentPendingConfig {},
+
+    #[error("A configuration update is already pending and has to be executed or cancelled first")]
+    PendingConfigExists {},
+
+    #[error("Pending configuration update cannot be executed before {activation_time}")]
+    PendingConfigNotActive { activation_time: u64 },
+
+    #[error("Contract is paused for {scope}")]
+    Paused { scope: PauseScope },
+
+    #[error("Contract is already paused for {scope}")]
+    AlreadyPaused { scope: PauseScope },
+
+    #[error("Contract is not paused for {scope}")]
+    NotPaused { scope: PauseScope },
+
+    #[error("Guardian cannot be the owner of the contract")]
+    GuardianIsOwner {},
+
+    #[error("Cannot migrate from contract {name}")]
+    InvalidContractName { name: String },
+
+    #[error("Invalid contract version {version}")]
+    InvalidVersion { version: String },
+
+    #[error("Cannot migrate from version {from} to the older version {to}")]
+    MigrationDowngrade { from: String, to: String },
 }
@@ -1,10 +1,11 @@
 use astroport::asset::{Asset, AssetInfo};
+use astroport_dca::dca::PauseScope;
 use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
 
 use crate::{
     error::ContractError,
     get_token_allowance::get_token_allowance,
-    state::{CONFIG, USER_CONFIG},
+    state::{assert_not_paused, CONFIG, USER_CONFIG},
 };
 
 /// ## Description
@@ -26,6 +27,8 @@ pub fn add_bot_tip(
     info: MessageInfo,
     assets: Vec<Asset>,
 ) -> Result<Response, ContractError> {
+    assert_not_paused(deps.storage, PauseScope::TipDeposits {})?;
+
     let mut user_config = USER_CONFIG
         .may_load(deps.storage, &info.sender)?
         .unwrap_or_default();
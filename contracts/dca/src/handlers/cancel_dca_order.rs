@@ -1,13 +1,14 @@
 use astroport::asset::AssetInfo;
 use astroport_dca::dca::DcaInfo;
-use cosmwasm_std::{attr, BankMsg, Coin, DepsMut, MessageInfo, Response};
+use cosmwasm_std::{attr, BankMsg, Coin, CosmosMsg, DepsMut, MessageInfo, Response};
 
 use crate::{error::ContractError, state::USER_DCA};
 
 /// ## Description
 /// Cancels a users DCA purchase so that it will no longer be fulfilled.
 ///
-/// Returns the `initial_asset` back to the user if it was a native token.
+/// Returns the `initial_asset` back to the user if it was a native token, along with the deposit
+/// paid for the order.
 ///
 /// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
 /// attributes if the operation was successful.
@@ -22,7 +23,8 @@ pub fn cancel_dca_order(
     info: MessageInfo,
     id: u64,
 ) -> Result<Response, ContractError> {
-    let mut funds = Vec::new();
+    let mut funds: Vec<CosmosMsg> = Vec::new();
+    let mut deposit = None;
 
     // remove order from user dca's, and add any native token funds for `initial_asset` into the `funds`.
     USER_DCA.update(
@@ -38,21 +40,29 @@ pub fn cancel_dca_order(
 
             let removed_order = &orders[order_position];
             if let AssetInfo::NativeToken { denom } = &removed_order.initial_asset.info {
-                funds.push(BankMsg::Send {
-                    to_address: info.sender.to_string(),
-                    amount: vec![Coin {
-                        amount: removed_order.initial_asset.amount,
-                        denom: denom.clone(),
-                    }],
-                })
+                funds.push(
+                    BankMsg::Send {
+                        to_address: info.sender.to_string(),
+                        amount: vec![Coin {
+                            amount: removed_order.initial_asset.amount,
+                            denom: denom.clone(),
+                        }],
+                    }
+                    .into(),
+                )
             }
 
-            orders.remove(order_position);
+            deposit = orders.remove(order_position).deposit;
 
             Ok(orders)
         },
     )?;
 
+    // refund the deposit paid for the order
+    if let Some(deposit) = deposit {
+        funds.push(deposit.into_msg(&deps.querier, info.sender.clone())?);
+    }
+
     Ok(Response::new().add_messages(funds).add_attributes(vec![
         attr("action", "cancel_dca_order"),
         attr("id", id.to_string()),
@@ -64,19 +74,19 @@ mod tests {
     use astroport::asset::{Asset, AssetInfo};
     use astroport_dca::dca::ExecuteMsg;
     use cosmwasm_std::{
-        attr, coins,
-        testing::{mock_dependencies, mock_env, mock_info},
-        Addr, BankMsg, DepsMut, MessageInfo, Response, Uint128,
+        attr, coin, coins,
+        testing::{mock_env, mock_info},
+        Addr, BankMsg, DepsMut, MessageInfo, Response, StdError, Uint128,
     };
     use cw_multi_test::Executor;
 
     use crate::{
         contract::execute,
         error::ContractError,
-        state::USER_DCA,
+        state::{CONFIG, USER_DCA},
         tests::{
-            app_mock_instantiate, mock_app, mock_creator, read_map, store_cw20_token_code,
-            store_dca_module_code,
+            app_mock_instantiate, mock_app, mock_creator, mock_instantiate, read_map,
+            store_cw20_token_code, store_dca_module_code,
         },
     };
 
@@ -102,6 +112,12 @@ mod tests {
                 first_purchase,
                 interval: 60,
                 dca_amount: Uint128::new(500_000),
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -109,7 +125,12 @@ mod tests {
 
     #[test]
     fn does_cancel_order_native() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let token = AssetInfo::NativeToken {
             denom: "uluna".to_string(),
@@ -152,6 +173,69 @@ mod tests {
         assert!(orders.is_empty())
     }
 
+    #[test]
+    fn does_refund_deposit() {
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let deposit = Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            },
+            amount: Uint128::new(5_000),
+        };
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.order_deposit = Some(deposit.clone());
+                Ok(config)
+            })
+            .unwrap();
+
+        create_order(
+            deps.as_mut(),
+            mock_info("creator", &[coin(1_000_000, "uluna"), coin(5_000, "uusd")]),
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            None,
+        );
+
+        let orders = USER_DCA
+            .load(&deps.storage, &mock_creator().sender)
+            .unwrap();
+        assert_eq!(orders[0].deposit, Some(deposit));
+
+        // the deposit is refunded along with the initial asset
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            ExecuteMsg::CancelDcaOrder { id: 1 },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new()
+                .add_attributes(vec![attr("action", "cancel_dca_order"), attr("id", "1")])
+                .add_message(BankMsg::Send {
+                    to_address: mock_creator().sender.into_string(),
+                    amount: coins(1_000_000, "uluna")
+                })
+                .add_message(BankMsg::Send {
+                    to_address: mock_creator().sender.into_string(),
+                    amount: coins(5_000, "uusd")
+                })
+        );
+    }
+
     #[test]
     fn does_cancel_order_token() {
         let mut app = mock_app();
@@ -220,6 +304,12 @@ mod tests {
                 interval: 500,
                 dca_amount: Uint128::new(500_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
             &[],
         )
@@ -241,7 +331,12 @@ mod tests {
 
     #[test]
     fn does_error_on_invalid_id() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         // errors if user has never made a order before
         let res = execute(
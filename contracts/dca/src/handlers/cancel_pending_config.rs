@@ -0,0 +1,119 @@
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, PENDING_CONFIG},
+};
+
+/// ## Description
+/// Removes the configuration update queued with `UpdateConfig`.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to cancel the update.
+pub fn cancel_pending_config(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    CONFIG.load(deps.storage)?.assert_owner(&info.sender)?;
+
+    if PENDING_CONFIG.may_load(deps.storage)?.is_none() {
+        return Err(ContractError::NonexistentPendingConfig {});
+    }
+
+    PENDING_CONFIG.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![attr("action", "cancel_pending_config")]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{testing::mock_info, Addr, Decimal};
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        state::{CONFIG, PENDING_CONFIG},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_cancel_pending_config() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+
+        // there is no update to cancel yet
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::CancelPendingConfig {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonexistentPendingConfig {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::UpdateConfig {
+                max_hops: None,
+                max_spread: Some(Decimal::percent(10)),
+                protocol_fee: None,
+                fee_collector: None,
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: None,
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
+            },
+        )
+        .unwrap();
+
+        // only the owner can cancel the update
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::CancelPendingConfig {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::CancelPendingConfig {},
+        )
+        .unwrap();
+        assert_eq!(PENDING_CONFIG.may_load(&deps.storage).unwrap(), None);
+
+        // the cancelled update can no longer be executed
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ExecutePendingConfig {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonexistentPendingConfig {});
+        assert_eq!(CONFIG.load(&deps.storage).unwrap(), config);
+    }
+}
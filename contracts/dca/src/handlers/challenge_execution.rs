@@ -0,0 +1,294 @@
+use astroport::asset::Asset;
+use cosmwasm_std::{attr, Decimal, DepsMut, Env, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, KEEPERS, KEEPER_EXECUTIONS},
+    twap::twap_return,
+};
+
+/// ## Description
+/// Challenges a DCA purchase performed by a registered keeper, comparing the amount it returned
+/// against the amount its reference route would have returned at the TWAP of each pair over the
+/// window before the purchase, where the reference route is the canonical route of its assets or
+/// the best Astroport route between them.
+///
+/// If the purchase returned less than the TWAP reference by more than the slash threshold, the
+/// bond of the keeper is slashed in favor of the user the purchase was performed for, in
+/// proportion to the shortfall of the purchase against the reference.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `id` - A [`u64`] representing the ID of the keeper execution being challenged.
+pub fn challenge_execution(deps: DepsMut, env: Env, id: u64) -> Result<Response, ContractError> {
+    let keeper_config = CONFIG
+        .load(deps.storage)?
+        .keeper_config
+        .ok_or(ContractError::KeeperModeNotConfigured {})?;
+
+    let execution = KEEPER_EXECUTIONS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::NonexistentExecution {})?;
+
+    if env.block.time.seconds()
+        > execution
+            .executed_at
+            .saturating_add(keeper_config.challenge_period)
+    {
+        return Err(ContractError::ChallengePeriodOver {});
+    }
+
+    let snapshots = execution
+        .snapshots
+        .as_ref()
+        .ok_or(ContractError::UnverifiableExecution {})?;
+
+    // the TWAP window is anchored before the purchase, so it cannot be chosen by the challenger
+    let reference = twap_return(
+        execution.offer_asset.amount,
+        snapshots,
+        execution.executed_at,
+    )?;
+    if reference.is_zero() {
+        return Err(ContractError::UnverifiableExecution {});
+    }
+
+    // check that the purchase was far enough below the reference to be slashed
+    let min_return = reference * (Decimal::one() - keeper_config.slash_threshold);
+    if execution.return_amount >= min_return {
+        return Err(ContractError::ExecutionWithinThreshold {
+            returned: execution.return_amount,
+            reference,
+        });
+    }
+
+    let mut keeper = KEEPERS
+        .may_load(deps.storage, &execution.keeper)?
+        .ok_or(ContractError::KeeperNotRegistered {})?;
+
+    // slash the bond of the keeper in proportion to the shortfall in favor of the user
+    let slashed = Asset {
+        info: keeper.bond.info.clone(),
+        amount: keeper
+            .bond
+            .amount
+            .multiply_ratio(reference - execution.return_amount, reference),
+    };
+    keeper.bond.amount -= slashed.amount;
+
+    match keeper.bond.amount.is_zero() {
+        true => KEEPERS.remove(deps.storage, &execution.keeper),
+        false => KEEPERS.save(deps.storage, &execution.keeper, &keeper)?,
+    }
+    KEEPER_EXECUTIONS.remove(deps.storage, id);
+
+    let mut response = Response::new();
+    if !slashed.amount.is_zero() {
+        response = response.add_message(
+            slashed
+                .clone()
+                .into_msg(&deps.querier, execution.user.clone())?,
+        );
+    }
+
+    Ok(response.add_attributes(vec![
+        attr("action", "challenge_execution"),
+        attr("id", id.to_string()),
+        attr("keeper", execution.keeper),
+        attr("user", execution.user),
+        attr("return_amount", execution.return_amount),
+        attr("reference", reference),
+        attr("slashed", slashed.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        router::SwapOperation,
+    };
+    use astroport_dca::dca::{ExecuteMsg, KeeperConfig};
+    use cosmwasm_std::{
+        attr, coins, testing::mock_info, Addr, BankMsg, Decimal, StdError, Uint128,
+    };
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        state::{
+            HopSnapshot, Keeper, KeeperExecution, PriceObservation, CONFIG, KEEPERS,
+            KEEPER_EXECUTIONS,
+        },
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_slash_bad_execution() {
+        let (mut deps, mut env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let bond = Asset {
+            amount: Uint128::new(1_000_000),
+            info: uluna.clone(),
+        };
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.keeper_config = Some(KeeperConfig {
+                    enabled: true,
+                    bond: bond.clone(),
+                    slash_threshold: Decimal::percent(10),
+                    challenge_period: 3_600,
+                });
+                Ok(config)
+            })
+            .unwrap();
+
+        KEEPERS
+            .save(
+                &mut deps.storage,
+                &Addr::unchecked("keeper"),
+                &Keeper {
+                    bond,
+                    unbonding_since: None,
+                },
+            )
+            .unwrap();
+
+        // over the 1_000 seconds before the purchase, 1 uluna was worth 2 ukrw on average, so the
+        // 10_000 uluna would have returned 20_000 ukrw at the TWAP
+        let executed_at = env.block.time.seconds();
+        let execution = |return_amount: u128, anchored: bool| KeeperExecution {
+            keeper: Addr::unchecked("keeper"),
+            user: Addr::unchecked("user"),
+            order_id: 1,
+            offer_asset: Asset {
+                amount: Uint128::new(10_000),
+                info: uluna.clone(),
+            },
+            hops: vec![SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: ukrw.clone(),
+            }],
+            return_amount: Uint128::new(return_amount),
+            executed_at,
+            snapshots: Some(vec![HopSnapshot {
+                pair_addr: Addr::unchecked("pair"),
+                price_cumulative: Uint128::new(2_000_000_000),
+                anchor: PriceObservation {
+                    price_cumulative: Uint128::zero(),
+                    observed_at: executed_at - 1_000,
+                },
+            }])
+            .filter(|_| anchored),
+        };
+
+        // a purchase which returned the TWAP reference
+        KEEPER_EXECUTIONS
+            .save(&mut deps.storage, 1, &execution(20_000, true))
+            .unwrap();
+        // a purchase which returned 5% less than the TWAP reference
+        KEEPER_EXECUTIONS
+            .save(&mut deps.storage, 2, &execution(19_000, true))
+            .unwrap();
+        // a purchase which returned 25% less than the TWAP reference
+        KEEPER_EXECUTIONS
+            .save(&mut deps.storage, 3, &execution(15_000, true))
+            .unwrap();
+        // a purchase whose reference route could not be anchored
+        KEEPER_EXECUTIONS
+            .save(&mut deps.storage, 4, &execution(15_000, false))
+            .unwrap();
+
+        // the return of each purchase is compared against the TWAP reference as is
+        for id in [1, 2] {
+            let res = execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("challenger", &[]),
+                ExecuteMsg::ChallengeExecution { id },
+            )
+            .unwrap_err();
+            assert_eq!(
+                res,
+                ContractError::ExecutionWithinThreshold {
+                    returned: KEEPER_EXECUTIONS
+                        .load(&deps.storage, id)
+                        .unwrap()
+                        .return_amount,
+                    reference: Uint128::new(20_000)
+                }
+            );
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("challenger", &[]),
+            ExecuteMsg::ChallengeExecution { id: 4 },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::UnverifiableExecution {});
+
+        // 15_000 ukrw is 25% short of the 20_000 ukrw reference, which slashes 25% of the bond
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("challenger", &[]),
+            ExecuteMsg::ChallengeExecution { id: 3 },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: coins(250_000, "uluna"),
+            }
+            .into()
+        );
+        assert_eq!(res.attributes[5], attr("reference", "20000"));
+        assert_eq!(res.attributes[6], attr("slashed", "250000uluna"));
+        assert_eq!(
+            KEEPERS
+                .load(&deps.storage, &Addr::unchecked("keeper"))
+                .unwrap()
+                .bond
+                .amount,
+            Uint128::new(750_000)
+        );
+        assert!(!KEEPER_EXECUTIONS.has(&deps.storage, 3));
+
+        // the challenge period of the other executions has passed
+        env.block.time = env.block.time.plus_seconds(3_601);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("challenger", &[]),
+            ExecuteMsg::ChallengeExecution { id: 1 },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::ChallengePeriodOver {});
+    }
+}
@@ -0,0 +1,161 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, KEEPERS},
+};
+
+/// ## Description
+/// Returns the bond of a keeper that has unbonded once the challenge period has passed, removing
+/// their registration.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the keeper who wants to claim their bond.
+pub fn claim_keeper_bond(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let keeper = KEEPERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::KeeperNotRegistered {})?;
+
+    let unbonding_since = keeper
+        .unbonding_since
+        .ok_or(ContractError::KeeperNotUnbonding {})?;
+
+    // executions can be challenged until the challenge period has passed
+    let challenge_period = CONFIG
+        .load(deps.storage)?
+        .keeper_config
+        .map(|c| c.challenge_period)
+        .unwrap_or_default();
+    let claimable_at = unbonding_since.saturating_add(challenge_period);
+    if env.block.time.seconds() < claimable_at {
+        return Err(ContractError::KeeperBondLocked { claimable_at });
+    }
+
+    KEEPERS.remove(deps.storage, &info.sender);
+
+    Ok(Response::new()
+        .add_message(
+            keeper
+                .bond
+                .clone()
+                .into_msg(&deps.querier, info.sender.clone())?,
+        )
+        .add_attributes(vec![
+            attr("action", "claim_keeper_bond"),
+            attr("keeper", info.sender),
+            attr("bond", keeper.bond.to_string()),
+        ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, KeeperConfig};
+    use cosmwasm_std::{coins, testing::mock_info, Addr, BankMsg, Decimal, StdError, Uint128};
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        state::{CONFIG, KEEPERS},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_claim_after_challenge_period() {
+        let (mut deps, mut env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.keeper_config = Some(KeeperConfig {
+                    enabled: true,
+                    bond: Asset {
+                        amount: Uint128::new(1_000_000),
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    },
+                    slash_threshold: Decimal::percent(10),
+                    challenge_period: 3_600,
+                });
+                Ok(config)
+            })
+            .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &coins(1_000_000, "uluna")),
+            ExecuteMsg::RegisterKeeper {},
+        )
+        .unwrap();
+
+        // cannot claim without unbonding
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::ClaimKeeperBond {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::KeeperNotUnbonding {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::UnbondKeeper {},
+        )
+        .unwrap();
+
+        // cannot claim before the challenge period has passed
+        let claimable_at = env.block.time.seconds() + 3_600;
+        env.block.time = env.block.time.plus_seconds(3_599);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::ClaimKeeperBond {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::KeeperBondLocked { claimable_at });
+
+        env.block.time = env.block.time.plus_seconds(1);
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("keeper", &[]),
+            ExecuteMsg::ClaimKeeperBond {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "keeper".to_string(),
+                amount: coins(1_000_000, "uluna"),
+            }
+            .into()
+        );
+        assert!(!KEEPERS.has(&deps.storage, &Addr::unchecked("keeper")));
+    }
+}
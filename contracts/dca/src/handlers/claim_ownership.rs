@@ -0,0 +1,48 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, OWNERSHIP_PROPOSAL},
+};
+
+/// ## Description
+/// Claims the ownership of the contract as the owner proposed with `ProposeNewOwner`, if the
+/// proposal has not expired.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the proposed new owner.
+pub fn claim_ownership(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let proposal = OWNERSHIP_PROPOSAL
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NonexistentOwnershipProposal {})?;
+
+    if info.sender != proposal.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+
+    if env.block.time.seconds() > proposal.ttl {
+        return Err(ContractError::OwnershipProposalExpired {});
+    }
+
+    OWNERSHIP_PROPOSAL.remove(deps.storage);
+    CONFIG.update::<_, ContractError>(deps.storage, |mut config| {
+        config.owner = proposal.owner.clone();
+        config.assert_valid_guardian()?;
+        Ok(config)
+    })?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "claim_ownership"),
+        attr("new_owner", proposal.owner),
+    ]))
+}
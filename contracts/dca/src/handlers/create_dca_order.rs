@@ -1,13 +1,15 @@
-use astroport::asset::{Asset, AssetInfo};
-use astroport_dca::dca::DcaInfo;
+use astroport::asset::{addr_validate_to_lower, Asset, AssetInfo};
+use astroport_dca::dca::{DcaInfo, PauseScope, Referral};
 use cosmwasm_std::{
-    attr, DepsMut, Env, MessageInfo, OverflowError, OverflowOperation, Response, StdError, Uint128,
+    attr, to_binary, Decimal, DepsMut, Env, MessageInfo, OverflowError, OverflowOperation,
+    Response, StdError, Uint128, WasmMsg,
 };
+use cw20::Cw20ExecuteMsg;
 
 use crate::{
     error::ContractError,
     get_token_allowance::get_token_allowance,
-    state::{USER_CONFIG, USER_DCA},
+    state::{assert_canonical_route_exists, assert_not_paused, CONFIG, USER_CONFIG, USER_DCA},
 };
 
 pub struct CreateDcaOrder {
@@ -16,12 +18,21 @@ pub struct CreateDcaOrder {
     pub interval: u64,
     pub dca_amount: Uint128,
     pub first_purchase: Option<u64>,
+    pub referrer: Option<String>,
+    pub referral_fee_bps: Option<u16>,
+    pub preferred_executor: Option<String>,
+    pub exclusivity_seconds: Option<u64>,
+    pub require_canonical_route: Option<bool>,
+    pub max_price_impact: Option<Decimal>,
 }
 
 /// ## Description
 /// Creates a new DCA order for a user where the `target_asset` will be purchased with `dca_amount`
 /// of token `initial_asset` every `interval`.
 ///
+/// If the contract configuration sets an order deposit, it is paid by the user alongside the
+/// `initial_asset` and refunded once the order is cancelled or completed.
+///
 /// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
 /// attributes if the operation was successful.
 /// ## Arguments
@@ -45,6 +56,24 @@ pub struct CreateDcaOrder {
 ///
 /// * `first_purchase` - A [`Option<u64>`] representing the first time the users DCA order should be
 /// processed if specified, otherwise as soon as the order is made it can be processed.
+///
+/// * `referrer` - An optional address of the referrer of the order as a [`String`], who will be
+/// paid `referral_fee_bps` of each DCA purchase.
+///
+/// * `referral_fee_bps` - An optional [`u16`] representing the share of each DCA purchase in basis
+/// points that is paid to the `referrer`, which cannot exceed the contract maximum.
+///
+/// * `preferred_executor` - An optional address as a [`String`] of the executor who has the
+/// exclusive right to perform each DCA purchase for `exclusivity_seconds` after it becomes due.
+///
+/// * `exclusivity_seconds` - An optional [`u64`] representing the exclusivity period of the
+/// `preferred_executor` in seconds, which cannot exceed `interval`.
+///
+/// * `require_canonical_route` - An optional [`bool`] representing whether DCA purchases can only
+/// be performed with the canonical route registered for the assets of the order.
+///
+/// * `max_price_impact` - An optional [`Decimal`] representing the maximum price impact of each DCA
+/// purchase, which overrides the maximum price impact of the user.
 pub fn create_dca_order(
     deps: DepsMut,
     env: Env,
@@ -57,8 +86,16 @@ pub fn create_dca_order(
         interval,
         dca_amount,
         first_purchase,
+        referrer,
+        referral_fee_bps,
+        preferred_executor,
+        exclusivity_seconds,
+        require_canonical_route,
+        max_price_impact,
     } = order_info;
 
+    assert_not_paused(deps.storage, PauseScope::OrderCreation {})?;
+
     // check that user has not previously created dca strategy with this initial_asset
     let mut orders = USER_DCA
         .may_load(deps.storage, &info.sender)?
@@ -69,6 +106,22 @@ pub fn create_dca_order(
         return Err(ContractError::DuplicateAsset {});
     }
 
+    // check that the assets are allowed and that dca_amount is at least the minimum
+    let config = CONFIG.load(deps.storage)?;
+    config.assert_allowed_order(&initial_asset.info, &target_asset, dca_amount)?;
+
+    // check that orders requiring the canonical route can be purchased with one
+    if require_canonical_route.unwrap_or_default() {
+        assert_canonical_route_exists(deps.storage, &initial_asset.info, &target_asset)?;
+    }
+
+    // check that the user has not reached the maximum amount of orders
+    if orders.len() >= config.max_orders_per_user as usize {
+        return Err(ContractError::TooManyOrders {
+            max: config.max_orders_per_user,
+        });
+    }
+
     // check that dca_amount is less than initial_asset.amount
     if dca_amount > initial_asset.amount {
         return Err(ContractError::DepositTooSmall {});
@@ -83,11 +136,54 @@ pub fn create_dca_order(
         return Err(ContractError::IndivisibleDeposit {});
     }
 
+    // validate the referrer and that the referral fee is within the contract maximum
+    let referral = match referrer {
+        Some(referrer) => {
+            let fee_bps = referral_fee_bps.unwrap_or_default();
+            if fee_bps > config.max_referral_fee_bps {
+                return Err(ContractError::ReferralFeeTooLarge {
+                    max: config.max_referral_fee_bps,
+                });
+            }
+
+            Some(Referral {
+                referrer: addr_validate_to_lower(deps.api, &referrer)?,
+                fee_bps,
+            })
+        }
+        None if referral_fee_bps.is_some() => return Err(ContractError::MissingReferrer {}),
+        None => None,
+    };
+
+    // validate the preferred executor and that their exclusivity period fits within the interval
+    let preferred_executor = preferred_executor
+        .map(|executor| addr_validate_to_lower(deps.api, &executor))
+        .transpose()?;
+    let exclusivity_seconds = match (&preferred_executor, exclusivity_seconds) {
+        (None, Some(_)) => return Err(ContractError::MissingPreferredExecutor {}),
+        (_, Some(seconds)) if seconds > interval => {
+            return Err(ContractError::ExclusivityTooLong {})
+        }
+        (_, seconds) => seconds.unwrap_or_default(),
+    };
+
+    // the refundable deposit paid for the order, where a deposit in the initial asset has to be
+    // sent or approved together with it
+    let deposit = config.order_deposit;
+    let initial_asset_deposit = deposit
+        .as_ref()
+        .filter(|deposit| deposit.info == initial_asset.info)
+        .map_or(Uint128::zero(), |deposit| deposit.amount);
+
     // check that user has sent the valid tokens to the contract
     // if native token, they should have included it in the message
     // otherwise, if cw20 token, they should have provided the correct allowance
     match &initial_asset.info {
-        AssetInfo::NativeToken { .. } => initial_asset.assert_sent_native_token_balance(&info)?,
+        AssetInfo::NativeToken { .. } => Asset {
+            info: initial_asset.info.clone(),
+            amount: initial_asset.amount + initial_asset_deposit,
+        }
+        .assert_sent_native_token_balance(&info)?,
         AssetInfo::Token { contract_addr } => {
             // allowance should be greater than the sum of all orders with this initial asset
             let total_allowance: Uint128 = orders
@@ -101,12 +197,30 @@ pub fn create_dca_order(
                 .sum();
 
             let allowance = get_token_allowance(&deps.as_ref(), &env, &info.sender, contract_addr)?;
-            if total_allowance + initial_asset.amount > allowance {
+            if total_allowance + initial_asset.amount + initial_asset_deposit > allowance {
                 return Err(ContractError::InvalidTokenDeposit {});
             }
         }
     }
 
+    // check that the user has sent the deposit, or transfer it if it is a cw20 token
+    let mut messages = vec![];
+    if let Some(deposit) = &deposit {
+        match &deposit.info {
+            AssetInfo::NativeToken { .. } if deposit.info == initial_asset.info => {}
+            AssetInfo::NativeToken { .. } => deposit.assert_sent_native_token_balance(&info)?,
+            AssetInfo::Token { contract_addr } => messages.push(WasmMsg::Execute {
+                contract_addr: contract_addr.to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                    owner: info.sender.to_string(),
+                    recipient: env.contract.address.to_string(),
+                    amount: deposit.amount,
+                })?,
+            }),
+        }
+    }
+
     let id = USER_CONFIG
         .update::<_, StdError>(deps.storage, &info.sender, |config| {
             let mut config = config.unwrap_or_default();
@@ -128,26 +242,39 @@ pub fn create_dca_order(
         interval,
         last_purchase: first_purchase.unwrap_or_default(),
         dca_amount,
+        referral,
+        preferred_executor,
+        exclusivity_seconds,
+        require_canonical_route: require_canonical_route.unwrap_or_default(),
+        max_price_impact,
+        deposit: deposit.clone(),
     });
 
     USER_DCA.save(deps.storage, &info.sender, &orders)?;
 
-    Ok(Response::new().add_attributes(vec![
+    let mut attributes = vec![
         attr("action", "create_dca_order"),
         attr("initial_asset", initial_asset.to_string()),
         attr("target_asset", target_asset.to_string()),
         attr("interval", interval.to_string()),
         attr("dca_amount", dca_amount),
-    ]))
+    ];
+    if let Some(deposit) = deposit {
+        attributes.push(attr("deposit", deposit.to_string()));
+    }
+
+    Ok(Response::new()
+        .add_messages(messages)
+        .add_attributes(attributes))
 }
 
 #[cfg(test)]
 mod tests {
     use astroport::asset::{Asset, AssetInfo};
-    use astroport_dca::dca::{DcaInfo, ExecuteMsg};
+    use astroport_dca::dca::{AllowedAssets, DcaInfo, ExecuteMsg, Referral};
     use cosmwasm_std::{
         attr, coins,
-        testing::{mock_dependencies, mock_env, mock_info},
+        testing::{mock_env, mock_info},
         Addr, DivideByZeroError, Response, StdError, Uint128,
     };
     use cw_multi_test::{App, AppResponse, Executor};
@@ -155,16 +282,21 @@ mod tests {
     use crate::{
         contract::execute,
         error::ContractError,
-        state::USER_DCA,
+        state::{CONFIG, USER_DCA},
         tests::{
-            app_mock_instantiate, mock_app, mock_creator, store_cw20_token_code,
+            app_mock_instantiate, mock_app, mock_creator, mock_instantiate, store_cw20_token_code,
             store_dca_module_code,
         },
     };
 
     #[test]
     fn does_create_native() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(100_000),
@@ -186,6 +318,12 @@ mod tests {
                 interval: 1_000,
                 dca_amount: Uint128::new(25_000),
                 first_purchase: Some(18_000),
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -214,7 +352,13 @@ mod tests {
                 initial_asset,
                 target_asset,
                 interval: 1_000,
-                last_purchase: 18_000
+                last_purchase: 18_000,
+                referral: None,
+                preferred_executor: None,
+                exclusivity_seconds: 0,
+                require_canonical_route: false,
+                max_price_impact: None,
+                deposit: None,
             }]
         );
     }
@@ -289,6 +433,12 @@ mod tests {
                 interval: 1000,
                 dca_amount: Uint128::new(25_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
             &[],
         )
@@ -297,7 +447,12 @@ mod tests {
 
     #[test]
     fn cannot_create_duplicate_asset() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let asset = Asset {
             amount: Uint128::new(25_000),
@@ -316,6 +471,12 @@ mod tests {
                 interval: 1_000,
                 dca_amount: Uint128::new(5_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap_err();
@@ -325,7 +486,12 @@ mod tests {
 
     #[test]
     fn cannot_create_greater_dca_order() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(100_000),
@@ -347,6 +513,12 @@ mod tests {
                 interval: 1_000,
                 dca_amount: initial_asset.amount * Uint128::new(2),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap_err();
@@ -356,7 +528,12 @@ mod tests {
 
     #[test]
     fn cannot_create_indivisible_order() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(100_000),
@@ -378,6 +555,12 @@ mod tests {
                 interval: 1_000,
                 dca_amount: Uint128::new(999),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap_err();
@@ -394,6 +577,12 @@ mod tests {
                 interval: 1_000,
                 dca_amount: Uint128::new(0),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap_err();
@@ -407,7 +596,12 @@ mod tests {
 
     #[test]
     fn does_require_native_sent() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(100_000),
@@ -429,6 +623,12 @@ mod tests {
                 interval: 1_000,
                 dca_amount: Uint128::new(25_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap_err();
@@ -499,6 +699,12 @@ mod tests {
                     interval: 1000,
                     dca_amount: Uint128::new(25_000),
                     first_purchase: None,
+                    referrer: None,
+                    referral_fee_bps: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: None,
+                    require_canonical_route: None,
+                    max_price_impact: None,
                 },
                 &[],
             )
@@ -581,6 +787,12 @@ mod tests {
                     interval: 500,
                     dca_amount: Uint128::new(5_000),
                     first_purchase: None,
+                    referrer: None,
+                    referral_fee_bps: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: None,
+                    require_canonical_route: None,
+                    max_price_impact: None,
                 },
                 &[],
             )
@@ -604,7 +816,12 @@ mod tests {
 
     #[test]
     fn does_increment_id() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(100_000),
@@ -627,6 +844,12 @@ mod tests {
                     interval: 1_000,
                     dca_amount: Uint128::new(25_000),
                     first_purchase: None,
+                    referrer: None,
+                    referral_fee_bps: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: None,
+                    require_canonical_route: None,
+                    max_price_impact: None,
                 },
             )
             .unwrap();
@@ -648,7 +871,13 @@ mod tests {
                     initial_asset: initial_asset.clone(),
                     target_asset: target_asset.clone(),
                     interval: 1_000,
-                    last_purchase: 0
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
                 },
                 DcaInfo {
                     id: 2,
@@ -656,9 +885,350 @@ mod tests {
                     initial_asset,
                     target_asset,
                     interval: 1_000,
-                    last_purchase: 0
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
                 }
             ]
         );
     }
+
+    #[test]
+    fn does_check_referral_fee() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.max_referral_fee_bps = 100;
+                Ok(config)
+            })
+            .unwrap();
+
+        let create_order =
+            |referrer: Option<&str>, referral_fee_bps: Option<u16>| ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    amount: Uint128::new(100_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                interval: 1_000,
+                dca_amount: Uint128::new(25_000),
+                first_purchase: None,
+                referrer: referrer.map(|r| r.to_string()),
+                referral_fee_bps,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
+            };
+
+        // referral fee cannot exceed the contract maximum
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(Some("frontend"), Some(150)),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::ReferralFeeTooLarge { max: 100 });
+
+        // referral fee cannot be set without a referrer
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(None, Some(50)),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::MissingReferrer {});
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(Some("frontend"), Some(50)),
+        )
+        .unwrap();
+
+        let orders = USER_DCA
+            .load(&deps.storage, &mock_creator().sender)
+            .unwrap();
+        assert_eq!(
+            orders[0].referral,
+            Some(Referral {
+                referrer: Addr::unchecked("frontend"),
+                fee_bps: 50
+            })
+        );
+    }
+
+    #[test]
+    fn does_check_exclusivity() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let create_order = |preferred_executor: Option<&str>, exclusivity_seconds: Option<u64>| {
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    amount: Uint128::new(100_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                },
+                target_asset: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+                interval: 1_000,
+                dca_amount: Uint128::new(25_000),
+                first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: preferred_executor.map(|e| e.to_string()),
+                exclusivity_seconds,
+                require_canonical_route: None,
+                max_price_impact: None,
+            }
+        };
+
+        // exclusivity cannot be set without a preferred executor
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(None, Some(100)),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::MissingPreferredExecutor {});
+
+        // exclusivity cannot be longer than the interval
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(Some("user_bot"), Some(1_001)),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::ExclusivityTooLong {});
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(Some("user_bot"), Some(100)),
+        )
+        .unwrap();
+
+        let orders = USER_DCA
+            .load(&deps.storage, &mock_creator().sender)
+            .unwrap();
+        assert_eq!(
+            orders[0].preferred_executor,
+            Some(Addr::unchecked("user_bot"))
+        );
+        assert_eq!(orders[0].exclusivity_seconds, 100);
+    }
+
+    #[test]
+    fn does_check_allowed_assets() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let ujpy = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.allowed_initial_assets = AllowedAssets::Only {
+                    assets: vec![uluna.clone()],
+                };
+                config.allowed_target_assets = AllowedAssets::Only {
+                    assets: vec![ukrw.clone()],
+                };
+                config.min_dca_amounts = vec![Asset {
+                    info: uluna.clone(),
+                    amount: Uint128::new(10_000),
+                }];
+                Ok(config)
+            })
+            .unwrap();
+
+        let create_order = |initial_asset: &AssetInfo, target_asset: &AssetInfo, dca_amount| {
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: Asset {
+                    amount: Uint128::new(100_000),
+                    info: initial_asset.clone(),
+                },
+                target_asset: target_asset.clone(),
+                interval: 1_000,
+                dca_amount: Uint128::new(dca_amount),
+                first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
+            }
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "ujpy")),
+            create_order(&ujpy, &ukrw, 25_000),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InitialAssetNotAllowed {
+                asset: ujpy.clone()
+            }
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(&uluna, &ujpy, 25_000),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::TargetAssetNotAllowed { asset: ujpy });
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(&uluna, &ukrw, 5_000),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::DcaAmountTooSmall {
+                min: Asset {
+                    info: uluna.clone(),
+                    amount: Uint128::new(10_000),
+                }
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order(&uluna, &ukrw, 10_000),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn does_limit_orders_and_take_deposit() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let deposit = Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::new(1_000),
+        };
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.max_orders_per_user = 1;
+                config.order_deposit = Some(deposit.clone());
+                Ok(config)
+            })
+            .unwrap();
+
+        let create_order = ExecuteMsg::CreateDcaOrder {
+            initial_asset: Asset {
+                amount: Uint128::new(100_000),
+                info: deposit.info.clone(),
+            },
+            target_asset: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            interval: 1_000,
+            dca_amount: Uint128::new(25_000),
+            first_purchase: None,
+            referrer: None,
+            referral_fee_bps: None,
+            preferred_executor: None,
+            exclusivity_seconds: None,
+            require_canonical_route: None,
+            max_price_impact: None,
+        };
+
+        // the deposit in the initial asset has to be sent together with it
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(100_000, "uluna")),
+            create_order.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::Std(StdError::generic_err(
+                "Native token balance mismatch between the argument and the transferred"
+            ))
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("creator", &coins(101_000, "uluna")),
+            create_order.clone(),
+        )
+        .unwrap();
+        assert!(res
+            .attributes
+            .contains(&attr("deposit", deposit.to_string())));
+
+        let orders = USER_DCA
+            .load(&deps.storage, &mock_creator().sender)
+            .unwrap();
+        assert_eq!(orders[0].deposit, Some(deposit));
+
+        // the user cannot exceed the maximum amount of orders
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("creator", &coins(101_000, "uluna")),
+            create_order,
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::TooManyOrders { max: 1 });
+    }
 }
@@ -0,0 +1,77 @@
+use cosmwasm_std::{DepsMut, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, OWNERSHIP_PROPOSAL},
+};
+
+/// ## Description
+/// Removes the pending proposal to transfer the ownership of the contract.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to remove the proposal.
+pub fn drop_ownership_proposal(
+    deps: DepsMut,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    CONFIG.load(deps.storage)?.assert_owner(&info.sender)?;
+
+    OWNERSHIP_PROPOSAL.remove(deps.storage);
+
+    Ok(Response::new().add_attribute("action", "drop_ownership_proposal"))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{testing::mock_info, Addr};
+
+    use crate::{
+        contract::execute, error::ContractError, state::OWNERSHIP_PROPOSAL, tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_drop_proposal() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ProposeNewOwner {
+                owner: "new_owner".to_string(),
+                expires_in: 100,
+            },
+        )
+        .unwrap();
+
+        // only the owner can drop the proposal
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("new_owner", &[]),
+            ExecuteMsg::DropOwnershipProposal {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::DropOwnershipProposal {},
+        )
+        .unwrap();
+
+        assert_eq!(OWNERSHIP_PROPOSAL.may_load(&deps.storage).unwrap(), None);
+    }
+}
@@ -0,0 +1,248 @@
+use astroport::asset::{Asset, AssetInfo};
+use cosmwasm_std::{
+    attr, to_binary, BankMsg, Coin, CosmosMsg, DepsMut, MessageInfo, Response, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use crate::{
+    error::ContractError,
+    state::{USER_CONFIG, USER_DCA},
+};
+
+/// ## Description
+/// Cancels all DCA orders of a user and returns every asset the contract holds for them, which
+/// are the native `initial_asset` of each order, the deposits paid for the orders and the native
+/// tip balance of the user. The cw20 tip balance is cleared without a transfer, as cw20 tips are
+/// only spent from the allowance of the user and never held by the contract.
+///
+/// As an emergency exit this only depends on the orders and the configuration of the user, so
+/// that it can be performed while the contract is paused or its configuration is unusable. The
+/// assets are returned without querying any other contract.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the sender who wants to withdraw all of their assets.
+pub fn emergency_withdraw_all(deps: DepsMut, info: MessageInfo) -> Result<Response, ContractError> {
+    let mut refunds: Vec<Asset> = vec![];
+    let mut add_refund = |asset: Asset| {
+        match refunds.iter_mut().find(|refund| refund.info == asset.info) {
+            Some(refund) => refund.amount += asset.amount,
+            None => refunds.push(asset),
+        };
+    };
+
+    let orders = USER_DCA
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    USER_DCA.remove(deps.storage, &info.sender);
+
+    for order in &orders {
+        // cw20 initial assets are only spent from the allowance of the user, so they are not held
+        if order.initial_asset.info.is_native_token() {
+            add_refund(order.initial_asset.clone());
+        }
+
+        if let Some(deposit) = &order.deposit {
+            add_refund(deposit.clone());
+        }
+    }
+
+    // the last id is kept so that the ids of future orders are not reused
+    if let Some(mut user_config) = USER_CONFIG.may_load(deps.storage, &info.sender)? {
+        for tip in user_config.tip_balance.drain(..) {
+            // cw20 tips are only spent from the allowance of the user, so they are not held
+            if tip.info.is_native_token() {
+                add_refund(tip);
+            }
+        }
+
+        USER_CONFIG.save(deps.storage, &info.sender, &user_config)?;
+    }
+
+    let mut coins: Vec<Coin> = vec![];
+    let mut messages: Vec<CosmosMsg> = vec![];
+    for refund in refunds
+        .into_iter()
+        .filter(|refund| !refund.amount.is_zero())
+    {
+        match refund.info {
+            AssetInfo::NativeToken { denom } => coins.push(Coin {
+                denom,
+                amount: refund.amount,
+            }),
+            AssetInfo::Token { contract_addr } => messages.push(
+                WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: info.sender.to_string(),
+                        amount: refund.amount,
+                    })?,
+                    funds: vec![],
+                }
+                .into(),
+            ),
+        }
+    }
+
+    if !coins.is_empty() {
+        messages.insert(
+            0,
+            BankMsg::Send {
+                to_address: info.sender.to_string(),
+                amount: coins,
+            }
+            .into(),
+        );
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "emergency_withdraw_all"),
+        attr("cancelled_orders", orders.len().to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{DcaInfo, ExecuteMsg};
+    use cosmwasm_std::{
+        attr, coin,
+        testing::{mock_dependencies, mock_env},
+        to_binary, Addr, BankMsg, Response, Uint128, WasmMsg,
+    };
+    use cw20::Cw20ExecuteMsg;
+
+    use crate::{
+        contract::execute,
+        state::{UserConfig, USER_CONFIG, USER_DCA},
+        tests::mock_creator,
+    };
+
+    fn order(id: u64, initial_asset: Asset, deposit: Option<Asset>) -> DcaInfo {
+        DcaInfo {
+            id,
+            initial_asset,
+            target_asset: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+            interval: 60,
+            last_purchase: 0,
+            dca_amount: Uint128::new(100),
+            referral: None,
+            preferred_executor: None,
+            exclusivity_seconds: 0,
+            require_canonical_route: false,
+            max_price_impact: None,
+            deposit,
+        }
+    }
+
+    #[test]
+    fn does_withdraw_all_without_config() {
+        // the contract is not instantiated, so there is no configuration to depend on
+        let mut deps = mock_dependencies();
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let token = AssetInfo::Token {
+            contract_addr: Addr::unchecked("token"),
+        };
+        let asset = |info: &AssetInfo, amount: u128| Asset {
+            info: info.clone(),
+            amount: Uint128::new(amount),
+        };
+
+        let sender = mock_creator().sender;
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &sender,
+                &vec![
+                    order(1, asset(&uluna, 1_000), Some(asset(&uusd, 50))),
+                    order(2, asset(&token, 2_000), Some(asset(&token, 70))),
+                    order(3, asset(&uusd, 3_000), None),
+                ],
+            )
+            .unwrap();
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &sender,
+                &UserConfig {
+                    last_id: 3,
+                    tip_balance: vec![asset(&uusd, 200), asset(&token, 30)],
+                    ..UserConfig::default()
+                },
+            )
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            ExecuteMsg::EmergencyWithdrawAll {},
+        )
+        .unwrap();
+
+        // the cw20 initial asset and tip are not returned as they were never transferred to the
+        // contract, while the cw20 deposit was
+        assert_eq!(
+            res,
+            Response::new()
+                .add_message(BankMsg::Send {
+                    to_address: sender.to_string(),
+                    amount: vec![coin(1_000, "uluna"), coin(3_250, "uusd")],
+                })
+                .add_message(WasmMsg::Execute {
+                    contract_addr: "token".to_string(),
+                    msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                        recipient: sender.to_string(),
+                        amount: Uint128::new(70),
+                    })
+                    .unwrap(),
+                    funds: vec![],
+                })
+                .add_attributes(vec![
+                    attr("action", "emergency_withdraw_all"),
+                    attr("cancelled_orders", "3"),
+                ])
+        );
+
+        assert_eq!(USER_DCA.may_load(&deps.storage, &sender).unwrap(), None);
+        assert_eq!(
+            USER_CONFIG.load(&deps.storage, &sender).unwrap(),
+            UserConfig {
+                last_id: 3,
+                ..UserConfig::default()
+            }
+        );
+    }
+
+    #[test]
+    fn does_withdraw_nothing_for_unknown_user() {
+        let mut deps = mock_dependencies();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            ExecuteMsg::EmergencyWithdrawAll {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            res,
+            Response::new().add_attributes(vec![
+                attr("action", "emergency_withdraw_all"),
+                attr("cancelled_orders", "0"),
+            ])
+        );
+    }
+}
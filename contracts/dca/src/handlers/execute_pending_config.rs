@@ -0,0 +1,162 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, PENDING_CONFIG},
+};
+
+/// ## Description
+/// Applies the configuration update queued with `UpdateConfig` once its activation time has
+/// passed.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to execute the update.
+pub fn execute_pending_config(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.assert_owner(&info.sender)?;
+
+    let pending_config = PENDING_CONFIG
+        .may_load(deps.storage)?
+        .ok_or(ContractError::NonexistentPendingConfig {})?;
+
+    if env.block.time.seconds() < pending_config.activation_time {
+        return Err(ContractError::PendingConfigNotActive {
+            activation_time: pending_config.activation_time,
+        });
+    }
+
+    pending_config.update.apply(deps.as_ref(), &mut config)?;
+
+    CONFIG.save(deps.storage, &config)?;
+    PENDING_CONFIG.remove(deps.storage);
+
+    Ok(Response::new().add_attributes(vec![attr("action", "execute_pending_config")]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport_dca::dca::{ExecuteMsg, QueryMsg};
+    use cosmwasm_std::{from_binary, testing::mock_info, Addr, Decimal};
+
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        state::{PendingConfig, UpdateConfigParameters, CONFIG, DEFAULT_CONFIG_TIMELOCK},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_execute_pending_config() {
+        let (mut deps, mut env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::UpdateConfig {
+                max_hops: None,
+                max_spread: Some(Decimal::percent(10)),
+                protocol_fee: None,
+                fee_collector: None,
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: None,
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
+            },
+        )
+        .unwrap();
+
+        let activation_time = env.block.time.seconds() + DEFAULT_CONFIG_TIMELOCK;
+
+        // the update is queued without changing the configuration
+        let pending_config: Option<PendingConfig> =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::PendingConfig {}).unwrap())
+                .unwrap();
+        assert_eq!(
+            pending_config,
+            Some(PendingConfig {
+                update: UpdateConfigParameters {
+                    max_spread: Some(Decimal::percent(10)),
+                    ..UpdateConfigParameters::default()
+                },
+                activation_time,
+            })
+        );
+        assert_eq!(CONFIG.load(&deps.storage).unwrap(), config);
+
+        // the update cannot be executed before the timelock has passed
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ExecutePendingConfig {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::PendingConfigNotActive { activation_time }
+        );
+
+        env.block.time = env.block.time.plus_seconds(DEFAULT_CONFIG_TIMELOCK);
+
+        // only the owner can execute the update
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::ExecutePendingConfig {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ExecutePendingConfig {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().max_spread,
+            Decimal::percent(10)
+        );
+
+        // the update can only be executed once
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ExecutePendingConfig {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonexistentPendingConfig {});
+    }
+}
@@ -1,17 +1,56 @@
 mod add_bot_tip;
 mod cancel_dca_order;
+mod cancel_pending_config;
+mod challenge_execution;
+mod claim_keeper_bond;
+mod claim_ownership;
 mod create_dca_order;
+mod drop_ownership_proposal;
+mod emergency_withdraw_all;
+mod execute_pending_config;
 mod modify_dca_order;
+mod pause;
 mod perform_dca_purchase;
+mod perform_dca_purchases;
+mod propose_new_owner;
+mod register_keeper;
+mod remove_fee_asset;
+mod remove_route;
+mod remove_whitelisted_tokens;
+mod set_fee_asset;
+mod set_route;
+mod unbond_keeper;
+mod unpause;
 mod update_config;
 mod update_user_config;
 mod withdraw;
 
 pub use add_bot_tip::add_bot_tip;
 pub use cancel_dca_order::cancel_dca_order;
+pub use cancel_pending_config::cancel_pending_config;
+pub use challenge_execution::challenge_execution;
+pub use claim_keeper_bond::claim_keeper_bond;
+pub use claim_ownership::claim_ownership;
 pub use create_dca_order::{create_dca_order, CreateDcaOrder};
+pub use drop_ownership_proposal::drop_ownership_proposal;
+pub use emergency_withdraw_all::emergency_withdraw_all;
+pub use execute_pending_config::execute_pending_config;
 pub use modify_dca_order::{modify_dca_order, ModifyDcaOrderParameters};
-pub use perform_dca_purchase::perform_dca_purchase;
+pub use pause::pause;
+pub use perform_dca_purchase::{
+    keeper_swap_reply, perform_dca_purchase, prepare_purchase, PerformDcaPurchase,
+    PreparedPurchase, KEEPER_SWAP_REPLY_ID,
+};
+pub use perform_dca_purchases::{batch_swap_reply, perform_dca_purchases, BATCH_SWAP_REPLY_ID};
+pub use propose_new_owner::propose_new_owner;
+pub use register_keeper::register_keeper;
+pub use remove_fee_asset::remove_fee_asset;
+pub use remove_route::remove_route;
+pub use remove_whitelisted_tokens::remove_whitelisted_tokens;
+pub use set_fee_asset::set_fee_asset;
+pub use set_route::set_route;
+pub use unbond_keeper::unbond_keeper;
+pub use unpause::unpause;
 pub use update_config::update_config;
 pub use update_user_config::update_user_config;
 pub use withdraw::withdraw;
@@ -1,7 +1,12 @@
 use astroport::asset::{Asset, AssetInfo};
+use astroport_dca::dca::PauseScope;
 use cosmwasm_std::{attr, coins, BankMsg, DepsMut, Env, MessageInfo, Response, Uint128};
 
-use crate::{error::ContractError, get_token_allowance::get_token_allowance, state::USER_DCA};
+use crate::{
+    error::ContractError,
+    get_token_allowance::get_token_allowance,
+    state::{assert_canonical_route_exists, assert_not_paused, CONFIG, USER_DCA},
+};
 
 /// Stores a modified dca order new parameters
 pub struct ModifyDcaOrderParameters {
@@ -56,6 +61,8 @@ pub fn modify_dca_order(
         new_first_purchase,
     } = order_details;
 
+    assert_not_paused(deps.storage, PauseScope::OrderCreation {})?;
+
     let mut orders = USER_DCA
         .may_load(deps.storage, &info.sender)?
         .unwrap_or_default();
@@ -89,6 +96,21 @@ pub fn modify_dca_order(
         return Err(ContractError::DuplicateAsset {});
     }
 
+    // check that the new assets are allowed and that new_dca_amount is at least the minimum
+    CONFIG.load(deps.storage)?.assert_allowed_order(
+        &new_initial_asset.info,
+        &new_target_asset,
+        new_dca_amount,
+    )?;
+
+    // check that the canonical route still exists if the order requires it and its assets change
+    if order.require_canonical_route
+        && (order.initial_asset.info != new_initial_asset.info
+            || order.target_asset != new_target_asset)
+    {
+        assert_canonical_route_exists(deps.storage, &new_initial_asset.info, &new_target_asset)?;
+    }
+
     if order.initial_asset.info == new_initial_asset.info {
         if !should_refund {
             // if the user needs to have deposited more, check that we have the correct funds/allowance sent
@@ -172,6 +194,11 @@ pub fn modify_dca_order(
     order.interval = new_interval;
     order.dca_amount = new_dca_amount;
 
+    // the exclusivity of the preferred executor cannot outlast the new interval
+    if order.exclusivity_seconds > order.interval {
+        return Err(ContractError::ExclusivityTooLong {});
+    }
+
     if let Some(new_first_purchase) = new_first_purchase {
         order.last_purchase = new_first_purchase;
     }
@@ -197,11 +224,14 @@ pub fn modify_dca_order(
 
 #[cfg(test)]
 mod test {
-    use astroport::asset::{Asset, AssetInfo};
-    use astroport_dca::dca::{DcaInfo, ExecuteMsg};
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        router::SwapOperation,
+    };
+    use astroport_dca::dca::{AllowedAssets, DcaInfo, ExecuteMsg};
     use cosmwasm_std::{
         attr, coins,
-        testing::{mock_dependencies, mock_env, mock_info},
+        testing::{mock_env, mock_info},
         Addr, BankMsg, Response, StdError, Uint128,
     };
     use cw_multi_test::Executor;
@@ -209,16 +239,21 @@ mod test {
     use crate::{
         contract::execute,
         error::ContractError,
-        state::USER_DCA,
+        state::{route_key, CONFIG, ROUTES, USER_DCA},
         tests::{
-            app_mock_instantiate, mock_app, mock_app_with_balance, mock_creator,
+            app_mock_instantiate, mock_app, mock_app_with_balance, mock_creator, mock_instantiate,
             store_cw20_token_code, store_dca_module_code,
         },
     };
 
     #[test]
     fn does_modify_order() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(15_000),
@@ -244,6 +279,12 @@ mod test {
                 interval: 5_000,
                 dca_amount: Uint128::new(1_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -289,16 +330,128 @@ mod test {
                 initial_asset,
                 interval: 1_000,
                 last_purchase: 18_000,
-                target_asset: new_target_asset
+                target_asset: new_target_asset,
+                referral: None,
+                preferred_executor: None,
+                exclusivity_seconds: 0,
+                require_canonical_route: false,
+                max_price_impact: None,
+                deposit: None,
             }]
         );
     }
 
+    #[test]
+    fn does_require_canonical_route_of_new_assets() {
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let initial_asset = Asset {
+            amount: Uint128::new(15_000),
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        };
+        let target_asset = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let new_target_asset = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+        let route = |ask_asset_info: &AssetInfo| {
+            vec![SwapOperation::AstroSwap {
+                offer_asset_info: initial_asset.info.clone(),
+                ask_asset_info: ask_asset_info.clone(),
+            }]
+        };
+
+        let create_order = ExecuteMsg::CreateDcaOrder {
+            initial_asset: initial_asset.clone(),
+            target_asset: target_asset.clone(),
+            interval: 5_000,
+            dca_amount: Uint128::new(1_000),
+            first_purchase: None,
+            referrer: None,
+            referral_fee_bps: None,
+            preferred_executor: None,
+            exclusivity_seconds: None,
+            require_canonical_route: Some(true),
+            max_price_impact: None,
+        };
+
+        // the order cannot be created until a canonical route is registered
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &coins(initial_asset.amount.u128(), "uluna")),
+            create_order.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonexistentRoute {});
+
+        ROUTES
+            .save(
+                &mut deps.storage,
+                route_key(&initial_asset.info, &target_asset),
+                &route(&target_asset),
+            )
+            .unwrap();
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &coins(initial_asset.amount.u128(), "uluna")),
+            create_order,
+        )
+        .unwrap();
+
+        let modify_order = ExecuteMsg::ModifyDcaOrder {
+            id: 1,
+            new_initial_asset: initial_asset.clone(),
+            new_target_asset: new_target_asset.clone(),
+            new_interval: 5_000,
+            new_dca_amount: Uint128::new(1_000),
+            new_first_purchase: None,
+        };
+
+        // the target asset cannot be changed to one without a canonical route
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            modify_order.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonexistentRoute {});
+
+        ROUTES
+            .save(
+                &mut deps.storage,
+                route_key(&initial_asset.info, &new_target_asset),
+                &route(&new_target_asset),
+            )
+            .unwrap();
+        execute(deps.as_mut(), mock_env(), mock_creator(), modify_order).unwrap();
+
+        let orders = USER_DCA
+            .load(&deps.storage, &mock_creator().sender)
+            .unwrap();
+        assert_eq!(orders[0].target_asset, new_target_asset);
+    }
+
     #[test]
     fn does_refund_same_native() {
         // checks that the contract will refund the user if the order is modified where the new
         // initial asset is the same type as the old one, with a smaller amount
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(15_000),
@@ -328,6 +481,12 @@ mod test {
                 interval: 5_000,
                 dca_amount: Uint128::new(1_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -373,7 +532,12 @@ mod test {
     fn does_validate_extra_sent_native() {
         // validates that when a user increases the initial_asset.amount, that they have attached
         // the required funds to their tx
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(15_000),
@@ -403,6 +567,12 @@ mod test {
                 interval: 5_000,
                 dca_amount: Uint128::new(1_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -540,6 +710,12 @@ mod test {
                 interval: 1000,
                 dca_amount: Uint128::new(25_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
             &[],
         )
@@ -562,6 +738,12 @@ mod test {
                 interval: 1_000,
                 dca_amount: Uint128::new(1_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
             &coins(20_000, "uluna"),
         )
@@ -619,7 +801,12 @@ mod test {
 
     #[test]
     fn can_change_initial_asset_native() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(15_000),
@@ -651,6 +838,12 @@ mod test {
                 interval: 5_000,
                 dca_amount: Uint128::new(1_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -808,6 +1001,12 @@ mod test {
                 interval: 1000,
                 dca_amount: Uint128::new(25_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
             &[],
         )
@@ -866,7 +1065,12 @@ mod test {
 
     #[test]
     fn does_error_on_invalid_id() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(15_000),
@@ -889,6 +1093,12 @@ mod test {
                 interval: 5_000,
                 dca_amount: Uint128::new(1_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -912,7 +1122,12 @@ mod test {
 
     #[test]
     fn cannot_change_to_duplicate_asset() {
-        let mut deps = mock_dependencies();
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
 
         let initial_asset = Asset {
             amount: Uint128::new(15_000),
@@ -939,6 +1154,12 @@ mod test {
                 interval: 5_000,
                 dca_amount: Uint128::new(1_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
         )
         .unwrap();
@@ -959,4 +1180,179 @@ mod test {
         .unwrap_err();
         assert_eq!(res, ContractError::DuplicateAsset {});
     }
+
+    #[test]
+    fn cannot_change_to_disallowed_asset() {
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let initial_asset = Asset {
+            amount: Uint128::new(15_000),
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        };
+        let target_asset = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        // create order
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &coins(initial_asset.amount.u128(), "uluna")),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: initial_asset.clone(),
+                target_asset: target_asset.clone(),
+                interval: 5_000,
+                dca_amount: Uint128::new(1_000),
+                first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
+            },
+        )
+        .unwrap();
+
+        // only ukrw can be purchased from now on, with a minimum of 1_000 uluna each purchase
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.allowed_target_assets = AllowedAssets::Only {
+                    assets: vec![target_asset.clone()],
+                };
+                config.min_dca_amounts = vec![Asset {
+                    info: initial_asset.info.clone(),
+                    amount: Uint128::new(1_000),
+                }];
+                Ok(config)
+            })
+            .unwrap();
+
+        let modify_order =
+            |new_target_asset: &AssetInfo, new_dca_amount| ExecuteMsg::ModifyDcaOrder {
+                id: 1,
+                new_initial_asset: initial_asset.clone(),
+                new_target_asset: new_target_asset.clone(),
+                new_interval: 1_000,
+                new_dca_amount: Uint128::new(new_dca_amount),
+                new_first_purchase: None,
+            };
+
+        let new_target_asset = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            modify_order(&new_target_asset, 1_000),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::TargetAssetNotAllowed {
+                asset: new_target_asset
+            }
+        );
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            modify_order(&target_asset, 500),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::DcaAmountTooSmall {
+                min: Asset {
+                    info: initial_asset.info.clone(),
+                    amount: Uint128::new(1_000),
+                }
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            modify_order(&target_asset, 3_000),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cannot_shrink_interval_below_exclusivity() {
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let initial_asset = Asset {
+            amount: Uint128::new(15_000),
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        };
+        let target_asset = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        // create order with an exclusivity of 1_000 seconds
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &coins(initial_asset.amount.u128(), "uluna")),
+            ExecuteMsg::CreateDcaOrder {
+                initial_asset: initial_asset.clone(),
+                target_asset: target_asset.clone(),
+                interval: 5_000,
+                dca_amount: Uint128::new(1_000),
+                first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: Some("user_bot".to_string()),
+                exclusivity_seconds: Some(1_000),
+                require_canonical_route: None,
+                max_price_impact: None,
+            },
+        )
+        .unwrap();
+
+        let modify_order = |new_interval| ExecuteMsg::ModifyDcaOrder {
+            id: 1,
+            new_initial_asset: initial_asset.clone(),
+            new_target_asset: target_asset.clone(),
+            new_interval,
+            new_dca_amount: Uint128::new(1_000),
+            new_first_purchase: None,
+        };
+
+        let res =
+            execute(deps.as_mut(), mock_env(), mock_creator(), modify_order(999)).unwrap_err();
+        assert_eq!(res, ContractError::ExclusivityTooLong {});
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            modify_order(1_000),
+        )
+        .unwrap();
+
+        let orders = USER_DCA
+            .load(&deps.storage, &mock_creator().sender)
+            .unwrap();
+        assert_eq!(orders[0].interval, 1_000);
+        assert_eq!(orders[0].exclusivity_seconds, 1_000);
+    }
 }
@@ -0,0 +1,168 @@
+use astroport_dca::dca::PauseScope;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, PAUSED_SCOPES},
+};
+
+/// ## Description
+/// Pauses `scope` of the contract in an emergency. Cancelling DCA orders and withdrawing bot tips
+/// cannot be paused, so that users can always exit the contract.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner or guardian who wants to pause the
+/// contract.
+///
+/// * `scope` - The [`PauseScope`] being paused.
+pub fn pause(
+    deps: DepsMut,
+    info: MessageInfo,
+    scope: PauseScope,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if config.guardian.as_ref() != Some(&info.sender) {
+        config.assert_owner(&info.sender)?;
+    }
+
+    let mut paused_scopes = PAUSED_SCOPES.may_load(deps.storage)?.unwrap_or_default();
+    if paused_scopes.contains(&scope) {
+        return Err(ContractError::AlreadyPaused { scope });
+    }
+
+    paused_scopes.push(scope.clone());
+    PAUSED_SCOPES.save(deps.storage, &paused_scopes)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "pause"),
+        attr("scope", scope.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, PauseScope, QueryMsg};
+    use cosmwasm_std::{coins, from_binary, testing::mock_info, Addr, Uint128};
+
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_pause() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(10_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        let tip = vec![Asset {
+            amount: Uint128::new(10_000),
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        }];
+
+        // only the owner or the guardian can pause the contract
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::Pause {
+                scope: PauseScope::TipDeposits {},
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Pause {
+                scope: PauseScope::TipDeposits {},
+            },
+        )
+        .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::Pause {
+                scope: PauseScope::TipDeposits {},
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::AlreadyPaused {
+                scope: PauseScope::TipDeposits {}
+            }
+        );
+
+        let paused: Vec<PauseScope> =
+            from_binary(&query(deps.as_ref(), env.clone(), QueryMsg::Paused {}).unwrap()).unwrap();
+        assert_eq!(paused, vec![PauseScope::TipDeposits {}]);
+
+        // tips cannot be deposited while paused
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(10_000, "uluna")),
+            ExecuteMsg::AddBotTip {
+                assets: tip.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::Paused {
+                scope: PauseScope::TipDeposits {}
+            }
+        );
+
+        // the guardian cannot unpause the contract
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("guardian", &[]),
+            ExecuteMsg::Unpause {
+                scope: PauseScope::TipDeposits {},
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::Unpause {
+                scope: PauseScope::TipDeposits {},
+            },
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("user", &coins(10_000, "uluna")),
+            ExecuteMsg::AddBotTip { assets: tip },
+        )
+        .unwrap();
+    }
+}
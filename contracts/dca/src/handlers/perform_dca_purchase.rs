@@ -1,22 +1,75 @@
 use astroport::{
     asset::{addr_validate_to_lower, Asset, AssetInfo},
-    router::{ExecuteMsg as RouterExecuteMsg, SwapOperation},
+    router::SwapOperation,
 };
-use astroport_dca::dca::DcaInfo;
+use astroport_dca::dca::{DcaInfo, PauseScope, SwapVenue};
 use cosmwasm_std::{
-    attr, to_binary, BankMsg, Coin, CosmosMsg, DepsMut, Env, MessageInfo, Response, StdError,
-    Uint128, WasmMsg,
+    attr, to_binary, Addr, Attribute, BankMsg, Coin, CosmosMsg, Decimal, DepsMut, Env, MessageInfo,
+    ReplyOn, Response, StdError, StdResult, SubMsg, Uint128, Uint256, WasmMsg,
 };
 use cw20::Cw20ExecuteMsg;
 
 use crate::{
     error::ContractError,
-    state::{CONFIG, USER_CONFIG, USER_DCA},
+    price_impact::assert_price_impact,
+    route::{assert_route_chains, find_best_route},
+    state::{
+        assert_not_paused, prune_keeper_executions, record_execution_return, route_key, Config,
+        KeeperExecution, PendingPurchase, PurchaseRecord, CONFIG, KEEPERS, KEEPER_EXECUTIONS,
+        KEEPER_STATS, LAST_EXECUTION_ID, PENDING_PURCHASE, PURCHASE_HISTORY, REFERRER_STATS,
+        ROUTES, USER_CONFIG, USER_DCA,
+    },
+    twap::snapshot_hops,
+    venue::{assert_venue_route, simulate, swap_msgs},
 };
 
+/// The reply ID of the swaps of DCA purchases performed by registered keepers
+pub const KEEPER_SWAP_REPLY_ID: u64 = 2;
+
+pub struct PerformDcaPurchase {
+    pub user: String,
+    pub id: u64,
+    pub hops: Option<Vec<SwapOperation>>,
+    pub fee_redeem: Vec<Asset>,
+    pub venue: Option<String>,
+}
+
+/// Describes a validated DCA purchase whose tip and fees are being paid, which still needs its
+/// hop route to be swapped
+pub struct PreparedPurchase {
+    /// The user the DCA purchase is performed for
+    pub user: Addr,
+    /// The asset being swapped, after the protocol and referral fees were taken
+    pub offer_asset: Asset,
+    /// The asset being purchased
+    pub target_asset: AssetInfo,
+    /// The hop route to swap through
+    pub hops: Vec<SwapOperation>,
+    /// The maximum spread of the swap
+    pub max_spread: Decimal,
+    /// The maximum price impact of the swap, if any, which batched executions check against the
+    /// swap aggregating the DCA purchase instead
+    pub max_price_impact: Option<Decimal>,
+    /// The swap venue to perform the hop route through
+    pub venue: SwapVenue,
+    /// The protocol fee taken from the DCA purchase, if any
+    pub protocol_fee: Option<Asset>,
+    /// The ID of the keeper execution recording the DCA purchase, if it was performed by a
+    /// registered keeper
+    pub execution_id: Option<u64>,
+    /// The messages paying the tip and the fees of the DCA purchase
+    pub messages: Vec<CosmosMsg>,
+    /// The attributes describing the DCA purchase
+    pub attributes: Vec<Attribute>,
+}
+
 /// ## Description
 /// Performs a DCA purchase on behalf of another user using the hop route specified.
 ///
+/// The purchased asset is sent to the user by the swap venue directly, unless the purchase is
+/// performed by a registered keeper, in which case it is sent to the contract so that the amount
+/// the swap returned can be recorded in [`keeper_swap_reply`].
+///
 /// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
 /// attributes if the operation was successful.
 /// ## Params
@@ -31,20 +84,190 @@ use crate::{
 ///
 /// * `id` - A [`u64`] representing the ID of the DCA order for the user
 ///
-/// * `hops` - A [`Vec<SwapOperation>`] of the hop operations to complete in the swap to purchase
-/// the target asset.
+/// * `hops` - An optional [`Vec<SwapOperation>`] of the hop operations to complete in the swap to
+/// purchase the target asset. If not specified, the route with the best return is discovered
+/// through the Astroport pairs between the whitelisted tokens.
 ///
 /// * `fee_redeem` - A [`Vec<Asset>`] of the fees redeemed by the sender for processing the DCA
 /// order.
+///
+/// * `venue` - The name of the whitelisted swap venue to perform the hop route through as an
+/// optional [`String`], defaulting to the Astroport router.
 pub fn perform_dca_purchase(
-    deps: DepsMut,
+    mut deps: DepsMut,
     env: Env,
     info: MessageInfo,
-    user: String,
-    id: u64,
-    hops: Vec<SwapOperation>,
-    fee_redeem: Vec<Asset>,
+    purchase: PerformDcaPurchase,
 ) -> Result<Response, ContractError> {
+    let id = purchase.id;
+    let PreparedPurchase {
+        user,
+        offer_asset,
+        target_asset,
+        hops,
+        max_spread,
+        venue,
+        protocol_fee,
+        execution_id,
+        mut messages,
+        attributes,
+        ..
+    } = prepare_purchase(deps.branch(), &env, &info.sender, purchase, false)?;
+
+    // record the purchase, where the purchased asset is sent to the user by the venue directly
+    // unless the amount it returned has to be measured for a keeper execution
+    PURCHASE_HISTORY.update::<_, StdError>(deps.storage, (&user, id), |history| {
+        let mut history = history.unwrap_or_default();
+        history.push(PurchaseRecord {
+            offer_asset: offer_asset.clone(),
+            return_asset: None,
+            executed_at: env.block.time.seconds(),
+            matched: false,
+            protocol_fee,
+        });
+        Ok(history)
+    })?;
+
+    let execution_id = match execution_id {
+        Some(execution_id) => execution_id,
+        None => {
+            // tell the venue to perform the swap operations
+            messages.extend(swap_msgs(
+                &venue,
+                &user,
+                &user,
+                &offer_asset,
+                &hops,
+                max_spread,
+            )?);
+
+            return Ok(Response::new()
+                .add_messages(messages)
+                .add_attribute("action", "perform_dca_purchase")
+                .add_attributes(attributes));
+        }
+    };
+
+    // the swap of a keeper execution sends the purchased asset to the contract, which replies to
+    // the swap itself to measure the amount it returned, and is performed before the tip and the
+    // fees are paid so that the balance of the purchased asset is only changed by the swap
+    let balance_before = target_asset.query_pool(&deps.querier, env.contract.address.clone())?;
+    PENDING_PURCHASE.save(
+        deps.storage,
+        &PendingPurchase {
+            user: user.clone(),
+            id,
+            target_asset,
+            execution_id,
+            balance_before,
+        },
+    )?;
+
+    let mut swap_messages: Vec<SubMsg> = swap_msgs(
+        &venue,
+        &user,
+        &env.contract.address,
+        &offer_asset,
+        &hops,
+        max_spread,
+    )?
+    .into_iter()
+    .map(SubMsg::new)
+    .collect();
+    if let Some(swap) = swap_messages.last_mut() {
+        swap.id = KEEPER_SWAP_REPLY_ID;
+        swap.reply_on = ReplyOn::Success;
+    }
+
+    Ok(Response::new()
+        .add_submessages(swap_messages)
+        .add_messages(messages)
+        .add_attribute("action", "perform_dca_purchase")
+        .add_attributes(attributes))
+}
+
+/// ## Description
+/// Records the amount the swap of a DCA purchase performed by a registered keeper returned in its
+/// keeper execution and its purchase history, and sends the purchased asset to the user.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+pub fn keeper_swap_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let PendingPurchase {
+        user,
+        id,
+        target_asset,
+        execution_id,
+        balance_before,
+    } = PENDING_PURCHASE.load(deps.storage)?;
+    PENDING_PURCHASE.remove(deps.storage);
+
+    let balance = target_asset.query_pool(&deps.querier, env.contract.address)?;
+    let return_asset = Asset {
+        info: target_asset,
+        amount: balance.checked_sub(balance_before)?,
+    };
+    record_execution_return(deps.storage, execution_id, return_asset.amount)?;
+
+    PURCHASE_HISTORY.update::<_, StdError>(deps.storage, (&user, id), |history| {
+        let mut history = history.unwrap_or_default();
+        if let Some(record) = history.last_mut() {
+            record.return_asset = Some(return_asset.clone());
+        }
+        Ok(history)
+    })?;
+
+    let mut messages = vec![];
+    if !return_asset.amount.is_zero() {
+        messages.push(return_asset.clone().into_msg(&deps.querier, user)?);
+    }
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "record_keeper_swap"),
+        attr("execution_id", execution_id.to_string()),
+        attr("return_amount", return_asset.amount),
+    ]))
+}
+
+/// ## Description
+/// Validates a DCA purchase performed by `sender`, updating the DCA order and paying the tip and
+/// the fees of the purchase, without swapping the hop route.
+///
+/// The tip includes the batch surcharge of each fee asset if the purchase is `batched`.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns the [`PreparedPurchase`] that still
+/// needs to be swapped.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `sender` - The address of the bot who is performing the DCA purchase.
+///
+/// * `purchase` - The [`PerformDcaPurchase`] being performed.
+///
+/// * `batched` - Whether the DCA purchase is performed as part of a batched execution.
+pub fn prepare_purchase(
+    deps: DepsMut,
+    env: &Env,
+    sender: &Addr,
+    purchase: PerformDcaPurchase,
+    batched: bool,
+) -> Result<PreparedPurchase, ContractError> {
+    let PerformDcaPurchase {
+        user,
+        id,
+        hops,
+        fee_redeem,
+        venue,
+    } = purchase;
+
+    assert_not_paused(deps.storage, PauseScope::Purchases {})?;
+
     // validate user address
     let user_address = addr_validate_to_lower(deps.api, &user)?;
 
@@ -54,6 +277,74 @@ pub fn perform_dca_purchase(
         .unwrap_or_default();
     let contract_config = CONFIG.load(deps.storage)?;
 
+    // validate the sender is a registered keeper if only keepers can perform purchases
+    if let Some(keeper_config) = contract_config.enabled_keeper_config() {
+        let keeper = KEEPERS
+            .may_load(deps.storage, sender)?
+            .ok_or(ContractError::KeeperNotRegistered {})?;
+
+        if keeper.unbonding_since.is_some() {
+            return Err(ContractError::KeeperUnbonding {});
+        }
+
+        if keeper.bond.info != keeper_config.bond.info
+            || keeper.bond.amount < keeper_config.bond.amount
+        {
+            return Err(ContractError::InsufficientKeeperBond {});
+        }
+    }
+
+    // retrieve the swap venue to perform the purchase through
+    let swap_venue = contract_config.venue(venue.as_deref())?;
+
+    // retrieve the canonical route of the order if it has to be used
+    let order = USER_DCA
+        .may_load(deps.storage, &user_address)?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|order| order.id == id);
+    let canonical_route = match &order {
+        Some(order) if order.require_canonical_route => Some(
+            ROUTES
+                .may_load(
+                    deps.storage,
+                    route_key(&order.initial_asset.info, &order.target_asset),
+                )?
+                .ok_or(ContractError::NonexistentRoute {})?,
+        ),
+        _ => None,
+    };
+
+    // use the canonical route or discover the best route if no hop route was specified
+    let discovered = hops.is_none() && canonical_route.is_none();
+    let hops = match (hops, canonical_route) {
+        (Some(hops), Some(route)) if hops != route => {
+            return Err(ContractError::NonCanonicalRoute {})
+        }
+        (Some(hops), _) => hops,
+        (None, Some(route)) => route,
+        (None, None) => {
+            let order = order.as_ref().ok_or(ContractError::NonexistentDca {})?;
+
+            // the route is discovered for the amount left to swap once the fees are taken
+            let (protocol_fee, referral_fee) = purchase_fees(&contract_config, order);
+            let swap_amount = order
+                .dca_amount
+                .checked_sub(protocol_fee)?
+                .checked_sub(referral_fee)?;
+
+            find_best_route(
+                &deps.querier,
+                &contract_config,
+                &swap_venue,
+                &order.initial_asset.info,
+                &order.target_asset,
+                swap_amount,
+                user_config.max_hops.unwrap_or(contract_config.max_hops),
+            )?
+        }
+    };
+
     // validate hops is at least one
     if hops.is_empty() {
         return Err(ContractError::EmptyHopRoute {});
@@ -94,40 +385,67 @@ pub fn perform_dca_purchase(
         }
     }
 
-    // validate that fee_redeem is a valid combination
-    let requested_fee_hops: Uint128 = fee_redeem
+    // validate that the route chains from the initial asset and can be performed through the venue
+    if let Some(order) = &order {
+        assert_route_chains(&hops, &order.initial_asset.info)?;
+    }
+    let pair_types = assert_venue_route(&deps.querier, &contract_config, &swap_venue, &hops)?;
+
+    // validate that fee_redeem is a valid combination, where each redeemed asset is a fraction of
+    // the full tip in that asset, and the fractions together do not exceed the full tip
+    let requested_fractions = fee_redeem
         .iter()
         .map(|a| {
-            let whitelisted_asset = contract_config
-                .whitelisted_fee_assets
-                .iter()
-                .find(|w| a.info == w.info)
-                .ok_or(ContractError::NonWhitelistedTipAsset {
+            let fee_asset = contract_config.fee_asset(&a.info).ok_or(
+                ContractError::NonWhitelistedTipAsset {
                     asset: a.info.clone(),
-                })?;
+                },
+            )?;
 
-            // ensure that it is exactly divisible
-            if !a
-                .amount
-                .checked_rem(whitelisted_asset.amount)
-                .map_err(|e| StdError::DivideByZero { source: e })?
-                .is_zero()
-            {
-                return Err(ContractError::IndivisibleDeposit {});
+            let tip = match batched {
+                true => fee_asset.batch_tip(hops_len)?,
+                false => fee_asset.tip(hops_len)?,
+            };
+            if a.amount > tip {
+                return Err(ContractError::RedeemTipTooLarge {
+                    requested: a.clone(),
+                    tip: Asset {
+                        info: a.info.clone(),
+                        amount: tip,
+                    },
+                });
             }
 
-            // we don't need to use `checked_div` here as we early exit above if
-            // `whitelisted_asset.amount` is zero
-            Ok(a.amount / whitelisted_asset.amount)
+            Ok((a.amount, tip))
         })
-        .collect::<Result<Vec<_>, _>>()?
-        .iter()
-        .sum();
+        .collect::<Result<Vec<_>, ContractError>>()?
+        .into_iter()
+        // we don't need to worry about `tip` being zero here, as we early exit above if the
+        // requested amount is larger than the tip
+        .filter(|(amount, _)| !amount.is_zero())
+        .collect::<Vec<_>>();
+
+    // the fractions are summed exactly as `numerator / denominator`, so that rounding each of
+    // them down cannot let the requested tip exceed the full tip
+    let (numerator, denominator) = requested_fractions.iter().try_fold(
+        (Uint256::zero(), Uint256::from(1u128)),
+        |(numerator, denominator), (amount, tip)| -> StdResult<_> {
+            Ok((
+                numerator
+                    .checked_mul((*tip).into())?
+                    .checked_add(Uint256::from(*amount).checked_mul(denominator)?)?,
+                denominator.checked_mul((*tip).into())?,
+            ))
+        },
+    )?;
 
-    if requested_fee_hops > Uint128::new(u128::from(hops_len)) {
-        return Err(ContractError::RedeemTipTooLarge {
-            requested: requested_fee_hops,
-            performed: Uint128::new(u128::from(hops_len)),
+    if numerator > denominator {
+        return Err(ContractError::RedeemTipExceedsTip {
+            requested: requested_fractions
+                .iter()
+                .fold(Decimal::zero(), |acc, (amount, tip)| {
+                    acc + Decimal::from_ratio(*amount, *tip)
+                }),
         });
     }
 
@@ -135,7 +453,7 @@ pub fn perform_dca_purchase(
     let mut messages: Vec<CosmosMsg> = Vec::new();
 
     // validate purchaser has enough funds to pay the sender
-    for fee_asset in fee_redeem {
+    for fee_asset in &fee_redeem {
         let mut user_balance = user_config
             .tip_balance
             .iter_mut()
@@ -151,20 +469,20 @@ pub fn perform_dca_purchase(
         user_balance.amount = new_balance;
 
         // add tip payment to messages
-        let tip_payment_message = match fee_asset.info {
+        let tip_payment_message = match &fee_asset.info {
             AssetInfo::NativeToken { denom } => BankMsg::Send {
-                to_address: info.clone().sender.to_string(),
+                to_address: sender.to_string(),
                 amount: vec![Coin {
                     amount: fee_asset.amount,
-                    denom,
+                    denom: denom.clone(),
                 }],
             }
             .into(),
             AssetInfo::Token { contract_addr } => WasmMsg::Execute {
-                contract_addr: contract_addr.into_string(),
+                contract_addr: contract_addr.to_string(),
                 msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
                     owner: user_address.clone().into_string(),
-                    recipient: info.sender.clone().into_string(),
+                    recipient: sender.to_string(),
                     amount: fee_asset.amount,
                 })?,
                 funds: vec![],
@@ -175,8 +493,21 @@ pub fn perform_dca_purchase(
         messages.push(tip_payment_message);
     }
 
-    // retrieve max_spread from user config, or default to contract set max_spread
-    let max_spread = user_config.max_spread.unwrap_or(contract_config.max_spread);
+    // retrieve max_spread from user config, or default to the contract set max_spread for the pair
+    // types in the route
+    let max_spread = user_config
+        .max_spread
+        .unwrap_or_else(|| contract_config.default_max_spread(&pair_types));
+
+    // the protocol fee and referral fee taken from the purchase, if any
+    let mut protocol_fee = None;
+    let mut referral_fee = None;
+
+    // the asset swapped in the purchase
+    let mut offer_asset = None;
+
+    // the deposit refunded if the order is completed by the purchase
+    let mut refunded_deposit = None;
 
     // load user dca orders and update the relevant one
     USER_DCA.update(
@@ -193,10 +524,20 @@ pub fn perform_dca_purchase(
             let mut order = &mut orders[order_idx];
 
             // check that it has been long enough between dca purchases
-            if order.last_purchase + order.interval > env.block.time.seconds() {
+            if order.next_purchase() > env.block.time.seconds() {
                 return Err(ContractError::PurchaseTooEarly {});
             }
 
+            // check that the preferred executor of the order is not within their exclusivity period
+            if let Some(executor) = order.exclusive_executor(env.block.time.seconds()) {
+                if executor != sender {
+                    return Err(ContractError::NotPreferredExecutor {
+                        executor: executor.clone(),
+                        until: order.exclusivity_end(),
+                    });
+                }
+            }
+
             // check that last hop is target asset
             let last_hop = hops.last().ok_or(ContractError::EmptyHopRoute {})?;
             if last_hop.get_target_asset_info() != order.target_asset {
@@ -211,64 +552,244 @@ pub fn perform_dca_purchase(
                 .map_err(|_| ContractError::InsufficientBalance {})?;
             order.last_purchase = env.block.time.seconds();
 
-            // add funds and router message to response
-            if let AssetInfo::Token { contract_addr } = &order.initial_asset.info {
-                // send a TransferFrom request to the token to the router
-                messages.push(
-                    WasmMsg::Execute {
-                        contract_addr: contract_addr.to_string(),
-                        funds: vec![],
-                        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
-                            owner: user_address.to_string(),
-                            recipient: contract_config.router_addr.to_string(),
-                            amount: order.dca_amount,
-                        })?,
-                    }
-                    .into(),
-                );
+            let (protocol_fee_amount, referral_fee_amount) = purchase_fees(&contract_config, order);
+
+            // take the protocol fee from the purchase before performing the swap
+            let mut swap_amount = order.dca_amount;
+            if let Some(fee_collector) = &contract_config.fee_collector {
+                let fee = Asset {
+                    info: order.initial_asset.info.clone(),
+                    amount: protocol_fee_amount,
+                };
+
+                if !fee.amount.is_zero() {
+                    swap_amount = swap_amount.checked_sub(fee.amount)?;
+                    messages.push(transfer_from_user(&fee, &user_address, fee_collector)?);
+                    protocol_fee = Some(fee);
+                }
             }
 
-            // if it is a native token, we need to send the funds
-            let funds = match &order.initial_asset.info {
-                AssetInfo::NativeToken { denom } => vec![Coin {
-                    amount: order.dca_amount,
-                    denom: denom.clone(),
-                }],
-                AssetInfo::Token { .. } => vec![],
-            };
+            // pay the referrer of the order their share of the purchase
+            if let Some(referral) = &order.referral {
+                let fee = Asset {
+                    info: order.initial_asset.info.clone(),
+                    amount: referral_fee_amount,
+                };
 
-            // tell the router to perform swap operations
-            messages.push(
-                WasmMsg::Execute {
-                    contract_addr: contract_config.router_addr.to_string(),
-                    funds,
-                    msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
-                        operations: hops,
-                        minimum_receive: None,
-                        to: Some(user_address.clone().into_string()),
-                        max_spread: Some(max_spread),
-                    })?,
+                if !fee.amount.is_zero() {
+                    swap_amount = swap_amount.checked_sub(fee.amount)?;
+                    messages.push(transfer_from_user(&fee, &user_address, &referral.referrer)?);
                 }
-                .into(),
-            );
+
+                referral_fee = Some((
+                    referral.referrer.clone(),
+                    Asset {
+                        info: order.initial_asset.info.clone(),
+                        amount: order.dca_amount,
+                    },
+                    fee,
+                ));
+            }
+
+            offer_asset = Some(Asset {
+                info: order.initial_asset.info.clone(),
+                amount: swap_amount,
+            });
 
             // remove order if it was fulfilled
             if order.initial_asset.amount.is_zero() {
-                orders.remove(order_idx);
+                refunded_deposit = orders.remove(order_idx).deposit;
             }
 
             Ok(orders)
         },
     )?;
+    let offer_asset = offer_asset.ok_or(ContractError::NonexistentDca {})?;
+
+    // refund the deposit of a completed order to the user
+    if let Some(deposit) = refunded_deposit {
+        messages.push(deposit.into_msg(&deps.querier, user_address.clone())?);
+    }
+
+    // validate that the price impact of the purchase does not exceed the maximum price impact of
+    // the order, or the one of the user if the order does not set one, where batched purchases are
+    // validated once they are aggregated into the swap that is actually performed
+    let max_price_impact = order
+        .as_ref()
+        .and_then(|order| order.max_price_impact)
+        .or(user_config.max_price_impact);
+    if let Some(max_price_impact) = max_price_impact.filter(|_| !batched) {
+        let return_amount = simulate(&deps.querier, &swap_venue, &offer_asset, &hops)?;
+        assert_price_impact(
+            &deps.querier,
+            &contract_config,
+            &swap_venue,
+            &offer_asset,
+            &hops,
+            return_amount,
+            max_price_impact,
+        )?;
+    }
 
     // save new config
     USER_CONFIG.save(deps.storage, &user_address, &user_config)?;
 
-    Ok(Response::new().add_messages(messages).add_attributes(vec![
-        attr("action", "perform_dca_purchase"),
-        attr("user", user_address),
+    let mut attributes = vec![
+        attr("user", user_address.to_string()),
         attr("id", id.to_string()),
-    ]))
+    ];
+    if let Some(protocol_fee) = &protocol_fee {
+        attributes.push(attr("protocol_fee", protocol_fee.to_string()));
+    }
+    if let Some(venue) = venue {
+        attributes.push(attr("venue", venue));
+    }
+
+    // record the purchase for the referrer
+    if let Some((referrer, volume, fee)) = referral_fee {
+        REFERRER_STATS.update::<_, StdError>(deps.storage, &referrer, |stats| {
+            let mut stats = stats.unwrap_or_default();
+            stats.record_purchase(&volume, &fee);
+            Ok(stats)
+        })?;
+
+        attributes.push(attr("referrer", referrer));
+        attributes.push(attr("referral_fee", fee.to_string()));
+    }
+
+    // record the purchase and the tips earned for the sender
+    KEEPER_STATS.update::<_, StdError>(deps.storage, sender, |stats| {
+        let mut stats = stats.unwrap_or_default();
+        stats.record_purchase(&fee_redeem, env.block.time.seconds());
+        Ok(stats)
+    })?;
+
+    let target_asset = hops
+        .last()
+        .ok_or(ContractError::EmptyHopRoute {})?
+        .get_target_asset_info();
+
+    // record purchases by registered keepers so that they can be challenged against the TWAP
+    let mut execution_id = None;
+    if let Some(keeper_config) = contract_config.enabled_keeper_config() {
+        let now = env.block.time.seconds();
+        prune_keeper_executions(deps.storage, now, keeper_config.challenge_period)?;
+
+        // the purchase is challenged against the TWAP of the canonical route of its assets, or of
+        // the best Astroport route between them, regardless of the route the keeper swapped through
+        let astroport = contract_config.venue(None)?;
+        let reference_route =
+            match ROUTES.may_load(deps.storage, route_key(&offer_asset.info, &target_asset))? {
+                Some(route) => Some(route),
+                None if discovered && swap_venue == astroport => Some(hops.clone()),
+                None => match find_best_route(
+                    &deps.querier,
+                    &contract_config,
+                    &astroport,
+                    &offer_asset.info,
+                    &target_asset,
+                    offer_asset.amount,
+                    user_config.max_hops.unwrap_or(contract_config.max_hops),
+                ) {
+                    Ok(route) => Some(route),
+                    Err(ContractError::NoRouteFound {}) => None,
+                    Err(err) => return Err(err),
+                },
+            };
+        let snapshots = match reference_route {
+            Some(route) => snapshot_hops(
+                deps.storage,
+                &deps.querier,
+                now,
+                &contract_config.factory_addr,
+                &route,
+            )?,
+            None => None,
+        };
+
+        let next_execution_id = LAST_EXECUTION_ID
+            .may_load(deps.storage)?
+            .unwrap_or_default()
+            + 1;
+        LAST_EXECUTION_ID.save(deps.storage, &next_execution_id)?;
+
+        KEEPER_EXECUTIONS.save(
+            deps.storage,
+            next_execution_id,
+            &KeeperExecution {
+                keeper: sender.clone(),
+                user: user_address.clone(),
+                order_id: id,
+                offer_asset: offer_asset.clone(),
+                hops: hops.clone(),
+                // the amount the purchase returned is recorded once its swap was performed
+                return_amount: Uint128::zero(),
+                executed_at: now,
+                snapshots,
+            },
+        )?;
+
+        attributes.push(attr("execution_id", next_execution_id.to_string()));
+        execution_id = Some(next_execution_id);
+    }
+
+    Ok(PreparedPurchase {
+        user: user_address,
+        offer_asset,
+        target_asset,
+        hops,
+        max_spread,
+        max_price_impact,
+        venue: swap_venue,
+        protocol_fee,
+        execution_id,
+        messages,
+        attributes,
+    })
+}
+
+/// ## Description
+/// Returns the amounts of the protocol fee and the referral fee taken from a DCA purchase of the
+/// `order`, where the protocol fee is only taken if a fee collector is set.
+fn purchase_fees(config: &Config, order: &DcaInfo) -> (Uint128, Uint128) {
+    let protocol_fee = match config.fee_collector {
+        Some(_) => config.protocol_fee_amount(order.dca_amount),
+        None => Uint128::zero(),
+    };
+
+    let referral_fee = order.referral.as_ref().map_or(Uint128::zero(), |referral| {
+        order
+            .dca_amount
+            .multiply_ratio(u128::from(referral.fee_bps), 10_000u128)
+    });
+
+    (protocol_fee, referral_fee)
+}
+
+/// ## Description
+/// Creates a message transferring `asset` from the `user` to the `recipient`, where native tokens
+/// are sent from the contract and cw20 tokens are transferred using the allowance of the `user`.
+fn transfer_from_user(asset: &Asset, user: &Addr, recipient: &Addr) -> StdResult<CosmosMsg> {
+    Ok(match &asset.info {
+        AssetInfo::NativeToken { denom } => BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: vec![Coin {
+                amount: asset.amount,
+                denom: denom.clone(),
+            }],
+        }
+        .into(),
+        AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: user.to_string(),
+                recipient: recipient.to_string(),
+                amount: asset.amount,
+            })?,
+        }
+        .into(),
+    })
 }
 
 #[cfg(test)]
@@ -276,31 +797,48 @@ mod test {
     use std::str::FromStr;
 
     use astroport::{
-        asset::{Asset, AssetInfo},
-        factory::{PairConfig, PairType},
-        router::SwapOperation,
+        asset::{Asset, AssetInfo, PairInfo},
+        factory::{PairConfig, PairType, QueryMsg as FactoryQueryMsg},
+        pair::{CumulativePricesResponse, PoolResponse, StablePoolParams},
+        router::{SimulateSwapOperationsResponse, SwapOperation},
+    };
+    use astroport_dca::dca::{
+        AllowedAssets, BatchPurchase, DcaInfo, ExecuteMsg, InstantiateMsg, KeeperConfig,
+        PairTypeSpread, Referral, SwapVenue, SwapVenueType,
     };
-    use astroport_dca::dca::{DcaInfo, ExecuteMsg, InstantiateMsg};
     use cosmwasm_std::{
-        attr, coin, coins,
-        testing::{mock_dependencies, mock_env, mock_info},
-        to_binary, Addr, BankMsg, CosmosMsg, Decimal, DivideByZeroError, Response, StdError,
-        Uint128, WasmMsg,
+        attr, coin, coins, from_binary,
+        testing::{
+            mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+            MOCK_CONTRACT_ADDR,
+        },
+        to_binary, Addr, BankMsg, ContractResult, CosmosMsg, Decimal, OwnedDeps, Reply, Response,
+        StdError, SubMsg, SubMsgResponse, SubMsgResult, SystemResult, Timestamp, Uint128, WasmMsg,
+        WasmQuery,
     };
     use cw20::{BalanceResponse, Cw20Coin};
     use cw_multi_test::{App, Executor};
 
     use crate::{
-        contract::execute,
+        contract::{execute, reply},
         error::ContractError,
-        state::{Config, UserConfig, CONFIG, USER_CONFIG, USER_DCA},
+        state::{
+            route_key, Config, Keeper, KeeperStats, PriceObservation, PriceObservations,
+            PurchaseRecord, ReferrerStats, UserConfig, CONFIG, KEEPERS, KEEPER_EXECUTIONS,
+            KEEPER_STATS, PENDING_PURCHASE, PRICE_OBSERVATIONS, PURCHASE_HISTORY, REFERRER_STATS,
+            ROUTES, USER_CONFIG, USER_DCA,
+        },
         tests::{
-            mock_app_with_balance, mock_creator, mock_instantiate, read_map,
-            store_astroport_pair_code, store_cw20_token_code, store_dca_module_code,
-            store_factory_code, store_router_code,
+            mock_app_with_balance, mock_creator, mock_factory, mock_instantiate,
+            per_hop_fee_assets, read_map, store_astroport_pair_code,
+            store_astroport_stable_pair_code, store_cw20_token_code, store_dca_module_code,
+            store_factory_code, store_mock_pair_code, store_mock_router_code, store_router_code,
+            MockPairInstantiateMsg, MockRouterInstantiateMsg,
         },
     };
 
+    use super::KEEPER_SWAP_REPLY_ID;
+
     fn instantiate(max_hops: Option<u32>) -> (App, Addr, Addr) {
         let admin = Addr::unchecked("admin");
 
@@ -319,6 +857,7 @@ mod test {
         let cw20_token_id = store_cw20_token_code(&mut app);
         let dca_module_id = store_dca_module_code(&mut app);
         let astroport_pair_id = store_astroport_pair_code(&mut app);
+        let astroport_stable_pair_id = store_astroport_stable_pair_code(&mut app);
         let factory_id = store_factory_code(&mut app);
         let router_id = store_router_code(&mut app);
 
@@ -378,14 +917,24 @@ mod test {
                     fee_address: None,
                     generator_address: None,
                     owner: mock_creator().sender.into_string(),
-                    pair_configs: vec![PairConfig {
-                        pair_type: PairType::Xyk {},
-                        is_disabled: false,
-                        is_generator_disabled: true,
-                        maker_fee_bps: 30,
-                        total_fee_bps: 30,
-                        code_id: astroport_pair_id,
-                    }],
+                    pair_configs: vec![
+                        PairConfig {
+                            pair_type: PairType::Xyk {},
+                            is_disabled: false,
+                            is_generator_disabled: true,
+                            maker_fee_bps: 30,
+                            total_fee_bps: 30,
+                            code_id: astroport_pair_id,
+                        },
+                        PairConfig {
+                            pair_type: PairType::Stable {},
+                            is_disabled: false,
+                            is_generator_disabled: true,
+                            maker_fee_bps: 5,
+                            total_fee_bps: 5,
+                            code_id: astroport_stable_pair_id,
+                        },
+                    ],
                     token_code_id: cw20_token_id,
                     whitelist_code_id: 100,
                 },
@@ -415,11 +964,14 @@ mod test {
                 dca_module_id,
                 mock_creator().sender,
                 &InstantiateMsg {
+                    owner: mock_creator().sender.into_string(),
+                    guardian: None,
+                    config_timelock: Some(0),
                     factory_addr: factory_addr.clone().into_string(),
                     max_hops: max_hops.unwrap_or(4),
                     max_spread: "0.05".to_string(),
                     router_addr: router_addr.into_string(),
-                    whitelisted_fee_assets: vec![
+                    whitelisted_fee_assets: per_hop_fee_assets(vec![
                         Asset {
                             amount: Uint128::new(15_000),
                             info: AssetInfo::NativeToken {
@@ -432,7 +984,7 @@ mod test {
                                 contract_addr: cw20_addr.clone(),
                             },
                         },
-                    ],
+                    ]),
                     whitelisted_tokens: vec![
                         AssetInfo::Token {
                             contract_addr: cw20_whitelist_addr,
@@ -444,6 +996,18 @@ mod test {
                             denom: "ujpy".to_string(),
                         },
                     ],
+                    protocol_fee: None,
+                    fee_collector: None,
+                    max_referral_fee_bps: 0,
+                    keeper_config: None,
+                    allowed_pair_types: None,
+                    pair_type_max_spreads: None,
+                    venues: None,
+                    allowed_initial_assets: None,
+                    allowed_target_assets: None,
+                    min_dca_amounts: None,
+                    max_orders_per_user: None,
+                    order_deposit: None,
                 },
                 &[],
                 "dca module",
@@ -452,15 +1016,21 @@ mod test {
             .unwrap();
 
         // create some pools
-        let mut create_pool = |assets: [AssetInfo; 2]| {
+        let mut create_pool = |pair_type: PairType, assets: [AssetInfo; 2]| {
+            // stableswap pairs need an amplification parameter
+            let init_params = match pair_type {
+                PairType::Stable {} => Some(to_binary(&StablePoolParams { amp: 100 }).unwrap()),
+                _ => None,
+            };
+
             let pair_res = app
                 .execute_contract(
                     mock_creator().sender,
                     factory_addr.clone(),
                     &astroport::factory::ExecuteMsg::CreatePair {
-                        pair_type: PairType::Xyk {},
+                        pair_type,
                         asset_infos: [assets[0].clone(), assets[1].clone()],
-                        init_params: None,
+                        init_params,
                     },
                     &[],
                 )
@@ -512,30 +1082,50 @@ mod test {
             .unwrap();
         };
 
-        create_pool([
-            AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: "ujpy".to_string(),
-            },
-        ]);
-        create_pool([
-            AssetInfo::NativeToken {
-                denom: "ujpy".to_string(),
-            },
-            AssetInfo::NativeToken {
-                denom: "ukrw".to_string(),
-            },
-        ]);
-        create_pool([
-            AssetInfo::Token {
-                contract_addr: cw20_addr.clone(),
-            },
-            AssetInfo::NativeToken {
-                denom: "ujpy".to_string(),
-            },
-        ]);
+        create_pool(
+            PairType::Xyk {},
+            [
+                AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                AssetInfo::NativeToken {
+                    denom: "ujpy".to_string(),
+                },
+            ],
+        );
+        create_pool(
+            PairType::Xyk {},
+            [
+                AssetInfo::NativeToken {
+                    denom: "ujpy".to_string(),
+                },
+                AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+            ],
+        );
+        create_pool(
+            PairType::Xyk {},
+            [
+                AssetInfo::Token {
+                    contract_addr: cw20_addr.clone(),
+                },
+                AssetInfo::NativeToken {
+                    denom: "ujpy".to_string(),
+                },
+            ],
+        );
+        create_pool(
+            PairType::Stable {},
+            [
+                AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+            ],
+        );
 
         (app, dca_addr, cw20_addr)
     }
@@ -565,6 +1155,12 @@ mod test {
                 interval: NORMAL_ORDER_INTERVAL,
                 dca_amount: Uint128::new(10_000),
                 first_purchase: None,
+                referrer: None,
+                referral_fee_bps: None,
+                preferred_executor: None,
+                exclusivity_seconds: None,
+                require_canonical_route: None,
+                max_price_impact: None,
             },
             funds,
         )
@@ -614,7 +1210,7 @@ mod test {
             &ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -631,13 +1227,14 @@ mod test {
                             denom: "ukrw".to_string(),
                         },
                     },
-                ],
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
             &[],
         )
@@ -684,99 +1281,490 @@ mod test {
             target_asset: AssetInfo::NativeToken {
                 denom: "ukrw".to_string(),
             },
+            referral: None,
+            preferred_executor: None,
+            exclusivity_seconds: 0,
+            require_canonical_route: false,
+            max_price_impact: None,
+            deposit: None,
         }];
         assert_eq!(user_dca_orders, expected_orders);
     }
 
     #[test]
-    fn can_perform_token_purchase() {
-        let (mut app, dca_addr, cw20_addr) = instantiate(None);
-
-        // increase allowance for order
-        app.execute_contract(
-            mock_creator().sender,
-            cw20_addr.clone(),
-            &cw20_base::msg::ExecuteMsg::IncreaseAllowance {
-                spender: dca_addr.clone().into_string(),
-                amount: Uint128::new(100_000),
-                expires: None,
-            },
-            &[],
-        )
-        .unwrap();
+    fn can_perform_stable_purchase() {
+        let (mut app, dca_addr, ..) = instantiate(None);
 
         create_normal_order(
             &mut app,
             dca_addr.clone(),
-            AssetInfo::Token {
-                contract_addr: cw20_addr.clone(),
-            },
             AssetInfo::NativeToken {
                 denom: "uluna".to_string(),
             },
+            AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
         );
 
         add_tip_balance(&mut app, dca_addr.clone());
 
-        let bot_user = Addr::unchecked("bot_user");
-
-        // perform purchase
+        // perform purchase through the stableswap pair
         app.execute_contract(
-            bot_user.clone(),
-            dca_addr.clone(),
+            Addr::unchecked("bot_user"),
+            dca_addr,
             &ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
-                    SwapOperation::AstroSwap {
-                        offer_asset_info: AssetInfo::Token {
-                            contract_addr: cw20_addr.clone(),
-                        },
-                        ask_asset_info: AssetInfo::NativeToken {
-                            denom: "ujpy".to_string(),
-                        },
+                hops: Some(vec![SwapOperation::AstroSwap {
+                    offer_asset_info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
                     },
-                    SwapOperation::AstroSwap {
-                        offer_asset_info: AssetInfo::NativeToken {
-                            denom: "ujpy".to_string(),
-                        },
-                        ask_asset_info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
-                        },
+                    ask_asset_info: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
                     },
-                ],
+                }]),
                 fee_redeem: vec![Asset {
-                    amount: Uint128::new(30_000),
+                    amount: Uint128::new(15_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
             &[],
         )
         .unwrap();
 
-        // should have removed from tip balance
-        let user_config = read_map(&app, dca_addr.clone(), &mock_creator().sender, USER_CONFIG);
-        // 150,000 start - 30,000 fee redeem
-        assert_eq!(
-            user_config.tip_balance,
-            vec![Asset {
-                amount: Uint128::new(120_000),
-                info: AssetInfo::NativeToken {
-                    denom: "uluna".to_string()
-                }
-            }]
-        );
+        // should have bought the target token
+        let user_balance = app
+            .wrap()
+            .query_balance(mock_creator().sender, "ukrw")
+            .unwrap();
+        assert!(!user_balance.amount.is_zero());
+    }
 
-        // should have added to bot balance
-        let bot_balance = app.wrap().query_balance(bot_user, "uluna").unwrap();
-        assert_eq!(bot_balance, coin(30_000, "uluna"));
+    #[test]
+    fn can_perform_purchase_through_venues() {
+        let (mut app, dca_addr, ..) = instantiate(None);
 
-        // should have spent the initial asset
-        let user_balance: cw20::BalanceResponse = app
-            .wrap()
-            .query_wasm_smart(
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ujpy = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(&dca_addr, &astroport_dca::dca::QueryMsg::Config {})
+            .unwrap();
+
+        // whitelist the uluna-ukrw pair directly and another router as a mock DEX router
+        let pair: PairInfo = app
+            .wrap()
+            .query_wasm_smart(
+                &config.factory_addr,
+                &astroport::factory::QueryMsg::Pair {
+                    asset_infos: [uluna.clone(), ukrw.clone()],
+                },
+            )
+            .unwrap();
+
+        let router_id = store_router_code(&mut app);
+        let dex_router_addr = app
+            .instantiate_contract(
+                router_id,
+                mock_creator().sender,
+                &astroport::router::InstantiateMsg {
+                    astroport_factory: config.factory_addr.into_string(),
+                },
+                &[],
+                "mock dex router",
+                None,
+            )
+            .unwrap();
+
+        app.execute_contract(
+            mock_creator().sender,
+            dca_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                max_hops: None,
+                max_spread: None,
+                protocol_fee: None,
+                fee_collector: None,
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: Some(vec![
+                    SwapVenue {
+                        name: "luna_krw".to_string(),
+                        venue_type: SwapVenueType::AstroportPair {},
+                        contract_addr: pair.contract_addr,
+                    },
+                    SwapVenue {
+                        name: "dex".to_string(),
+                        venue_type: SwapVenueType::Router {},
+                        contract_addr: dex_router_addr,
+                    },
+                ]),
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            mock_creator().sender,
+            dca_addr.clone(),
+            &ExecuteMsg::ExecutePendingConfig {},
+            &[],
+        )
+        .unwrap();
+
+        create_normal_order(&mut app, dca_addr.clone(), uluna.clone(), ukrw.clone());
+        add_tip_balance(&mut app, dca_addr.clone());
+
+        let purchase = |app: &mut App, hops: Vec<SwapOperation>, venue: &str| {
+            app.execute_contract(
+                Addr::unchecked("bot_user"),
+                dca_addr.clone(),
+                &ExecuteMsg::PerformDcaPurchase {
+                    user: mock_creator().sender.into_string(),
+                    id: 1,
+                    hops: Some(hops),
+                    fee_redeem: vec![],
+                    venue: Some(venue.to_string()),
+                },
+                &[],
+            )
+        };
+        let balance = |app: &App| {
+            app.wrap()
+                .query_balance(mock_creator().sender, "ukrw")
+                .unwrap()
+                .amount
+        };
+
+        // cannot perform a route of several hops through the pair
+        let two_hops = vec![
+            SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: ujpy.clone(),
+            },
+            SwapOperation::AstroSwap {
+                offer_asset_info: ujpy,
+                ask_asset_info: ukrw.clone(),
+            },
+        ];
+        let err = purchase(&mut app, two_hops.clone(), "luna_krw").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::InvalidPairVenueRoute {}
+        );
+
+        // purchase through the pair
+        purchase(
+            &mut app,
+            vec![SwapOperation::AstroSwap {
+                offer_asset_info: uluna,
+                ask_asset_info: ukrw,
+            }],
+            "luna_krw",
+        )
+        .unwrap();
+        let pair_purchase = balance(&app);
+        assert!(!pair_purchase.is_zero());
+
+        // purchase through the mock dex router once the next purchase is due
+        app.update_block(|b| b.time = b.time.plus_seconds(NORMAL_ORDER_INTERVAL));
+        purchase(&mut app, two_hops, "dex").unwrap();
+        assert!(balance(&app) > pair_purchase);
+
+        // cannot perform a purchase through a venue that is not whitelisted
+        app.update_block(|b| b.time = b.time.plus_seconds(NORMAL_ORDER_INTERVAL));
+        let err = purchase(&mut app, vec![], "unknown").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NonexistentVenue {
+                name: "unknown".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn can_perform_purchase_through_mock_venues() {
+        let (mut app, dca_addr, ..) = instantiate(None);
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ujpy = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let config: Config = app
+            .wrap()
+            .query_wasm_smart(&dca_addr, &astroport_dca::dca::QueryMsg::Config {})
+            .unwrap();
+
+        // mock venues that swap at a fixed rate, where one of the routers swaps through the pairs
+        // of a factory that does not exist
+        let mock_router_id = store_mock_router_code(&mut app);
+        let mock_pair_id = store_mock_pair_code(&mut app);
+        let mut instantiate_router = |factory_addr: &str, rate: u64| {
+            app.instantiate_contract(
+                mock_router_id,
+                mock_creator().sender,
+                &MockRouterInstantiateMsg {
+                    factory_addr: factory_addr.to_string(),
+                    rate: Decimal::from_ratio(rate, 1u128),
+                },
+                &[],
+                "mock dex router",
+                None,
+            )
+            .unwrap()
+        };
+        let dex_router_addr = instantiate_router(config.factory_addr.as_str(), 2);
+        let unknown_router_addr = instantiate_router("unknown_factory", 2);
+        let mut instantiate_pair = |pair_type: PairType, rate: u64| {
+            app.instantiate_contract(
+                mock_pair_id,
+                mock_creator().sender,
+                &MockPairInstantiateMsg {
+                    asset_infos: [uluna.clone(), ukrw.clone()],
+                    pair_type,
+                    rate: Decimal::from_ratio(rate, 1u128),
+                },
+                &[],
+                "mock pair",
+                None,
+            )
+            .unwrap()
+        };
+        let xyk_pair_addr = instantiate_pair(PairType::Xyk {}, 3);
+        let custom_pair_addr = instantiate_pair(PairType::Custom("mock".to_string()), 3);
+
+        // the venues pay out ukrw from their own balance
+        for venue_addr in [&dex_router_addr, &xyk_pair_addr] {
+            app.init_modules(|router, _, storage| {
+                router
+                    .bank
+                    .init_balance(storage, venue_addr, coins(1_000_000, "ukrw"))
+                    .unwrap()
+            });
+        }
+
+        let venue = |name: &str, venue_type: SwapVenueType, contract_addr: &Addr| SwapVenue {
+            name: name.to_string(),
+            venue_type,
+            contract_addr: contract_addr.clone(),
+        };
+        app.execute_contract(
+            mock_creator().sender,
+            dca_addr.clone(),
+            &ExecuteMsg::UpdateConfig {
+                max_hops: None,
+                max_spread: None,
+                protocol_fee: None,
+                fee_collector: None,
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: Some(vec![
+                    venue("dex", SwapVenueType::Router {}, &dex_router_addr),
+                    venue("unknown", SwapVenueType::Router {}, &unknown_router_addr),
+                    venue("xyk_pair", SwapVenueType::AstroportPair {}, &xyk_pair_addr),
+                    venue(
+                        "custom_pair",
+                        SwapVenueType::AstroportPair {},
+                        &custom_pair_addr,
+                    ),
+                ]),
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
+            },
+            &[],
+        )
+        .unwrap();
+        app.execute_contract(
+            mock_creator().sender,
+            dca_addr.clone(),
+            &ExecuteMsg::ExecutePendingConfig {},
+            &[],
+        )
+        .unwrap();
+
+        create_normal_order(&mut app, dca_addr.clone(), uluna.clone(), ukrw.clone());
+        add_tip_balance(&mut app, dca_addr.clone());
+
+        let purchase = |app: &mut App, hops: Vec<SwapOperation>, venue: &str| {
+            app.execute_contract(
+                Addr::unchecked("bot_user"),
+                dca_addr.clone(),
+                &ExecuteMsg::PerformDcaPurchase {
+                    user: mock_creator().sender.into_string(),
+                    id: 1,
+                    hops: Some(hops),
+                    fee_redeem: vec![],
+                    venue: Some(venue.to_string()),
+                },
+                &[],
+            )
+        };
+        let balance = |app: &App| {
+            app.wrap()
+                .query_balance(mock_creator().sender, "ukrw")
+                .unwrap()
+                .amount
+        };
+        let swap = |offer: &AssetInfo, ask: &AssetInfo| SwapOperation::AstroSwap {
+            offer_asset_info: offer.clone(),
+            ask_asset_info: ask.clone(),
+        };
+        let two_hops = vec![swap(&uluna, &ujpy), swap(&ujpy, &ukrw)];
+
+        // cannot swap through a router whose factory does not have the pairs of the route
+        let err = purchase(&mut app, two_hops.clone(), "unknown").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::NonexistentPair {
+                offer: uluna.clone(),
+                ask: ujpy
+            }
+        );
+
+        // cannot swap through a pair of a pair type that is not allowed
+        let err = purchase(&mut app, vec![swap(&uluna, &ukrw)], "custom_pair").unwrap_err();
+        assert_eq!(
+            err.downcast::<ContractError>().unwrap(),
+            ContractError::PairTypeNotAllowed {
+                pair_type: PairType::Custom("mock".to_string())
+            }
+        );
+
+        // purchase through the router of the other dex at its rate
+        purchase(&mut app, two_hops, "dex").unwrap();
+        assert_eq!(balance(&app), Uint128::new(20_000));
+
+        // purchase through the pair at its rate once the next purchase is due
+        app.update_block(|b| b.time = b.time.plus_seconds(NORMAL_ORDER_INTERVAL));
+        purchase(&mut app, vec![swap(&uluna, &ukrw)], "xyk_pair").unwrap();
+        assert_eq!(balance(&app), Uint128::new(50_000));
+    }
+
+    #[test]
+    fn can_perform_token_purchase() {
+        let (mut app, dca_addr, cw20_addr) = instantiate(None);
+
+        // increase allowance for order
+        app.execute_contract(
+            mock_creator().sender,
+            cw20_addr.clone(),
+            &cw20_base::msg::ExecuteMsg::IncreaseAllowance {
+                spender: dca_addr.clone().into_string(),
+                amount: Uint128::new(100_000),
+                expires: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        create_normal_order(
+            &mut app,
+            dca_addr.clone(),
+            AssetInfo::Token {
+                contract_addr: cw20_addr.clone(),
+            },
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        );
+
+        add_tip_balance(&mut app, dca_addr.clone());
+
+        let bot_user = Addr::unchecked("bot_user");
+
+        // perform purchase
+        app.execute_contract(
+            bot_user.clone(),
+            dca_addr.clone(),
+            &ExecuteMsg::PerformDcaPurchase {
+                user: mock_creator().sender.into_string(),
+                id: 1,
+                hops: Some(vec![
+                    SwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::Token {
+                            contract_addr: cw20_addr.clone(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "ujpy".to_string(),
+                        },
+                    },
+                    SwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "ujpy".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    },
+                ]),
+                fee_redeem: vec![Asset {
+                    amount: Uint128::new(30_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+                venue: None,
+            },
+            &[],
+        )
+        .unwrap();
+
+        // should have removed from tip balance
+        let user_config = read_map(&app, dca_addr.clone(), &mock_creator().sender, USER_CONFIG);
+        // 150,000 start - 30,000 fee redeem
+        assert_eq!(
+            user_config.tip_balance,
+            vec![Asset {
+                amount: Uint128::new(120_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string()
+                }
+            }]
+        );
+
+        // should have added to bot balance
+        let bot_balance = app.wrap().query_balance(bot_user, "uluna").unwrap();
+        assert_eq!(bot_balance, coin(30_000, "uluna"));
+
+        // should have spent the initial asset
+        let user_balance: cw20::BalanceResponse = app
+            .wrap()
+            .query_wasm_smart(
                 cw20_addr.clone(),
                 &cw20_base::msg::QueryMsg::Balance {
                     address: mock_creator().sender.into_string(),
@@ -823,6 +1811,12 @@ mod test {
             target_asset: AssetInfo::NativeToken {
                 denom: "uluna".to_string(),
             },
+            referral: None,
+            preferred_executor: None,
+            exclusivity_seconds: 0,
+            require_canonical_route: false,
+            max_price_impact: None,
+            deposit: None,
         }];
         assert_eq!(user_dca_orders, expected_orders);
     }
@@ -854,13 +1848,14 @@ mod test {
                 &ExecuteMsg::PerformDcaPurchase {
                     user: mock_creator().sender.into_string(),
                     id: 1,
-                    hops: vec![],
+                    hops: Some(vec![]),
                     fee_redeem: vec![Asset {
                         amount: Uint128::new(30_000),
                         info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    venue: None,
                 },
                 &[],
             )
@@ -899,7 +1894,7 @@ mod test {
                 &ExecuteMsg::PerformDcaPurchase {
                     user: mock_creator().sender.into_string(),
                     id: 1,
-                    hops: vec![
+                    hops: Some(vec![
                         SwapOperation::AstroSwap {
                             offer_asset_info: AssetInfo::NativeToken {
                                 denom: "uluna".to_string(),
@@ -924,13 +1919,14 @@ mod test {
                                 denom: "ujpy".to_string(),
                             },
                         },
-                    ],
+                    ]),
                     fee_redeem: vec![Asset {
                         amount: Uint128::new(30_000),
                         info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    venue: None,
                 },
                 &[],
             )
@@ -969,7 +1965,7 @@ mod test {
                 &ExecuteMsg::PerformDcaPurchase {
                     user: mock_creator().sender.into_string(),
                     id: 1,
-                    hops: vec![
+                    hops: Some(vec![
                         SwapOperation::AstroSwap {
                             offer_asset_info: AssetInfo::NativeToken {
                                 denom: "uluna".to_string(),
@@ -994,13 +1990,14 @@ mod test {
                                 denom: "ukrw".to_string(),
                             },
                         },
-                    ],
+                    ]),
                     fee_redeem: vec![Asset {
                         amount: Uint128::new(30_000),
                         info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    venue: None,
                 },
                 &[],
             )
@@ -1021,7 +2018,7 @@ mod test {
                 &ExecuteMsg::PerformDcaPurchase {
                     user: mock_creator().sender.into_string(),
                     id: 1,
-                    hops: vec![
+                    hops: Some(vec![
                         SwapOperation::NativeSwap {
                             offer_denom: "uluna".to_string(),
                             ask_denom: "ugbp".to_string(),
@@ -1034,13 +2031,14 @@ mod test {
                                 denom: "ukrw".to_string(),
                             },
                         },
-                    ],
+                    ]),
                     fee_redeem: vec![Asset {
                         amount: Uint128::new(30_000),
                         info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    venue: None,
                 },
                 &[],
             )
@@ -1055,8 +2053,8 @@ mod test {
     }
 
     #[test]
-    fn does_check_tip_is_divisible() {
-        let (mut app, dca_addr, ..) = instantiate(None);
+    fn does_check_combined_tip_size() {
+        let (mut app, dca_addr, cw20_addr) = instantiate(None);
 
         create_normal_order(
             &mut app,
@@ -1081,7 +2079,7 @@ mod test {
                 &ExecuteMsg::PerformDcaPurchase {
                     user: mock_creator().sender.into_string(),
                     id: 1,
-                    hops: vec![
+                    hops: Some(vec![
                         SwapOperation::AstroSwap {
                             offer_asset_info: AssetInfo::NativeToken {
                                 denom: "uluna".to_string(),
@@ -1098,34 +2096,120 @@ mod test {
                                 denom: "ukrw".to_string(),
                             },
                         },
-                    ],
-                    fee_redeem: vec![Asset {
-                        amount: Uint128::new(35_000),
-                        info: AssetInfo::NativeToken {
-                            denom: "uluna".to_string(),
+                    ]),
+                    fee_redeem: vec![
+                        Asset {
+                            amount: Uint128::new(20_000),
+                            info: AssetInfo::NativeToken {
+                                denom: "uluna".to_string(),
+                            },
                         },
-                    }],
+                        Asset {
+                            amount: Uint128::new(15_000),
+                            info: AssetInfo::Token {
+                                contract_addr: cw20_addr,
+                            },
+                        },
+                    ],
+                    venue: None,
                 },
                 &[],
             )
             .unwrap_err();
 
+        // should error because 2/3 of the uluna tip and 1/2 of the cw20 tip is more than the full tip
         assert_eq!(
             res.downcast::<ContractError>().unwrap(),
-            ContractError::IndivisibleDeposit {}
+            ContractError::RedeemTipExceedsTip {
+                requested: Decimal::from_ratio(20_000u128, 30_000u128) + Decimal::percent(50)
+            }
         );
     }
 
     #[test]
-    fn does_check_requested_fee_whitelisted() {
-        let (mut app, dca_addr, ..) = instantiate(None);
+    fn does_sum_tip_fractions_exactly() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
 
-        create_normal_order(
-            &mut app,
-            dca_addr.clone(),
-            AssetInfo::NativeToken {
-                denom: "uluna".to_string(),
-            },
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![
+                Asset {
+                    amount: Uint128::new(3),
+                    info: uluna.clone(),
+                },
+                Asset {
+                    amount: Uint128::new(30_000_000_000_000_000_000),
+                    info: uusd.clone(),
+                },
+            ],
+            vec![],
+        );
+
+        let perform_purchase = |uusd_amount: u128| ExecuteMsg::PerformDcaPurchase {
+            user: mock_creator().sender.into_string(),
+            id: 1,
+            hops: Some(vec![SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+            }]),
+            fee_redeem: vec![
+                Asset {
+                    amount: Uint128::new(1),
+                    info: uluna.clone(),
+                },
+                Asset {
+                    amount: Uint128::new(uusd_amount),
+                    info: uusd.clone(),
+                },
+            ],
+            venue: None,
+        };
+
+        // a third of the uluna tip and just over two thirds of the uusd tip exceed the full tip,
+        // even though the fractions rounded down to decimals do not
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_creator(),
+            perform_purchase(20_000_000_000_000_000_001),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::RedeemTipExceedsTip {
+                requested: Decimal::from_str("0.999999999999999999").unwrap()
+            }
+        );
+
+        // exactly the full tip passes the check, and only fails on the empty tip balance
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_creator(),
+            perform_purchase(20_000_000_000_000_000_000),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InsufficientTipBalance {});
+    }
+
+    #[test]
+    fn does_check_requested_fee_whitelisted() {
+        let (mut app, dca_addr, ..) = instantiate(None);
+
+        create_normal_order(
+            &mut app,
+            dca_addr.clone(),
+            AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
             AssetInfo::NativeToken {
                 denom: "ukrw".to_string(),
             },
@@ -1143,7 +2227,7 @@ mod test {
                 &ExecuteMsg::PerformDcaPurchase {
                     user: mock_creator().sender.into_string(),
                     id: 1,
-                    hops: vec![
+                    hops: Some(vec![
                         SwapOperation::AstroSwap {
                             offer_asset_info: AssetInfo::NativeToken {
                                 denom: "uluna".to_string(),
@@ -1160,13 +2244,14 @@ mod test {
                                 denom: "ukrw".to_string(),
                             },
                         },
-                    ],
+                    ]),
                     fee_redeem: vec![Asset {
                         amount: Uint128::new(30_000),
                         info: AssetInfo::NativeToken {
                             denom: "ujpy".to_string(),
                         },
                     }],
+                    venue: None,
                 },
                 &[],
             )
@@ -1185,6 +2270,7 @@ mod test {
     #[test]
     fn does_not_panic_for_zero_asset() {
         let mut deps = mock_dependencies();
+        mock_factory(&mut deps.querier, &Addr::unchecked("factory"));
 
         let cw20_addr = Addr::unchecked("cw20_addr");
 
@@ -1192,19 +2278,34 @@ mod test {
             .save(
                 &mut deps.storage,
                 &Config {
+                    owner: Addr::unchecked("owner"),
                     router_addr: Addr::unchecked("router"),
                     factory_addr: Addr::unchecked("factory"),
                     max_hops: 4,
                     max_spread: Decimal::from_str("0.05").unwrap(),
-                    whitelisted_fee_assets: vec![Asset {
+                    whitelisted_fee_assets: per_hop_fee_assets(vec![Asset {
                         amount: Uint128::new(0),
                         info: AssetInfo::Token {
                             contract_addr: cw20_addr.clone(),
                         },
-                    }],
+                    }]),
                     whitelisted_tokens: vec![AssetInfo::NativeToken {
                         denom: "ujpy".to_string(),
                     }],
+                    protocol_fee: Decimal::zero(),
+                    fee_collector: None,
+                    max_referral_fee_bps: 0,
+                    keeper_config: None,
+                    allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+                    pair_type_max_spreads: vec![],
+                    venues: vec![],
+                    guardian: None,
+                    config_timelock: 86_400,
+                    allowed_initial_assets: AllowedAssets::All {},
+                    allowed_target_assets: AllowedAssets::All {},
+                    min_dca_amounts: vec![],
+                    max_orders_per_user: 50,
+                    order_deposit: None,
                 },
             )
             .unwrap();
@@ -1216,7 +2317,7 @@ mod test {
             ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -1233,45 +2334,57 @@ mod test {
                             denom: "ukrw".to_string(),
                         },
                     },
-                ],
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::Token {
                         contract_addr: cw20_addr,
                     },
                 }],
+                venue: None,
             },
         )
         .unwrap_err();
 
         assert_eq!(
             res,
-            ContractError::Std(StdError::DivideByZero {
-                source: DivideByZeroError {
-                    operand: "30000".to_string()
-                }
-            })
+            ContractError::RedeemTipTooLarge {
+                requested: Asset {
+                    amount: Uint128::new(30_000),
+                    info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked("cw20_addr"),
+                    },
+                },
+                tip: Asset {
+                    amount: Uint128::zero(),
+                    info: AssetInfo::Token {
+                        contract_addr: Addr::unchecked("cw20_addr"),
+                    },
+                },
+            }
         );
     }
 
     #[test]
     fn does_check_tip_redeem_size() {
         let mut deps = mock_dependencies();
+        mock_factory(&mut deps.querier, &Addr::unchecked("factory"));
 
         CONFIG
             .save(
                 &mut deps.storage,
                 &Config {
+                    owner: Addr::unchecked("owner"),
                     router_addr: Addr::unchecked("router"),
                     factory_addr: Addr::unchecked("factory"),
                     max_hops: 4,
                     max_spread: Decimal::from_str("0.05").unwrap(),
-                    whitelisted_fee_assets: vec![Asset {
+                    whitelisted_fee_assets: per_hop_fee_assets(vec![Asset {
                         amount: Uint128::new(15_000),
                         info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
-                    }],
+                    }]),
                     whitelisted_tokens: vec![
                         AssetInfo::NativeToken {
                             denom: "ujpy".to_string(),
@@ -1280,6 +2393,20 @@ mod test {
                             denom: "ukrw".to_string(),
                         },
                     ],
+                    protocol_fee: Decimal::zero(),
+                    fee_collector: None,
+                    max_referral_fee_bps: 0,
+                    keeper_config: None,
+                    allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+                    pair_type_max_spreads: vec![],
+                    venues: vec![],
+                    guardian: None,
+                    config_timelock: 86_400,
+                    allowed_initial_assets: AllowedAssets::All {},
+                    allowed_target_assets: AllowedAssets::All {},
+                    min_dca_amounts: vec![],
+                    max_orders_per_user: 50,
+                    order_deposit: None,
                 },
             )
             .unwrap();
@@ -1291,7 +2418,7 @@ mod test {
             ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -1316,13 +2443,14 @@ mod test {
                             denom: "ugbp".to_string(),
                         },
                     },
-                ],
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(60_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
         )
         .unwrap_err();
@@ -1331,8 +2459,18 @@ mod test {
         assert_eq!(
             res,
             ContractError::RedeemTipTooLarge {
-                requested: Uint128::new(4),
-                performed: Uint128::new(3)
+                requested: Asset {
+                    amount: Uint128::new(60_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                },
+                tip: Asset {
+                    amount: Uint128::new(45_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                },
             }
         );
     }
@@ -1340,24 +2478,40 @@ mod test {
     #[test]
     fn does_error_if_not_enough_balance() {
         let mut deps = mock_dependencies();
+        mock_factory(&mut deps.querier, &Addr::unchecked("factory"));
 
         CONFIG
             .save(
                 &mut deps.storage,
                 &Config {
+                    owner: Addr::unchecked("owner"),
                     router_addr: Addr::unchecked("router"),
                     factory_addr: Addr::unchecked("factory"),
                     max_hops: 4,
                     max_spread: Decimal::from_str("0.05").unwrap(),
-                    whitelisted_fee_assets: vec![Asset {
+                    whitelisted_fee_assets: per_hop_fee_assets(vec![Asset {
                         amount: Uint128::new(15_000),
                         info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
                         },
-                    }],
+                    }]),
                     whitelisted_tokens: vec![AssetInfo::NativeToken {
                         denom: "ujpy".to_string(),
                     }],
+                    protocol_fee: Decimal::zero(),
+                    fee_collector: None,
+                    max_referral_fee_bps: 0,
+                    keeper_config: None,
+                    allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+                    pair_type_max_spreads: vec![],
+                    venues: vec![],
+                    guardian: None,
+                    config_timelock: 86_400,
+                    allowed_initial_assets: AllowedAssets::All {},
+                    allowed_target_assets: AllowedAssets::All {},
+                    min_dca_amounts: vec![],
+                    max_orders_per_user: 50,
+                    order_deposit: None,
                 },
             )
             .unwrap();
@@ -1376,6 +2530,7 @@ mod test {
                         },
                         amount: Uint128::new(25_000),
                     }],
+                    max_price_impact: None,
                 },
             )
             .unwrap();
@@ -1387,7 +2542,7 @@ mod test {
             ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -1404,13 +2559,14 @@ mod test {
                             denom: "ugbp".to_string(),
                         },
                     },
-                ],
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
         )
         .unwrap_err();
@@ -1468,7 +2624,7 @@ mod test {
             &ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -1485,13 +2641,14 @@ mod test {
                             denom: "ukrw".to_string(),
                         },
                     },
-                ],
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::Token {
                         contract_addr: cw20_addr.clone(),
                     },
                 }],
+                venue: None,
             },
             &[],
         )
@@ -1540,6 +2697,7 @@ mod test {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    max_price_impact: None,
                 },
             )
             .unwrap();
@@ -1562,6 +2720,12 @@ mod test {
                     },
                     interval: 500,
                     last_purchase: env.block.time.seconds(),
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
                 }],
             )
             .unwrap();
@@ -1574,7 +2738,7 @@ mod test {
             ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -1591,13 +2755,117 @@ mod test {
                             denom: "ukrw".to_string(),
                         },
                     },
-                ],
+                ]),
+                fee_redeem: vec![Asset {
+                    amount: Uint128::new(30_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+                venue: None,
+            },
+        )
+        .unwrap_err();
+
+        assert_eq!(res, ContractError::PurchaseTooEarly {});
+    }
+
+    #[test]
+    fn does_not_panic_if_interval_too_big() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![AssetInfo::NativeToken {
+                denom: "ujpy".to_string(),
+            }],
+        );
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(45_000),
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(100_000),
+                    },
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    interval: u64::MAX,
+                    last_purchase: env.block.time.seconds(),
+                    referral: None,
+                    preferred_executor: Some(Addr::unchecked("executor")),
+                    exclusivity_seconds: 1_000,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        // the next purchase saturates instead of overflowing, so it is never due
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            ExecuteMsg::PerformDcaPurchase {
+                user: mock_creator().sender.into_string(),
+                id: 1,
+                hops: Some(vec![
+                    SwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "ujpy".to_string(),
+                        },
+                    },
+                    SwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "ujpy".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                    },
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
         )
         .unwrap_err();
@@ -1635,6 +2903,7 @@ mod test {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    max_price_impact: None,
                 },
             )
             .unwrap();
@@ -1657,6 +2926,12 @@ mod test {
                     },
                     interval: 500,
                     last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
                 }],
             )
             .unwrap();
@@ -1669,7 +2944,7 @@ mod test {
             ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -1686,13 +2961,14 @@ mod test {
                             denom: "ukrw".to_string(),
                         },
                     },
-                ],
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
         )
         .unwrap_err();
@@ -1730,6 +3006,7 @@ mod test {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    max_price_impact: None,
                 },
             )
             .unwrap();
@@ -1752,6 +3029,12 @@ mod test {
                     },
                     interval: 500,
                     last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
                 }],
             )
             .unwrap();
@@ -1764,7 +3047,7 @@ mod test {
             ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: vec![
+                hops: Some(vec![
                     SwapOperation::AstroSwap {
                         offer_asset_info: AssetInfo::NativeToken {
                             denom: "uluna".to_string(),
@@ -1781,13 +3064,14 @@ mod test {
                             denom: "ugbp".to_string(),
                         },
                     },
-                ],
+                ]),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
         )
         .unwrap_err();
@@ -1825,6 +3109,7 @@ mod test {
                             denom: "uluna".to_string(),
                         },
                     }],
+                    max_price_impact: None,
                 },
             )
             .unwrap();
@@ -1847,6 +3132,12 @@ mod test {
                     },
                     interval: 500,
                     last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
                 }],
             )
             .unwrap();
@@ -1878,13 +3169,14 @@ mod test {
             ExecuteMsg::PerformDcaPurchase {
                 user: mock_creator().sender.into_string(),
                 id: 1,
-                hops: hops.clone(),
+                hops: Some(hops.clone()),
                 fee_redeem: vec![Asset {
                     amount: Uint128::new(30_000),
                     info: AssetInfo::NativeToken {
                         denom: "uluna".to_string(),
                     },
                 }],
+                venue: None,
             },
         )
         .unwrap();
@@ -1920,4 +3212,1449 @@ mod test {
                 ])
         );
     }
+
+    #[test]
+    fn does_use_pair_type_spread() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        // xyk pairs default to a wider spread than the contract wide default
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.pair_type_max_spreads = vec![PairTypeSpread {
+                    pair_type: PairType::Xyk {},
+                    max_spread: Decimal::percent(10),
+                }];
+                Ok(config)
+            })
+            .unwrap();
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(45_000),
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        let hops = vec![SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+        }];
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: mock_creator().sender.into_string(),
+                id: 1,
+                hops: Some(hops.clone()),
+                fee_redeem: vec![Asset {
+                    amount: Uint128::new(15_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+                venue: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages[1].msg,
+            WasmMsg::Execute {
+                contract_addr: "router".to_string(),
+                funds: coins(10_000, "uluna"),
+                msg: to_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                    operations: hops,
+                    minimum_receive: None,
+                    to: Some(mock_creator().sender.into_string()),
+                    max_spread: Some(Decimal::percent(10)),
+                })
+                .unwrap(),
+            }
+            .into()
+        );
+    }
+
+    #[test]
+    fn does_limit_price_impact() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        // the spot price is 2 ukrw per uluna, while the purchase only returns 18_000 ukrw for
+        // 10_000 uluna, a price impact of 10%
+        let reserves = [
+            Asset {
+                amount: Uint128::new(1_000_000),
+                info: uluna.clone(),
+            },
+            Asset {
+                amount: Uint128::new(2_000_000),
+                info: ukrw.clone(),
+            },
+        ];
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } => match contract_addr.as_str() {
+                "factory" => match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    _ => unimplemented!(),
+                },
+                "pair" => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&PoolResponse {
+                        assets: reserves.clone(),
+                        total_share: Uint128::new(1_000_000),
+                    })
+                    .unwrap(),
+                )),
+                "router" => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&SimulateSwapOperationsResponse {
+                        amount: Uint128::new(18_000),
+                    })
+                    .unwrap(),
+                )),
+                _ => unimplemented!(),
+            },
+            _ => unimplemented!(),
+        });
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(45_000),
+                        info: uluna.clone(),
+                    }],
+                    max_price_impact: Some(Decimal::percent(5)),
+                },
+            )
+            .unwrap();
+
+        let save_order = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                          max_price_impact: Option<Decimal>| {
+            USER_DCA
+                .save(
+                    &mut deps.storage,
+                    &mock_creator().sender,
+                    &vec![DcaInfo {
+                        id: 1,
+                        dca_amount: Uint128::new(10_000),
+                        initial_asset: Asset {
+                            info: uluna.clone(),
+                            amount: Uint128::new(20_000),
+                        },
+                        target_asset: ukrw.clone(),
+                        interval: 500,
+                        last_purchase: 0,
+                        referral: None,
+                        preferred_executor: None,
+                        exclusivity_seconds: 0,
+                        require_canonical_route: false,
+                        max_price_impact,
+                        deposit: None,
+                    }],
+                )
+                .unwrap()
+        };
+
+        let msg = ExecuteMsg::PerformDcaPurchase {
+            user: mock_creator().sender.into_string(),
+            id: 1,
+            hops: Some(vec![SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: ukrw.clone(),
+            }]),
+            fee_redeem: vec![Asset {
+                amount: Uint128::new(15_000),
+                info: uluna.clone(),
+            }],
+            venue: None,
+        };
+
+        // the maximum price impact of the user is exceeded
+        save_order(&mut deps, None);
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            msg.clone(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::PriceImpactTooHigh {
+                price_impact: Decimal::percent(10),
+                max_price_impact: Decimal::percent(5),
+            }
+        );
+
+        // the order overrides the maximum price impact of the user
+        save_order(&mut deps, Some(Decimal::percent(15)));
+        execute(deps.as_mut(), mock_env(), mock_info("bot_addr", &[]), msg).unwrap();
+    }
+
+    #[test]
+    fn does_limit_price_impact_of_batched_swap() {
+        let (mut app, dca_addr, ..) = instantiate(None);
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ujpy = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+
+        // each order alone has a price impact of about 2.3% in the uluna/ujpy pool, while the
+        // batched swap of both has a price impact of about 4.1%
+        for _ in 0..2 {
+            app.execute_contract(
+                mock_creator().sender,
+                dca_addr.clone(),
+                &ExecuteMsg::CreateDcaOrder {
+                    initial_asset: Asset {
+                        amount: Uint128::new(100_000),
+                        info: uluna.clone(),
+                    },
+                    target_asset: ujpy.clone(),
+                    interval: NORMAL_ORDER_INTERVAL,
+                    dca_amount: Uint128::new(10_000),
+                    first_purchase: None,
+                    referrer: None,
+                    referral_fee_bps: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: None,
+                    require_canonical_route: None,
+                    max_price_impact: Some(Decimal::percent(3)),
+                },
+                &coins(100_000, "uluna"),
+            )
+            .unwrap();
+        }
+
+        add_tip_balance(&mut app, dca_addr.clone());
+
+        let purchase = |id: u64| BatchPurchase {
+            user: mock_creator().sender.into_string(),
+            id,
+            hops: Some(vec![SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: ujpy.clone(),
+            }]),
+            fee_redeem: vec![Asset {
+                amount: Uint128::new(15_000),
+                info: uluna.clone(),
+            }],
+        };
+
+        let res = app
+            .execute_contract(
+                Addr::unchecked("bot_user"),
+                dca_addr.clone(),
+                &ExecuteMsg::PerformDcaPurchases {
+                    purchases: vec![purchase(1), purchase(2)],
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert!(matches!(
+            res.downcast::<ContractError>().unwrap(),
+            ContractError::PriceImpactTooHigh { max_price_impact, .. }
+                if max_price_impact == Decimal::percent(3)
+        ));
+
+        // the orders can still be purchased on their own
+        app.execute_contract(
+            Addr::unchecked("bot_user"),
+            dca_addr,
+            &ExecuteMsg::PerformDcaPurchases {
+                purchases: vec![purchase(1)],
+            },
+            &[],
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn does_take_protocol_fee() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.protocol_fee = Decimal::percent(1);
+                config.fee_collector = Some(Addr::unchecked("fee_collector"));
+                Ok(config)
+            })
+            .unwrap();
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(15_000),
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        let hops = vec![SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+        }];
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: mock_creator().sender.into_string(),
+                id: 1,
+                hops: Some(hops.clone()),
+                fee_redeem: vec![Asset {
+                    amount: Uint128::new(15_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+                venue: None,
+            },
+        )
+        .unwrap();
+
+        // 1% of the 10_000 uluna purchase should go to the fee collector
+        let expected_msgs: Vec<CosmosMsg> = vec![
+            BankMsg::Send {
+                amount: coins(15_000, "uluna"),
+                to_address: "bot_addr".to_string(),
+            }
+            .into(),
+            BankMsg::Send {
+                amount: coins(100, "uluna"),
+                to_address: "fee_collector".to_string(),
+            }
+            .into(),
+            WasmMsg::Execute {
+                contract_addr: "router".to_string(),
+                funds: coins(9_900, "uluna"),
+                msg: to_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                    operations: hops,
+                    minimum_receive: None,
+                    to: Some(mock_creator().sender.into_string()),
+                    max_spread: Some(Decimal::from_str("0.05").unwrap()),
+                })
+                .unwrap(),
+            }
+            .into(),
+        ];
+
+        assert_eq!(
+            res,
+            Response::new()
+                .add_messages(expected_msgs)
+                .add_attributes(vec![
+                    attr("action", "perform_dca_purchase"),
+                    attr("user", mock_creator().sender.into_string()),
+                    attr("id", "1"),
+                    attr("protocol_fee", "100uluna"),
+                ])
+        );
+
+        // the full dca amount is removed from the order
+        let orders = USER_DCA
+            .load(&deps.storage, &mock_creator().sender)
+            .unwrap();
+        assert_eq!(orders[0].initial_asset.amount, Uint128::new(10_000));
+
+        // the purchase and the tip are recorded for the bot
+        let stats = KEEPER_STATS
+            .load(&deps.storage, &Addr::unchecked("bot_addr"))
+            .unwrap();
+        assert_eq!(
+            stats,
+            KeeperStats {
+                purchases: 1,
+                tips: vec![Asset {
+                    amount: Uint128::new(15_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+                last_execution: mock_env().block.time.seconds(),
+            }
+        );
+
+        // the protocol fee is recorded in the purchase history
+        assert_eq!(
+            PURCHASE_HISTORY
+                .load(&deps.storage, (&mock_creator().sender, 1))
+                .unwrap(),
+            vec![PurchaseRecord {
+                offer_asset: Asset {
+                    amount: Uint128::new(9_900),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                },
+                return_asset: None,
+                executed_at: mock_env().block.time.seconds(),
+                matched: false,
+                protocol_fee: Some(Asset {
+                    amount: Uint128::new(100),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }),
+            }]
+        );
+    }
+
+    #[test]
+    fn does_pay_referrer() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(15_000),
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: Some(Referral {
+                        referrer: Addr::unchecked("frontend"),
+                        fee_bps: 25,
+                    }),
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        let hops = vec![SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+        }];
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: mock_creator().sender.into_string(),
+                id: 1,
+                hops: Some(hops.clone()),
+                fee_redeem: vec![Asset {
+                    amount: Uint128::new(15_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+                venue: None,
+            },
+        )
+        .unwrap();
+
+        // 0.25% of the 10_000 uluna purchase should go to the referrer
+        let expected_msgs: Vec<CosmosMsg> = vec![
+            BankMsg::Send {
+                amount: coins(15_000, "uluna"),
+                to_address: "bot_addr".to_string(),
+            }
+            .into(),
+            BankMsg::Send {
+                amount: coins(25, "uluna"),
+                to_address: "frontend".to_string(),
+            }
+            .into(),
+            WasmMsg::Execute {
+                contract_addr: "router".to_string(),
+                funds: coins(9_975, "uluna"),
+                msg: to_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                    operations: hops,
+                    minimum_receive: None,
+                    to: Some(mock_creator().sender.into_string()),
+                    max_spread: Some(Decimal::from_str("0.05").unwrap()),
+                })
+                .unwrap(),
+            }
+            .into(),
+        ];
+
+        assert_eq!(
+            res,
+            Response::new()
+                .add_messages(expected_msgs)
+                .add_attributes(vec![
+                    attr("action", "perform_dca_purchase"),
+                    attr("user", mock_creator().sender.into_string()),
+                    attr("id", "1"),
+                    attr("referrer", "frontend"),
+                    attr("referral_fee", "25uluna"),
+                ])
+        );
+
+        // the purchase is recorded for the referrer
+        let stats = REFERRER_STATS
+            .load(&deps.storage, &Addr::unchecked("frontend"))
+            .unwrap();
+        assert_eq!(
+            stats,
+            ReferrerStats {
+                purchases: 1,
+                volume: vec![Asset {
+                    amount: Uint128::new(10_000),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+                earnings: vec![Asset {
+                    amount: Uint128::new(25),
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn does_enforce_exclusivity() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(30_000),
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+
+        // the order becomes due at 500 and is exclusive to the users bot until 600
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: Some(Addr::unchecked("user_bot")),
+                    exclusivity_seconds: 100,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        let perform_purchase = || ExecuteMsg::PerformDcaPurchase {
+            user: mock_creator().sender.into_string(),
+            id: 1,
+            hops: Some(vec![SwapOperation::AstroSwap {
+                offer_asset_info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                ask_asset_info: AssetInfo::NativeToken {
+                    denom: "ukrw".to_string(),
+                },
+            }]),
+            fee_redeem: vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            venue: None,
+        };
+
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(550);
+
+        // other bots cannot perform the purchase during the exclusivity period
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::NotPreferredExecutor {
+                executor: Addr::unchecked("user_bot"),
+                until: 600,
+            }
+        );
+
+        // the preferred executor can
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("user_bot", &[]),
+            perform_purchase(),
+        )
+        .unwrap();
+
+        // once the next exclusivity period is over, any bot can perform the purchase
+        let mut env = mock_env();
+        env.block.time = Timestamp::from_seconds(1_150);
+
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("bot_addr", &[]),
+            perform_purchase(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn does_require_canonical_route() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![AssetInfo::NativeToken {
+                denom: "uusd".to_string(),
+            }],
+        );
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(30_000),
+                        info: uluna.clone(),
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: uluna.clone(),
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: ukrw.clone(),
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: true,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        let direct_route = vec![SwapOperation::AstroSwap {
+            offer_asset_info: uluna.clone(),
+            ask_asset_info: ukrw.clone(),
+        }];
+        let canonical_route = vec![
+            SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: uusd.clone(),
+            },
+            SwapOperation::AstroSwap {
+                offer_asset_info: uusd,
+                ask_asset_info: ukrw.clone(),
+            },
+        ];
+
+        let perform_purchase = |hops: Option<Vec<SwapOperation>>| ExecuteMsg::PerformDcaPurchase {
+            user: mock_creator().sender.into_string(),
+            id: 1,
+            hops,
+            fee_redeem: vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            venue: None,
+        };
+
+        // the order cannot be performed until a canonical route is registered
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(Some(direct_route.clone())),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonexistentRoute {});
+
+        ROUTES
+            .save(
+                &mut deps.storage,
+                route_key(&uluna, &ukrw),
+                &canonical_route,
+            )
+            .unwrap();
+
+        // other routes are rejected
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(Some(direct_route)),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonCanonicalRoute {});
+
+        // the canonical route is used if no route is specified
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(None),
+        )
+        .unwrap();
+
+        let expected_msg: CosmosMsg = WasmMsg::Execute {
+            contract_addr: "router".to_string(),
+            funds: vec![coin(10_000, "uluna")],
+            msg: to_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                operations: canonical_route,
+                minimum_receive: None,
+                to: Some(mock_creator().sender.into_string()),
+                max_spread: Some(Decimal::from_str("0.05").unwrap()),
+            })
+            .unwrap(),
+        }
+        .into();
+        assert!(res.messages.iter().any(|msg| msg.msg == expected_msg));
+    }
+
+    #[test]
+    fn does_validate_route() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: uluna.clone(),
+            }],
+            vec![uusd.clone()],
+        );
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: uluna.clone(),
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: ukrw.clone(),
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        let hop = |offer: &AssetInfo, ask: &AssetInfo| SwapOperation::AstroSwap {
+            offer_asset_info: offer.clone(),
+            ask_asset_info: ask.clone(),
+        };
+
+        let perform_purchase = |hops: Vec<SwapOperation>| ExecuteMsg::PerformDcaPurchase {
+            user: mock_creator().sender.into_string(),
+            id: 1,
+            hops: Some(hops),
+            fee_redeem: vec![],
+            venue: None,
+        };
+
+        // the first hop has to offer the initial asset
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(vec![hop(&uusd, &ukrw)]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InvalidFirstHop {
+                initial_asset: uluna.clone()
+            }
+        );
+
+        // each hop has to offer the asset returned by the previous hop
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(vec![hop(&uluna, &uusd), hop(&uluna, &ukrw)]),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::DisconnectedHop { hop: 1 });
+
+        // the pairs have to be of an allowed pair type
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.allowed_pair_types = vec![PairType::Stable {}];
+                Ok(config)
+            })
+            .unwrap();
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(vec![hop(&uluna, &ukrw)]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::PairTypeNotAllowed {
+                pair_type: PairType::Xyk {}
+            }
+        );
+
+        // the pairs have to exist
+        mock_factory(&mut deps.querier, &Addr::unchecked("other_factory"));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            perform_purchase(vec![hop(&uluna, &ukrw)]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::NonexistentPair {
+                offer: uluna,
+                ask: ukrw
+            }
+        );
+    }
+
+    #[test]
+    fn does_discover_route_for_swap_amount() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.protocol_fee = Decimal::percent(1);
+                config.fee_collector = Some(Addr::unchecked("fee_collector"));
+                Ok(config)
+            })
+            .unwrap();
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: Some(Referral {
+                        referrer: Addr::unchecked("frontend"),
+                        fee_bps: 25,
+                    }),
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        // routes can only be simulated for the 9_875 uluna left after the 1% protocol fee and the
+        // 0.25% referral fee
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+                let asset_infos = match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => asset_infos,
+                    _ => unimplemented!(),
+                };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&PairInfo {
+                        asset_infos,
+                        contract_addr: Addr::unchecked("pair"),
+                        liquidity_token: Addr::unchecked("lp_token"),
+                        pair_type: PairType::Xyk {},
+                    })
+                    .unwrap(),
+                ))
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "router" => {
+                match from_binary(msg).unwrap() {
+                    astroport::router::QueryMsg::SimulateSwapOperations {
+                        offer_amount, ..
+                    } if offer_amount == Uint128::new(9_875) => {
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&SimulateSwapOperationsResponse {
+                                amount: offer_amount,
+                            })
+                            .unwrap(),
+                        ))
+                    }
+                    _ => SystemResult::Ok(ContractResult::Err("Invalid amount".to_string())),
+                }
+            }
+            _ => unimplemented!(),
+        });
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bot_addr", &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: mock_creator().sender.into_string(),
+                id: 1,
+                hops: None,
+                fee_redeem: vec![],
+                venue: None,
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages.last().unwrap().msg,
+            CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: "router".to_string(),
+                funds: coins(9_875, "uluna"),
+                msg: to_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                    operations: vec![SwapOperation::AstroSwap {
+                        offer_asset_info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        ask_asset_info: AssetInfo::NativeToken {
+                            denom: "ukrw".to_string(),
+                        },
+                    }],
+                    minimum_receive: None,
+                    to: Some(mock_creator().sender.into_string()),
+                    max_spread: Some(Decimal::from_str("0.05").unwrap()),
+                })
+                .unwrap(),
+            })
+        );
+    }
+
+    #[test]
+    fn does_record_keeper_swap() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let hops = vec![SwapOperation::AstroSwap {
+            offer_asset_info: uluna.clone(),
+            ask_asset_info: ukrw.clone(),
+        }];
+        let bond = Asset {
+            amount: Uint128::new(1_000_000),
+            info: uluna.clone(),
+        };
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.keeper_config = Some(KeeperConfig {
+                    enabled: true,
+                    bond: bond.clone(),
+                    slash_threshold: Decimal::percent(10),
+                    challenge_period: 3_600,
+                });
+                Ok(config)
+            })
+            .unwrap();
+        KEEPERS
+            .save(
+                &mut deps.storage,
+                &Addr::unchecked("bot_addr"),
+                &Keeper {
+                    bond,
+                    unbonding_since: None,
+                },
+            )
+            .unwrap();
+        ROUTES
+            .save(&mut deps.storage, route_key(&uluna, &ukrw), &hops)
+            .unwrap();
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(45_000),
+                        info: uluna.clone(),
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &mock_creator().sender,
+                &vec![DcaInfo {
+                    id: 1,
+                    dca_amount: Uint128::new(10_000),
+                    initial_asset: Asset {
+                        info: uluna.clone(),
+                        amount: Uint128::new(20_000),
+                    },
+                    target_asset: ukrw.clone(),
+                    interval: 500,
+                    last_purchase: 0,
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        // the pair of the canonical route was observed 1_000 seconds before the purchase
+        let anchor = PriceObservation {
+            price_cumulative: Uint128::zero(),
+            observed_at: env.block.time.seconds() - 1_000,
+        };
+        PRICE_OBSERVATIONS
+            .save(
+                &mut deps.storage,
+                (&Addr::unchecked("pair"), uluna.to_string()),
+                &PriceObservations {
+                    anchor: None,
+                    latest: anchor.clone(),
+                },
+            )
+            .unwrap();
+
+        let pool = [
+            Asset {
+                info: uluna.clone(),
+                amount: Uint128::new(1_000_000),
+            },
+            Asset {
+                info: ukrw.clone(),
+                amount: Uint128::new(2_000_000),
+            },
+        ];
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+                match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    _ => unimplemented!(),
+                }
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "pair" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&CumulativePricesResponse {
+                        assets: pool.clone(),
+                        total_share: Uint128::new(1_000_000),
+                        price0_cumulative_last: Uint128::new(2_000_000_000),
+                        price1_cumulative_last: Uint128::new(500_000_000),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => unimplemented!(),
+        });
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bot_addr", &[]),
+            ExecuteMsg::PerformDcaPurchase {
+                user: mock_creator().sender.into_string(),
+                id: 1,
+                hops: Some(hops.clone()),
+                fee_redeem: vec![Asset {
+                    amount: Uint128::new(15_000),
+                    info: uluna.clone(),
+                }],
+                venue: None,
+            },
+        )
+        .unwrap();
+
+        // the purchased asset is swapped to the contract before the tip is paid
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::reply_on_success(
+                    WasmMsg::Execute {
+                        contract_addr: "router".to_string(),
+                        funds: coins(10_000, "uluna"),
+                        msg: to_binary(&astroport::router::ExecuteMsg::ExecuteSwapOperations {
+                            operations: hops.clone(),
+                            minimum_receive: None,
+                            to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                            max_spread: Some(Decimal::percent(5)),
+                        })
+                        .unwrap(),
+                    },
+                    KEEPER_SWAP_REPLY_ID,
+                ),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "bot_addr".to_string(),
+                    amount: coins(15_000, "uluna"),
+                }),
+            ]
+        );
+
+        // the execution is anchored by the observation of the canonical route
+        let execution = KEEPER_EXECUTIONS.load(&deps.storage, 1).unwrap();
+        assert_eq!(execution.return_amount, Uint128::zero());
+        assert_eq!(execution.snapshots.unwrap()[0].anchor, anchor);
+
+        // the amount the swap returned is recorded and sent to the user
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(19_500, "ukrw"));
+        let res = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: KEEPER_SWAP_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: mock_creator().sender.into_string(),
+                amount: coins(19_500, "ukrw"),
+            })]
+        );
+        assert_eq!(
+            KEEPER_EXECUTIONS
+                .load(&deps.storage, 1)
+                .unwrap()
+                .return_amount,
+            Uint128::new(19_500)
+        );
+        assert_eq!(
+            PURCHASE_HISTORY
+                .load(&deps.storage, (&mock_creator().sender, 1))
+                .unwrap(),
+            vec![PurchaseRecord {
+                offer_asset: Asset {
+                    info: uluna,
+                    amount: Uint128::new(10_000),
+                },
+                return_asset: Some(Asset {
+                    info: ukrw,
+                    amount: Uint128::new(19_500),
+                }),
+                executed_at: env.block.time.seconds(),
+                matched: false,
+                protocol_fee: None,
+            }]
+        );
+        assert!(PENDING_PURCHASE.may_load(&deps.storage).unwrap().is_none());
+    }
 }
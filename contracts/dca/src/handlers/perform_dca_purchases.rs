@@ -0,0 +1,571 @@
+use astroport::{
+    asset::{Asset, AssetInfo},
+    router::ExecuteMsg as RouterExecuteMsg,
+};
+use astroport_dca::dca::BatchPurchase;
+use cosmwasm_std::{
+    attr, to_binary, Addr, Coin, DepsMut, Env, Event, MessageInfo, Response, StdError, StdResult,
+    SubMsg, Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use crate::{
+    error::ContractError,
+    matching::match_opposite_swaps,
+    price_impact::assert_price_impact,
+    state::{
+        record_execution_return, BatchShare, BatchSwap, PendingBatch, PurchaseRecord, CONFIG,
+        PENDING_BATCH, PURCHASE_HISTORY,
+    },
+    venue::simulate,
+};
+
+use super::{prepare_purchase, PerformDcaPurchase, PreparedPurchase};
+
+/// The reply ID of the swaps of batched executions
+pub const BATCH_SWAP_REPLY_ID: u64 = 1;
+
+/// ## Description
+/// Performs several DCA purchases on behalf of other users in a batched execution.
+///
+/// DCA purchases with the same initial asset, target asset and hop route are grouped into a single
+/// swap of their summed amount. Opposite single hop swaps are matched against each other at the
+/// TWAP of their pair, and only the residual amounts are swapped through the Astroport router, as
+/// long as their price impact does not exceed the smallest maximum price impact of their DCA
+/// purchases. The swaps are performed one after another, with the output of each swap being
+/// distributed pro rata in [`batch_swap_reply`].
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes and a `dca_purchase` event for each DCA purchase if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the bot who is performing the DCA purchases, who will be
+/// rewarded with the batch tip of each purchase.
+///
+/// * `purchases` - The [`Vec<BatchPurchase>`] DCA purchases to perform.
+pub fn perform_dca_purchases(
+    mut deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    purchases: Vec<BatchPurchase>,
+) -> Result<Response, ContractError> {
+    if purchases.is_empty() {
+        return Err(ContractError::EmptyBatch {});
+    }
+
+    let mut messages = vec![];
+    let mut events = vec![];
+    let mut swaps: Vec<BatchSwap> = vec![];
+    for purchase in purchases {
+        let PreparedPurchase {
+            user,
+            offer_asset,
+            target_asset,
+            hops,
+            max_spread,
+            max_price_impact,
+            protocol_fee,
+            execution_id,
+            messages: purchase_messages,
+            attributes,
+            ..
+        } = prepare_purchase(
+            deps.branch(),
+            &env,
+            &info.sender,
+            PerformDcaPurchase {
+                user: purchase.user,
+                id: purchase.id,
+                hops: purchase.hops,
+                fee_redeem: purchase.fee_redeem,
+                venue: None,
+            },
+            true,
+        )?;
+
+        messages.extend(purchase_messages);
+        events.push(Event::new("dca_purchase").add_attributes(attributes));
+
+        let share = BatchShare {
+            user,
+            id: purchase.id,
+            amount: offer_asset.amount,
+            protocol_fee,
+            execution_id,
+        };
+
+        // group the purchase with the purchases swapping the same assets through the same route
+        match swaps.iter_mut().find(|swap| {
+            swap.offer_asset.info == offer_asset.info
+                && swap.target_asset == target_asset
+                && swap.hops == hops
+        }) {
+            Some(swap) => {
+                swap.offer_asset.amount = swap.offer_asset.amount.checked_add(share.amount)?;
+                swap.max_spread = swap.max_spread.min(max_spread);
+                swap.max_price_impact = match (swap.max_price_impact, max_price_impact) {
+                    (Some(current), Some(new)) => Some(current.min(new)),
+                    (current, new) => current.or(new),
+                };
+                swap.shares.push(share);
+            }
+            None => swaps.push(BatchSwap {
+                offer_asset,
+                target_asset,
+                hops,
+                max_spread,
+                max_price_impact,
+                shares: vec![share],
+            }),
+        }
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let (match_messages, match_events) =
+        match_opposite_swaps(deps.storage, &deps.querier, &env, &config, &mut swaps)?;
+
+    // validate the price impact of the swaps that are actually performed, which aggregate the DCA
+    // purchases that were not matched
+    let venue = config.venue(None)?;
+    for swap in &swaps {
+        if let Some(max_price_impact) = swap.max_price_impact {
+            let return_amount = simulate(&deps.querier, &venue, &swap.offer_asset, &swap.hops)?;
+            assert_price_impact(
+                &deps.querier,
+                &config,
+                &venue,
+                &swap.offer_asset,
+                &swap.hops,
+                return_amount,
+                max_price_impact,
+            )?;
+        }
+    }
+
+    // the swaps are performed before the matches are settled and the tips and fees are paid, so
+    // that the balance of the target asset is only changed by the swap until its output is
+    // distributed
+    let mut swap_messages = vec![];
+    if let Some(swap) = swaps.first() {
+        let balance_before = swap
+            .target_asset
+            .query_pool(&deps.querier, env.contract.address.clone())?;
+        swap_messages = batch_swap_msgs(&config.router_addr, &env.contract.address, swap)?;
+
+        PENDING_BATCH.save(
+            deps.storage,
+            &PendingBatch {
+                swaps,
+                balance_before,
+            },
+        )?;
+    }
+
+    Ok(Response::new()
+        .add_submessages(swap_messages)
+        .add_messages(match_messages)
+        .add_messages(messages)
+        .add_attribute("action", "perform_dca_purchases")
+        .add_events(events)
+        .add_events(match_events))
+}
+
+/// ## Description
+/// Distributes the output of the batched swap that was just performed to the users of its DCA
+/// purchases, proportionally to the amount each purchase swapped with the last purchase receiving
+/// the rounding remainder, and performs the next batched swap if there is one. The share of each
+/// purchase performed by a registered keeper is added to the return of its keeper execution.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Params
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+pub fn batch_swap_reply(deps: DepsMut, env: Env) -> Result<Response, ContractError> {
+    let PendingBatch {
+        mut swaps,
+        balance_before,
+    } = PENDING_BATCH.load(deps.storage)?;
+    let swap = swaps.remove(0);
+
+    let balance = swap
+        .target_asset
+        .query_pool(&deps.querier, env.contract.address.clone())?;
+    let return_amount = balance.checked_sub(balance_before)?;
+
+    let mut messages = vec![];
+    let mut distributed = Uint128::zero();
+    for (i, share) in swap.shares.iter().enumerate() {
+        // the last share receives the remainder, so that no rounding dust is left in the contract
+        let return_asset = Asset {
+            info: swap.target_asset.clone(),
+            amount: match i + 1 == swap.shares.len() {
+                true => return_amount.checked_sub(distributed)?,
+                false => return_amount.multiply_ratio(share.amount, swap.offer_asset.amount),
+            },
+        };
+
+        if let Some(execution_id) = share.execution_id {
+            record_execution_return(deps.storage, execution_id, return_asset.amount)?;
+        }
+
+        PURCHASE_HISTORY.update::<_, StdError>(
+            deps.storage,
+            (&share.user, share.id),
+            |history| {
+                let mut history = history.unwrap_or_default();
+                history.push(PurchaseRecord {
+                    offer_asset: Asset {
+                        info: swap.offer_asset.info.clone(),
+                        amount: share.amount,
+                    },
+                    return_asset: Some(return_asset.clone()),
+                    executed_at: env.block.time.seconds(),
+                    matched: false,
+                    protocol_fee: share.protocol_fee.clone(),
+                });
+                Ok(history)
+            },
+        )?;
+
+        if !return_asset.amount.is_zero() {
+            distributed = distributed.checked_add(return_asset.amount)?;
+            messages.push(SubMsg::new(
+                return_asset.into_msg(&deps.querier, share.user.clone())?,
+            ));
+        }
+    }
+
+    match swaps.first() {
+        Some(next) => {
+            // the output being distributed is still held by the contract until the distribution
+            // messages are executed, so it is excluded from the balance before the next swap
+            let mut balance_before = next
+                .target_asset
+                .query_pool(&deps.querier, env.contract.address.clone())?;
+            if next.target_asset == swap.target_asset {
+                balance_before = balance_before.checked_sub(distributed)?;
+            }
+
+            let config = CONFIG.load(deps.storage)?;
+            messages.extend(batch_swap_msgs(
+                &config.router_addr,
+                &env.contract.address,
+                next,
+            )?);
+
+            PENDING_BATCH.save(
+                deps.storage,
+                &PendingBatch {
+                    swaps,
+                    balance_before,
+                },
+            )?;
+        }
+        None => PENDING_BATCH.remove(deps.storage),
+    }
+
+    Ok(Response::new()
+        .add_submessages(messages)
+        .add_attributes(vec![
+            attr("action", "distribute_batch_swap"),
+            attr("return_amount", return_amount),
+        ]))
+}
+
+/// ## Description
+/// Creates the messages performing a batched swap through the router, sending its output to the
+/// contract, where only the swap itself replies to the contract.
+fn batch_swap_msgs(
+    router_addr: &Addr,
+    contract_addr: &Addr,
+    swap: &BatchSwap,
+) -> StdResult<Vec<SubMsg>> {
+    let mut messages = vec![];
+
+    let funds = match &swap.offer_asset.info {
+        AssetInfo::NativeToken { denom } => vec![Coin {
+            amount: swap.offer_asset.amount,
+            denom: denom.clone(),
+        }],
+        AssetInfo::Token { contract_addr } => {
+            // send a TransferFrom request to the token to the router for each user
+            for share in &swap.shares {
+                messages.push(SubMsg::new(WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: share.user.to_string(),
+                        recipient: router_addr.to_string(),
+                        amount: share.amount,
+                    })?,
+                }));
+            }
+
+            vec![]
+        }
+    };
+
+    messages.push(SubMsg::reply_on_success(
+        WasmMsg::Execute {
+            contract_addr: router_addr.to_string(),
+            funds,
+            msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                operations: swap.hops.clone(),
+                minimum_receive: None,
+                to: Some(contract_addr.to_string()),
+                max_spread: Some(swap.max_spread),
+            })?,
+        },
+        BATCH_SWAP_REPLY_ID,
+    ));
+
+    Ok(messages)
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        router::{ExecuteMsg as RouterExecuteMsg, SwapOperation},
+    };
+    use astroport_dca::dca::{BatchPurchase, DcaInfo, ExecuteMsg};
+    use cosmwasm_std::{
+        attr, coins,
+        testing::{mock_info, MOCK_CONTRACT_ADDR},
+        to_binary, Addr, BankMsg, Decimal, Reply, StdError, SubMsg, SubMsgResponse, SubMsgResult,
+        Uint128, WasmMsg,
+    };
+
+    use crate::{
+        contract::{execute, reply},
+        state::{
+            BatchShare, BatchSwap, PendingBatch, PurchaseRecord, UserConfig, CONFIG, PENDING_BATCH,
+            PURCHASE_HISTORY, USER_CONFIG, USER_DCA,
+        },
+        tests::mock_instantiate,
+    };
+
+    use super::BATCH_SWAP_REPLY_ID;
+
+    #[test]
+    fn does_batch_purchases() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: uluna.clone(),
+            }],
+            vec![],
+        );
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.whitelisted_fee_assets[0].batch_surcharge = Some(Uint128::new(5_000));
+                Ok(config)
+            })
+            .unwrap();
+
+        // two users purchase ukrw with uluna through the same route
+        let users = [
+            (Addr::unchecked("alice"), 10_000u128),
+            (Addr::unchecked("bob"), 20_000u128),
+        ];
+        for (user, dca_amount) in &users {
+            USER_CONFIG
+                .save(
+                    &mut deps.storage,
+                    user,
+                    &UserConfig {
+                        last_id: 1,
+                        max_hops: None,
+                        max_spread: None,
+                        tip_balance: vec![Asset {
+                            amount: Uint128::new(50_000),
+                            info: uluna.clone(),
+                        }],
+                        max_price_impact: None,
+                    },
+                )
+                .unwrap();
+
+            USER_DCA
+                .save(
+                    &mut deps.storage,
+                    user,
+                    &vec![DcaInfo {
+                        id: 1,
+                        dca_amount: Uint128::new(*dca_amount),
+                        initial_asset: Asset {
+                            info: uluna.clone(),
+                            amount: Uint128::new(100_000),
+                        },
+                        target_asset: ukrw.clone(),
+                        interval: 500,
+                        last_purchase: 0,
+                        referral: None,
+                        preferred_executor: None,
+                        exclusivity_seconds: 0,
+                        require_canonical_route: false,
+                        max_price_impact: None,
+                        deposit: None,
+                    }],
+                )
+                .unwrap();
+        }
+
+        let hops = vec![SwapOperation::AstroSwap {
+            offer_asset_info: uluna.clone(),
+            ask_asset_info: ukrw.clone(),
+        }];
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bot_addr", &[]),
+            ExecuteMsg::PerformDcaPurchases {
+                purchases: users
+                    .iter()
+                    .map(|(user, _)| BatchPurchase {
+                        user: user.to_string(),
+                        id: 1,
+                        hops: Some(hops.clone()),
+                        // the batch tip includes the batch surcharge
+                        fee_redeem: vec![Asset {
+                            amount: Uint128::new(20_000),
+                            info: uluna.clone(),
+                        }],
+                    })
+                    .collect(),
+            },
+        )
+        .unwrap();
+
+        // the purchases are swapped together before the tips are paid
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::reply_on_success(
+                    WasmMsg::Execute {
+                        contract_addr: "router".to_string(),
+                        funds: coins(30_000, "uluna"),
+                        msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                            operations: hops.clone(),
+                            minimum_receive: None,
+                            to: Some(MOCK_CONTRACT_ADDR.to_string()),
+                            max_spread: Some(Decimal::percent(5)),
+                        })
+                        .unwrap(),
+                    },
+                    BATCH_SWAP_REPLY_ID
+                ),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "bot_addr".to_string(),
+                    amount: coins(20_000, "uluna"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "bot_addr".to_string(),
+                    amount: coins(20_000, "uluna"),
+                }),
+            ]
+        );
+        assert_eq!(res.events.len(), 2);
+        assert_eq!(
+            PENDING_BATCH.load(&deps.storage).unwrap(),
+            PendingBatch {
+                swaps: vec![BatchSwap {
+                    offer_asset: Asset {
+                        info: uluna.clone(),
+                        amount: Uint128::new(30_000),
+                    },
+                    target_asset: ukrw.clone(),
+                    hops,
+                    max_spread: Decimal::percent(5),
+                    max_price_impact: None,
+                    shares: users
+                        .iter()
+                        .map(|(user, dca_amount)| BatchShare {
+                            user: user.clone(),
+                            id: 1,
+                            amount: Uint128::new(*dca_amount),
+                            protocol_fee: None,
+                            execution_id: None,
+                        })
+                        .collect(),
+                }],
+                balance_before: Uint128::zero(),
+            }
+        );
+
+        // the output of the swap is distributed pro rata, where the last share receives the
+        // rounding remainder
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(3_001, "ukrw"));
+        let res = reply(
+            deps.as_mut(),
+            env.clone(),
+            Reply {
+                id: BATCH_SWAP_REPLY_ID,
+                result: SubMsgResult::Ok(SubMsgResponse {
+                    events: vec![],
+                    data: None,
+                }),
+            },
+        )
+        .unwrap();
+
+        assert_eq!(
+            res.messages,
+            vec![
+                SubMsg::new(BankMsg::Send {
+                    to_address: "alice".to_string(),
+                    amount: coins(1_000, "ukrw"),
+                }),
+                SubMsg::new(BankMsg::Send {
+                    to_address: "bob".to_string(),
+                    amount: coins(2_001, "ukrw"),
+                }),
+            ]
+        );
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "distribute_batch_swap"),
+                attr("return_amount", "3001"),
+            ]
+        );
+        assert!(PENDING_BATCH.may_load(&deps.storage).unwrap().is_none());
+
+        // the share of each user is recorded
+        assert_eq!(
+            PURCHASE_HISTORY
+                .load(&deps.storage, (&users[1].0, 1))
+                .unwrap(),
+            vec![PurchaseRecord {
+                offer_asset: Asset {
+                    info: uluna,
+                    amount: Uint128::new(20_000),
+                },
+                return_asset: Some(Asset {
+                    info: ukrw,
+                    amount: Uint128::new(2_001),
+                }),
+                executed_at: env.block.time.seconds(),
+                matched: false,
+                protocol_fee: None,
+            }]
+        );
+    }
+}
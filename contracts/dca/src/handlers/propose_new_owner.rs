@@ -0,0 +1,172 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{OwnershipProposal, CONFIG, MAX_PROPOSAL_TTL, OWNERSHIP_PROPOSAL},
+};
+
+/// ## Description
+/// Proposes a new owner of the contract, who can claim the ownership with `ClaimOwnership` within
+/// `expires_in` seconds. Any previous proposal is replaced.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to transfer the ownership.
+///
+/// * `owner` - The address of the proposed new owner as a [`String`].
+///
+/// * `expires_in` - The time in seconds the proposal can be claimed in, which cannot exceed
+/// [`MAX_PROPOSAL_TTL`].
+pub fn propose_new_owner(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: String,
+    expires_in: u64,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    config.assert_owner(&info.sender)?;
+
+    let owner = addr_validate_to_lower(deps.api, &owner)?;
+    if owner == config.owner {
+        return Err(ContractError::SameOwner {});
+    }
+
+    if expires_in > MAX_PROPOSAL_TTL {
+        return Err(ContractError::ProposalTtlTooLong {
+            max: MAX_PROPOSAL_TTL,
+        });
+    }
+
+    let ttl = env.block.time.seconds() + expires_in;
+    OWNERSHIP_PROPOSAL.save(
+        deps.storage,
+        &OwnershipProposal {
+            owner: owner.clone(),
+            ttl,
+        },
+    )?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "propose_new_owner"),
+        attr("new_owner", owner),
+        attr("expires_at", ttl.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{testing::mock_info, Addr};
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        state::{CONFIG, MAX_PROPOSAL_TTL},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_transfer_ownership() {
+        let (mut deps, mut env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let propose = |owner: &str, expires_in: u64| ExecuteMsg::ProposeNewOwner {
+            owner: owner.to_string(),
+            expires_in,
+        };
+
+        // only the owner can propose a new owner
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("new_owner", &[]),
+            propose("new_owner", 100),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            propose("new_owner", MAX_PROPOSAL_TTL + 1),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::ProposalTtlTooLong {
+                max: MAX_PROPOSAL_TTL
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            propose("new_owner", 100),
+        )
+        .unwrap();
+
+        // only the proposed owner can claim the ownership
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ClaimOwnership {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        // the proposal cannot be claimed once it expired
+        env.block.time = env.block.time.plus_seconds(101);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("new_owner", &[]),
+            ExecuteMsg::ClaimOwnership {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::OwnershipProposalExpired {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            propose("new_owner", 100),
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("new_owner", &[]),
+            ExecuteMsg::ClaimOwnership {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().owner,
+            Addr::unchecked("new_owner")
+        );
+
+        // the proposal is removed once claimed
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("new_owner", &[]),
+            ExecuteMsg::ClaimOwnership {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::NonexistentOwnershipProposal {});
+    }
+}
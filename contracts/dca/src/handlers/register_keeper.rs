@@ -0,0 +1,170 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{attr, to_binary, DepsMut, Env, MessageInfo, Response, WasmMsg};
+use cw20::Cw20ExecuteMsg;
+
+use crate::{
+    error::ContractError,
+    state::{Keeper, CONFIG, KEEPERS},
+};
+
+/// ## Description
+/// Registers the sender as a keeper who can perform DCA purchases when the permissioned keeper
+/// mode is enabled, bonding the keeper bond set in the contract configuration.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the keeper who wants to register, containing the bond if it
+/// is a native token. If the bond is a cw20 token, the keeper needs to have set the allowance for
+/// the contract to the bond amount.
+pub fn register_keeper(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let keeper_config = config
+        .keeper_config
+        .ok_or(ContractError::KeeperModeNotConfigured {})?;
+
+    if KEEPERS.has(deps.storage, &info.sender) {
+        return Err(ContractError::KeeperAlreadyRegistered {});
+    }
+
+    let bond = keeper_config.bond;
+
+    // native bonds are sent with the message, while cw20 bonds are transferred to the contract
+    let mut messages = vec![];
+    match &bond.info {
+        AssetInfo::NativeToken { .. } => bond.assert_sent_native_token_balance(&info)?,
+        AssetInfo::Token { contract_addr } => messages.push(WasmMsg::Execute {
+            contract_addr: contract_addr.to_string(),
+            funds: vec![],
+            msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                owner: info.sender.to_string(),
+                recipient: env.contract.address.to_string(),
+                amount: bond.amount,
+            })?,
+        }),
+    }
+
+    KEEPERS.save(
+        deps.storage,
+        &info.sender,
+        &Keeper {
+            bond: bond.clone(),
+            unbonding_since: None,
+        },
+    )?;
+
+    Ok(Response::new().add_messages(messages).add_attributes(vec![
+        attr("action", "register_keeper"),
+        attr("keeper", info.sender),
+        attr("bond", bond.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, KeeperConfig};
+    use cosmwasm_std::{coins, testing::mock_info, Addr, Decimal, StdError, Uint128};
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        state::{Keeper, CONFIG, KEEPERS},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_register_keeper() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        // keepers cannot register until keeper mode is configured
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &coins(1_000_000, "uluna")),
+            ExecuteMsg::RegisterKeeper {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::KeeperModeNotConfigured {});
+
+        let bond = Asset {
+            amount: Uint128::new(1_000_000),
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+        };
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.keeper_config = Some(KeeperConfig {
+                    enabled: true,
+                    bond: bond.clone(),
+                    slash_threshold: Decimal::percent(10),
+                    challenge_period: 3_600,
+                });
+                Ok(config)
+            })
+            .unwrap();
+
+        // bond must be sent
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &coins(500_000, "uluna")),
+            ExecuteMsg::RegisterKeeper {},
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::Std(StdError::generic_err(
+                "Native token balance mismatch between the argument and the transferred"
+            ))
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &coins(1_000_000, "uluna")),
+            ExecuteMsg::RegisterKeeper {},
+        )
+        .unwrap();
+
+        assert_eq!(
+            KEEPERS
+                .load(&deps.storage, &Addr::unchecked("keeper"))
+                .unwrap(),
+            Keeper {
+                bond,
+                unbonding_since: None
+            }
+        );
+
+        // cannot register twice
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("keeper", &coins(1_000_000, "uluna")),
+            ExecuteMsg::RegisterKeeper {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::KeeperAlreadyRegistered {});
+    }
+}
@@ -0,0 +1,145 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{error::ContractError, state::CONFIG};
+
+/// ## Description
+/// Removes `asset` from the whitelisted fee assets, so that it can no longer be deposited or paid
+/// as a bot tip. Existing tip balances in the asset remain withdrawable.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to remove the fee asset.
+///
+/// * `asset` - The [`AssetInfo`] of the fee asset being removed.
+pub fn remove_fee_asset(
+    deps: DepsMut,
+    info: MessageInfo,
+    asset: AssetInfo,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.assert_owner(&info.sender)?;
+
+    if !config.is_whitelisted_fee_asset(&asset) {
+        return Err(ContractError::NonWhitelistedTipAsset { asset });
+    }
+
+    config.whitelisted_fee_assets.retain(|a| a.info != asset);
+
+    // the keeper bond and the order deposit have to remain whitelisted fee assets, while the
+    // deposits already paid in the asset remain refundable
+    config.validate(deps.api)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_fee_asset"),
+        attr("asset", asset.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{coins, testing::mock_info, Addr, BankMsg, Uint128};
+
+    use crate::{contract::execute, error::ContractError, tests::mock_instantiate};
+
+    #[test]
+    fn does_keep_tip_balance_withdrawable() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: uluna.clone(),
+            }],
+            vec![],
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(10_000, "uluna")),
+            ExecuteMsg::AddBotTip {
+                assets: vec![Asset {
+                    amount: Uint128::new(10_000),
+                    info: uluna.clone(),
+                }],
+            },
+        )
+        .unwrap();
+
+        // only the owner can remove fee assets
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::RemoveFeeAsset {
+                asset: uluna.clone(),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::RemoveFeeAsset {
+                asset: uluna.clone(),
+            },
+        )
+        .unwrap();
+
+        // the asset can no longer be deposited
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &coins(10_000, "uluna")),
+            ExecuteMsg::AddBotTip {
+                assets: vec![Asset {
+                    amount: Uint128::new(10_000),
+                    info: uluna.clone(),
+                }],
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::NonWhitelistedTipAsset {
+                asset: uluna.clone()
+            }
+        );
+
+        // but the existing tip balance can still be withdrawn
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("user", &[]),
+            ExecuteMsg::Withdraw {
+                assets: vec![Asset {
+                    amount: Uint128::new(10_000),
+                    info: uluna,
+                }],
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages[0].msg,
+            BankMsg::Send {
+                to_address: "user".to_string(),
+                amount: coins(10_000, "uluna"),
+            }
+            .into()
+        );
+    }
+}
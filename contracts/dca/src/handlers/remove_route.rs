@@ -0,0 +1,37 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{route_key, CONFIG, ROUTES},
+};
+
+/// ## Description
+/// Removes the canonical hop route from `from` to `to`.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to remove the route.
+///
+/// * `from` - The [`AssetInfo`] of the asset being offered.
+///
+/// * `to` - The [`AssetInfo`] of the asset being purchased.
+pub fn remove_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: AssetInfo,
+    to: AssetInfo,
+) -> Result<Response, ContractError> {
+    CONFIG.load(deps.storage)?.assert_owner(&info.sender)?;
+
+    ROUTES.remove(deps.storage, route_key(&from, &to));
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "remove_route"),
+        attr("from", from.to_string()),
+        attr("to", to.to_string()),
+    ]))
+}
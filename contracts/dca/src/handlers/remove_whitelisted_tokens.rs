@@ -0,0 +1,107 @@
+use astroport::asset::AssetInfo;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{error::ContractError, state::CONFIG};
+
+/// ## Description
+/// Removes `tokens` from the whitelisted tokens that can be used in a hop route, which takes
+/// effect immediately. Tokens that are not whitelisted are ignored.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to remove the tokens.
+///
+/// * `tokens` - The [`Vec<AssetInfo>`] tokens being removed from the whitelist.
+pub fn remove_whitelisted_tokens(
+    deps: DepsMut,
+    info: MessageInfo,
+    tokens: Vec<AssetInfo>,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.assert_owner(&info.sender)?;
+
+    config
+        .whitelisted_tokens
+        .retain(|token| !tokens.contains(token));
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![attr("action", "remove_whitelisted_tokens")]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::AssetInfo;
+    use astroport_dca::dca::ExecuteMsg;
+    use cosmwasm_std::{testing::mock_info, Addr};
+
+    use crate::{
+        contract::execute,
+        state::{CONFIG, DEFAULT_CONFIG_TIMELOCK},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_update_whitelisted_tokens() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+
+        let (mut deps, mut env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![uluna.clone()],
+        );
+
+        // added tokens are queued behind the configuration timelock
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::AddWhitelistedTokens {
+                tokens: vec![uluna.clone(), ukrw.clone(), uusd.clone()],
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().whitelisted_tokens,
+            vec![uluna.clone()]
+        );
+
+        env.block.time = env.block.time.plus_seconds(DEFAULT_CONFIG_TIMELOCK);
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ExecutePendingConfig {},
+        )
+        .unwrap();
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().whitelisted_tokens,
+            vec![uluna.clone(), ukrw.clone(), uusd.clone()]
+        );
+
+        // removed tokens are removed immediately
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::RemoveWhitelistedTokens { tokens: vec![ukrw] },
+        )
+        .unwrap();
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().whitelisted_tokens,
+            vec![uluna, uusd]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use astroport_dca::dca::FeeAsset;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{error::ContractError, state::CONFIG};
+
+/// ## Description
+/// Whitelists `fee_asset` for bot tips, replacing the tip paid in the asset if it is already
+/// whitelisted.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to set the fee asset.
+///
+/// * `fee_asset` - The [`FeeAsset`] being whitelisted along with the tip paid in it.
+pub fn set_fee_asset(
+    deps: DepsMut,
+    info: MessageInfo,
+    fee_asset: FeeAsset,
+) -> Result<Response, ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+    config.assert_owner(&info.sender)?;
+
+    match config
+        .whitelisted_fee_assets
+        .iter_mut()
+        .find(|a| a.info == fee_asset.info)
+    {
+        Some(whitelisted) => *whitelisted = fee_asset.clone(),
+        None => config.whitelisted_fee_assets.push(fee_asset.clone()),
+    }
+
+    config.validate(deps.api)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_fee_asset"),
+        attr("asset", fee_asset.info.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, FeeAsset};
+    use cosmwasm_std::{testing::mock_info, Addr, Uint128};
+
+    use crate::{contract::execute, error::ContractError, state::CONFIG, tests::mock_instantiate};
+
+    #[test]
+    fn does_set_fee_asset() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: uluna.clone(),
+            }],
+            vec![],
+        );
+
+        let fee_asset = |info: &AssetInfo, per_hop_fee: u128| FeeAsset {
+            info: info.clone(),
+            base_fee: Uint128::zero(),
+            per_hop_fee: Uint128::new(per_hop_fee),
+            batch_surcharge: None,
+        };
+
+        // replaces the tip of an existing fee asset and adds new fee assets
+        for fee_asset in [fee_asset(&uluna, 20_000), fee_asset(&ukrw, 5_000)] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("factory_owner", &[]),
+                ExecuteMsg::SetFeeAsset { fee_asset },
+            )
+            .unwrap();
+        }
+
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap().whitelisted_fee_assets,
+            vec![fee_asset(&uluna, 20_000), fee_asset(&ukrw, 5_000)]
+        );
+
+        // a fee asset has to pay a tip
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::SetFeeAsset {
+                fee_asset: fee_asset(&ukrw, 0),
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::ZeroFeeAsset { asset: ukrw });
+    }
+}
@@ -0,0 +1,213 @@
+use astroport::{asset::AssetInfo, router::SwapOperation};
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    route::assert_route_chains,
+    state::{route_key, CONFIG, ROUTES},
+};
+
+/// ## Description
+/// Registers a canonical hop route from `from` to `to`, which DCA orders can require purchases to
+/// be performed with.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to register the route.
+///
+/// * `from` - The [`AssetInfo`] of the asset being offered.
+///
+/// * `to` - The [`AssetInfo`] of the asset being purchased.
+///
+/// * `hops` - The [`Vec<SwapOperation>`] of the route, which has to chain from `from` to `to`.
+pub fn set_route(
+    deps: DepsMut,
+    info: MessageInfo,
+    from: AssetInfo,
+    to: AssetInfo,
+    hops: Vec<SwapOperation>,
+) -> Result<Response, ContractError> {
+    CONFIG.load(deps.storage)?.assert_owner(&info.sender)?;
+
+    if hops.is_empty() {
+        return Err(ContractError::EmptyHopRoute {});
+    }
+
+    // validate that the route chains from `from` to `to`, while the pairs swapped through are
+    // validated when performing DCA purchases
+    assert_route_chains(&hops, &from)?;
+    if hops[hops.len() - 1].get_target_asset_info() != to {
+        return Err(ContractError::InvalidRoute {});
+    }
+
+    ROUTES.save(deps.storage, route_key(&from, &to), &hops)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "set_route"),
+        attr("from", from.to_string()),
+        attr("to", to.to_string()),
+        attr("hops", hops.len().to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::{asset::AssetInfo, router::SwapOperation};
+    use astroport_dca::dca::{ExecuteMsg, QueryMsg};
+    use cosmwasm_std::Addr;
+    use cw_multi_test::{App, Executor};
+
+    use crate::{
+        error::ContractError,
+        tests::{
+            app_mock_instantiate, mock_app, mock_creator, store_dca_module_code, store_factory_code,
+        },
+    };
+
+    fn instantiate() -> (App, Addr) {
+        let mut app = mock_app();
+
+        let dca_module_id = store_dca_module_code(&mut app);
+        let factory_id = store_factory_code(&mut app);
+
+        let factory_addr = app
+            .instantiate_contract(
+                factory_id,
+                mock_creator().sender,
+                &astroport::factory::InstantiateMsg {
+                    owner: "factory_owner".to_string(),
+                    token_code_id: 99,
+                    whitelist_code_id: 100,
+                    pair_configs: vec![],
+                    fee_address: None,
+                    generator_address: None,
+                },
+                &[],
+                "factory",
+                None,
+            )
+            .unwrap();
+
+        let dca_addr = app_mock_instantiate(
+            &mut app,
+            dca_module_id,
+            factory_addr,
+            Addr::unchecked("router"),
+            vec![],
+        );
+
+        (app, dca_addr)
+    }
+
+    #[test]
+    fn does_set_route() {
+        let (mut app, dca_addr) = instantiate();
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let hops = vec![
+            SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: uusd.clone(),
+            },
+            SwapOperation::AstroSwap {
+                offer_asset_info: uusd,
+                ask_asset_info: ukrw.clone(),
+            },
+        ];
+
+        // only the owner can register routes
+        let res = app
+            .execute_contract(
+                mock_creator().sender,
+                dca_addr.clone(),
+                &ExecuteMsg::SetRoute {
+                    from: uluna.clone(),
+                    to: ukrw.clone(),
+                    hops: hops.clone(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            res.root_cause().to_string(),
+            ContractError::Unauthorized {}.to_string()
+        );
+
+        // the route has to end in the target asset
+        let res = app
+            .execute_contract(
+                Addr::unchecked("factory_owner"),
+                dca_addr.clone(),
+                &ExecuteMsg::SetRoute {
+                    from: uluna.clone(),
+                    to: ukrw.clone(),
+                    hops: hops[..1].to_vec(),
+                },
+                &[],
+            )
+            .unwrap_err();
+        assert_eq!(
+            res.root_cause().to_string(),
+            ContractError::InvalidRoute {}.to_string()
+        );
+
+        app.execute_contract(
+            Addr::unchecked("factory_owner"),
+            dca_addr.clone(),
+            &ExecuteMsg::SetRoute {
+                from: uluna.clone(),
+                to: ukrw.clone(),
+                hops: hops.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let route: Option<Vec<SwapOperation>> = app
+            .wrap()
+            .query_wasm_smart(
+                &dca_addr,
+                &QueryMsg::Route {
+                    from: uluna.clone(),
+                    to: ukrw.clone(),
+                },
+            )
+            .unwrap();
+        assert_eq!(route, Some(hops));
+
+        app.execute_contract(
+            Addr::unchecked("factory_owner"),
+            dca_addr.clone(),
+            &ExecuteMsg::RemoveRoute {
+                from: uluna.clone(),
+                to: ukrw.clone(),
+            },
+            &[],
+        )
+        .unwrap();
+
+        let route: Option<Vec<SwapOperation>> = app
+            .wrap()
+            .query_wasm_smart(
+                &dca_addr,
+                &QueryMsg::Route {
+                    from: uluna,
+                    to: ukrw,
+                },
+            )
+            .unwrap();
+        assert_eq!(route, None);
+    }
+}
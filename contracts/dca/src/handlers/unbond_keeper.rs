@@ -0,0 +1,126 @@
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
+
+use crate::{error::ContractError, state::KEEPERS};
+
+/// ## Description
+/// Starts unbonding the bond of the sender, who can no longer perform DCA purchases once they
+/// are unbonding.
+///
+/// The bond can be claimed with `ClaimKeeperBond` once the challenge period has passed.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `info` - A [`MessageInfo`] from the keeper who wants to unbond.
+pub fn unbond_keeper(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+) -> Result<Response, ContractError> {
+    let mut keeper = KEEPERS
+        .may_load(deps.storage, &info.sender)?
+        .ok_or(ContractError::KeeperNotRegistered {})?;
+
+    if keeper.unbonding_since.is_some() {
+        return Err(ContractError::KeeperUnbonding {});
+    }
+
+    keeper.unbonding_since = Some(env.block.time.seconds());
+    KEEPERS.save(deps.storage, &info.sender, &keeper)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "unbond_keeper"),
+        attr("keeper", info.sender),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::{ExecuteMsg, KeeperConfig};
+    use cosmwasm_std::{coins, testing::mock_info, Addr, Decimal, StdError, Uint128};
+
+    use crate::{
+        contract::execute,
+        error::ContractError,
+        state::{CONFIG, KEEPERS},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_unbond_keeper() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        CONFIG
+            .update::<_, StdError>(&mut deps.storage, |mut config| {
+                config.keeper_config = Some(KeeperConfig {
+                    enabled: true,
+                    bond: Asset {
+                        amount: Uint128::new(1_000_000),
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                    },
+                    slash_threshold: Decimal::percent(10),
+                    challenge_period: 3_600,
+                });
+                Ok(config)
+            })
+            .unwrap();
+
+        // only registered keepers can unbond
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::UnbondKeeper {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::KeeperNotRegistered {});
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &coins(1_000_000, "uluna")),
+            ExecuteMsg::RegisterKeeper {},
+        )
+        .unwrap();
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("keeper", &[]),
+            ExecuteMsg::UnbondKeeper {},
+        )
+        .unwrap();
+
+        let keeper = KEEPERS
+            .load(&deps.storage, &Addr::unchecked("keeper"))
+            .unwrap();
+        assert_eq!(keeper.unbonding_since, Some(env.block.time.seconds()));
+
+        // a keeper cannot unbond twice
+        let res = execute(
+            deps.as_mut(),
+            env,
+            mock_info("keeper", &[]),
+            ExecuteMsg::UnbondKeeper {},
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::KeeperUnbonding {});
+    }
+}
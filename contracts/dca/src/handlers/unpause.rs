@@ -0,0 +1,120 @@
+use astroport_dca::dca::PauseScope;
+use cosmwasm_std::{attr, DepsMut, MessageInfo, Response};
+
+use crate::{
+    error::ContractError,
+    state::{CONFIG, PAUSED_SCOPES},
+};
+
+/// ## Description
+/// Unpauses `scope` of the contract.
+///
+/// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
+/// attributes if the operation was successful.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to unpause the contract.
+///
+/// * `scope` - The [`PauseScope`] being unpaused.
+pub fn unpause(
+    deps: DepsMut,
+    info: MessageInfo,
+    scope: PauseScope,
+) -> Result<Response, ContractError> {
+    CONFIG.load(deps.storage)?.assert_owner(&info.sender)?;
+
+    let mut paused_scopes = PAUSED_SCOPES.may_load(deps.storage)?.unwrap_or_default();
+    if !paused_scopes.contains(&scope) {
+        return Err(ContractError::NotPaused { scope });
+    }
+
+    paused_scopes.retain(|paused_scope| paused_scope != &scope);
+    PAUSED_SCOPES.save(deps.storage, &paused_scopes)?;
+
+    Ok(Response::new().add_attributes(vec![
+        attr("action", "unpause"),
+        attr("scope", scope.to_string()),
+    ]))
+}
+
+#[cfg(test)]
+mod tests {
+    use astroport_dca::dca::{ExecuteMsg, PauseScope, QueryMsg};
+    use cosmwasm_std::{attr, from_binary, testing::mock_info, Addr, Response};
+
+    use crate::{
+        contract::{execute, query},
+        error::ContractError,
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_unpause() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        // a scope that is not paused cannot be unpaused
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::Unpause {
+                scope: PauseScope::Purchases {},
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::NotPaused {
+                scope: PauseScope::Purchases {}
+            }
+        );
+
+        for scope in [PauseScope::Purchases {}, PauseScope::OrderCreation {}] {
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("guardian", &[]),
+                ExecuteMsg::Pause { scope },
+            )
+            .unwrap();
+        }
+
+        // only the owner can unpause the contract
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("user", &[]),
+            ExecuteMsg::Unpause {
+                scope: PauseScope::Purchases {},
+            },
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::Unauthorized {});
+
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::Unpause {
+                scope: PauseScope::Purchases {},
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            res,
+            Response::new()
+                .add_attributes(vec![attr("action", "unpause"), attr("scope", "purchases"),])
+        );
+
+        // the other scope stays paused
+        let paused: Vec<PauseScope> =
+            from_binary(&query(deps.as_ref(), env, QueryMsg::Paused {}).unwrap()).unwrap();
+        assert_eq!(paused, vec![PauseScope::OrderCreation {}]);
+    }
+}
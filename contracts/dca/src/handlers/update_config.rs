@@ -1,13 +1,15 @@
-use astroport::{
-    asset::{Asset, AssetInfo},
-    querier::query_factory_config,
-};
-use cosmwasm_std::{attr, Decimal, DepsMut, MessageInfo, Response, StdError};
+use cosmwasm_std::{attr, DepsMut, Env, MessageInfo, Response};
 
-use crate::{error::ContractError, state::CONFIG};
+use crate::{
+    error::ContractError,
+    state::{PendingConfig, UpdateConfigParameters, CONFIG, PENDING_CONFIG},
+};
 
 /// ## Description
-/// Updates the contract configuration with the specified optional parameters.
+/// Queues an update of the contract configuration with the specified optional parameters, which
+/// can be executed with `ExecutePendingConfig` once the `config_timelock` has passed. A queued
+/// update has to be executed or cancelled with `CancelPendingConfig` before another one can be
+/// queued, so that queueing an update never silently discards another.
 ///
 /// If any new configuration value is excluded, the current configuration value will remain
 /// unchanged.
@@ -17,74 +19,69 @@ use crate::{error::ContractError, state::CONFIG};
 /// ## Arguments
 /// * `deps` - A [`DepsMut`] that contains the dependencies.
 ///
-/// * `info` - A [`MessageInfo`] from the factory contract owner who wants to modify the
-/// configuration of the contract.
-///
-/// * `max_hops` - An optional value which represents the new maximum amount of hops per swap if the
-/// user does not specify a value.
+/// * `env` - The [`Env`] of the blockchain.
 ///
-/// * `per_hop_fee` - An optional [`Vec<Asset>`] which represents the new fee paid to bots per hop
-/// executed in a DCA purchase.
-///
-/// * `whitelisted_tokens` - An optional [`Vec<AssetInfo>`] which represents the new whitelisted
-/// tokens that can be used in a hop route for DCA purchases.
+/// * `info` - A [`MessageInfo`] from the contract owner who wants to modify the
+/// configuration of the contract.
 ///
-/// * `max_spread` - An optional [`Decimal`] which represents the new maximum spread for each DCA
-/// purchase if the user does not specify a value.
+/// * `new_config` - The [`UpdateConfigParameters`] containing the new configuration values.
 pub fn update_config(
     deps: DepsMut,
+    env: Env,
     info: MessageInfo,
-    max_hops: Option<u32>,
-    whitelisted_fee_assets: Option<Vec<Asset>>,
-    whitelisted_tokens: Option<Vec<AssetInfo>>,
-    max_spread: Option<Decimal>,
+    new_config: UpdateConfigParameters,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    let factory_config = query_factory_config(&deps.querier, config.factory_addr)?;
+    config.assert_owner(&info.sender)?;
 
-    if info.sender != factory_config.owner {
-        return Err(ContractError::Unauthorized {});
+    if PENDING_CONFIG.may_load(deps.storage)?.is_some() {
+        return Err(ContractError::PendingConfigExists {});
     }
 
-    // update config
-    CONFIG.update::<_, StdError>(deps.storage, |mut config| {
-        if let Some(new_max_hops) = max_hops {
-            config.max_hops = new_max_hops;
-        }
-
-        if let Some(new_whitelisted_fee_assets) = whitelisted_fee_assets {
-            config.whitelisted_fee_assets = new_whitelisted_fee_assets;
-        }
-
-        if let Some(new_whitelisted_tokens) = whitelisted_tokens {
-            config.whitelisted_tokens = new_whitelisted_tokens;
-        }
+    // check that the update would result in a valid configuration
+    new_config.apply(deps.as_ref(), &mut config.clone())?;
 
-        if let Some(new_max_spread) = max_spread {
-            config.max_spread = new_max_spread;
-        }
-
-        Ok(config)
-    })?;
+    let activation_time = env.block.time.seconds() + config.config_timelock;
+    PENDING_CONFIG.save(
+        deps.storage,
+        &PendingConfig {
+            update: new_config,
+            activation_time,
+        },
+    )?;
 
-    Ok(Response::default().add_attributes(vec![attr("action", "update_config")]))
+    Ok(Response::default().add_attributes(vec![
+        attr("action", "update_config"),
+        attr("activation_time", activation_time.to_string()),
+    ]))
 }
 
 #[cfg(test)]
 mod test {
     use std::str::FromStr;
 
-    use astroport::asset::{Asset, AssetInfo};
-    use astroport_dca::dca::ExecuteMsg;
-    use cosmwasm_std::{Addr, Decimal, Uint128};
+    use astroport::{
+        asset::AssetInfo,
+        factory::{ConfigResponse as FactoryConfigResponse, PairType},
+        router::ConfigResponse as RouterConfigResponse,
+    };
+    use astroport_dca::dca::{AllowedAssets, ExecuteMsg};
+    use cosmwasm_std::{
+        testing::mock_info, to_binary, Addr, ContractResult, Decimal, SystemError, SystemResult,
+        WasmQuery,
+    };
     use cw_multi_test::{App, Executor};
 
     use crate::{
+        contract::execute,
         error::ContractError,
-        state::{Config, CONFIG},
+        state::{
+            Config, CONFIG, DEFAULT_CONFIG_TIMELOCK, DEFAULT_MAX_ORDERS_PER_USER, MAX_PROTOCOL_FEE,
+            PENDING_CONFIG,
+        },
         tests::{
-            app_mock_instantiate, mock_app, mock_creator, read_item, store_dca_module_code,
-            store_factory_code,
+            app_mock_instantiate, mock_app, mock_creator, mock_instantiate, read_item,
+            store_dca_module_code, store_factory_code,
         },
     };
 
@@ -123,32 +120,47 @@ mod test {
         (app, dca_addr)
     }
 
+    /// Executes the queued configuration update once the timelock has passed
+    fn execute_pending_config(app: &mut App, dca_addr: &Addr) {
+        app.update_block(|block| block.time = block.time.plus_seconds(DEFAULT_CONFIG_TIMELOCK));
+
+        app.execute_contract(
+            Addr::unchecked("factory_owner"),
+            dca_addr.clone(),
+            &ExecuteMsg::ExecutePendingConfig {},
+            &[],
+        )
+        .unwrap();
+    }
+
     #[test]
     fn does_update() {
         let (mut app, dca_addr) = instantiate();
 
         let config = read_item(&app, &dca_addr, CONFIG);
 
-        let mut new_fee_assets = vec![Asset {
-            amount: Uint128::new(25_000),
-            info: AssetInfo::Token {
-                contract_addr: Addr::unchecked("bad_token"),
-            },
-        }];
-        new_fee_assets.extend_from_slice(&config.whitelisted_fee_assets);
-
-        let mut new_tokens = vec![AssetInfo::Token {
-            contract_addr: Addr::unchecked("bad_token"),
-        }];
-        new_tokens.extend_from_slice(&config.whitelisted_tokens);
-
         let new_config = Config {
+            owner: Addr::unchecked("factory_owner"),
             max_hops: config.max_hops + 1,
             max_spread: Decimal::from_str("0.1").unwrap() + config.max_spread,
             factory_addr: Addr::unchecked("contract0"),
             router_addr: Addr::unchecked("router"),
-            whitelisted_fee_assets: new_fee_assets,
-            whitelisted_tokens: new_tokens,
+            whitelisted_fee_assets: config.whitelisted_fee_assets.clone(),
+            whitelisted_tokens: config.whitelisted_tokens.clone(),
+            protocol_fee: Decimal::percent(1),
+            fee_collector: Some(Addr::unchecked("fee_collector")),
+            max_referral_fee_bps: 0,
+            keeper_config: None,
+            allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+            pair_type_max_spreads: vec![],
+            venues: vec![],
+            guardian: None,
+            config_timelock: DEFAULT_CONFIG_TIMELOCK,
+            allowed_initial_assets: AllowedAssets::All {},
+            allowed_target_assets: AllowedAssets::All {},
+            min_dca_amounts: vec![],
+            max_orders_per_user: DEFAULT_MAX_ORDERS_PER_USER,
+            order_deposit: None,
         };
 
         app.execute_contract(
@@ -156,14 +168,33 @@ mod test {
             dca_addr.clone(),
             &ExecuteMsg::UpdateConfig {
                 max_hops: Some(new_config.max_hops),
-                whitelisted_tokens: Some(new_config.whitelisted_tokens.clone()),
-                whitelisted_fee_assets: Some(new_config.whitelisted_fee_assets.clone()),
                 max_spread: Some(new_config.max_spread),
+                protocol_fee: Some(new_config.protocol_fee),
+                fee_collector: Some("fee_collector".to_string()),
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: None,
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
             },
             &[],
         )
         .unwrap();
 
+        // the update is only applied once the timelock has passed
+        assert_eq!(read_item(&app, &dca_addr, CONFIG), config);
+
+        execute_pending_config(&mut app, &dca_addr);
+
         let config = read_item(&app, &dca_addr, CONFIG);
         assert_eq!(config, new_config);
     }
@@ -179,14 +210,30 @@ mod test {
             dca_addr.clone(),
             &ExecuteMsg::UpdateConfig {
                 max_hops: None,
-                whitelisted_tokens: None,
-                whitelisted_fee_assets: None,
                 max_spread: None,
+                protocol_fee: None,
+                fee_collector: None,
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: None,
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
             },
             &[],
         )
         .unwrap();
 
+        execute_pending_config(&mut app, &dca_addr);
+
         let new_config = read_item(&app, &dca_addr, CONFIG);
 
         assert_eq!(config, new_config);
@@ -202,16 +249,23 @@ mod test {
                 dca_addr,
                 &ExecuteMsg::UpdateConfig {
                     max_hops: Some(1),
-                    whitelisted_tokens: Some(vec![AssetInfo::NativeToken {
-                        denom: "ugbp".to_string(),
-                    }]),
-                    whitelisted_fee_assets: Some(vec![Asset {
-                        amount: Uint128::new(5_000),
-                        info: AssetInfo::Token {
-                            contract_addr: Addr::unchecked("bad_addr"),
-                        },
-                    }]),
                     max_spread: Some(Decimal::from_str("0.075").unwrap()),
+                    protocol_fee: None,
+                    fee_collector: None,
+                    max_referral_fee_bps: None,
+                    keeper_config: None,
+                    allowed_pair_types: None,
+                    pair_type_max_spreads: None,
+                    venues: None,
+                    guardian: None,
+                    config_timelock: None,
+                    factory_addr: None,
+                    router_addr: None,
+                    allowed_initial_assets: None,
+                    allowed_target_assets: None,
+                    min_dca_amounts: None,
+                    max_orders_per_user: None,
+                    order_deposit: None,
                 },
                 &[],
             )
@@ -222,4 +276,276 @@ mod test {
             ContractError::Unauthorized {}
         );
     }
+
+    #[test]
+    fn does_check_protocol_fee() {
+        let (mut app, dca_addr) = instantiate();
+
+        let update =
+            |protocol_fee: Decimal, fee_collector: Option<String>| ExecuteMsg::UpdateConfig {
+                max_hops: None,
+                max_spread: None,
+                protocol_fee: Some(protocol_fee),
+                fee_collector,
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: None,
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
+            };
+
+        // protocol fee cannot exceed the hard cap
+        let res = app
+            .execute_contract(
+                Addr::unchecked("factory_owner"),
+                dca_addr.clone(),
+                &update(
+                    MAX_PROTOCOL_FEE + Decimal::permille(1),
+                    Some("fee_collector".to_string()),
+                ),
+                &[],
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            res.downcast::<ContractError>().unwrap(),
+            ContractError::ProtocolFeeTooLarge {
+                max: MAX_PROTOCOL_FEE
+            }
+        );
+
+        // protocol fee cannot be taken without a fee collector
+        let res = app
+            .execute_contract(
+                Addr::unchecked("factory_owner"),
+                dca_addr.clone(),
+                &update(Decimal::percent(1), None),
+                &[],
+            )
+            .unwrap_err();
+
+        assert_eq!(
+            res.downcast::<ContractError>().unwrap(),
+            ContractError::MissingFeeCollector {}
+        );
+
+        // the hard cap itself is allowed
+        app.execute_contract(
+            Addr::unchecked("factory_owner"),
+            dca_addr.clone(),
+            &update(MAX_PROTOCOL_FEE, Some("fee_collector".to_string())),
+            &[],
+        )
+        .unwrap();
+
+        execute_pending_config(&mut app, &dca_addr);
+
+        let config = read_item(&app, &dca_addr, CONFIG);
+        assert_eq!(config.protocol_fee, MAX_PROTOCOL_FEE);
+        assert_eq!(config.fee_collector, Some(Addr::unchecked("fee_collector")));
+    }
+
+    #[test]
+    fn does_validate_astroport_contracts() {
+        let (mut deps, mut env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        // new_router uses new_factory, while old_router uses the current factory
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, .. } => match contract_addr.as_str() {
+                "new_factory" => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&FactoryConfigResponse {
+                        owner: Addr::unchecked("factory_owner"),
+                        pair_configs: vec![],
+                        token_code_id: 0,
+                        fee_address: None,
+                        generator_address: None,
+                        whitelist_code_id: 0,
+                    })
+                    .unwrap(),
+                )),
+                "new_router" | "old_router" => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&RouterConfigResponse {
+                        astroport_factory: match contract_addr.as_str() {
+                            "new_router" => "new_factory".to_string(),
+                            _ => "factory".to_string(),
+                        },
+                    })
+                    .unwrap(),
+                )),
+                _ => SystemResult::Err(SystemError::NoSuchContract {
+                    addr: contract_addr.clone(),
+                }),
+            },
+            _ => unimplemented!(),
+        });
+
+        let update = |factory_addr: &str, router_addr: &str| ExecuteMsg::UpdateConfig {
+            max_hops: None,
+            max_spread: None,
+            protocol_fee: None,
+            fee_collector: None,
+            max_referral_fee_bps: None,
+            keeper_config: None,
+            allowed_pair_types: None,
+            pair_type_max_spreads: None,
+            venues: None,
+            guardian: None,
+            config_timelock: None,
+            factory_addr: Some(factory_addr.to_string()),
+            router_addr: Some(router_addr.to_string()),
+            allowed_initial_assets: None,
+            allowed_target_assets: None,
+            min_dca_amounts: None,
+            max_orders_per_user: None,
+            order_deposit: None,
+        };
+
+        // the factory has to be an Astroport factory
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            update("not_a_factory", "new_router"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InvalidFactory {
+                addr: "not_a_factory".to_string()
+            }
+        );
+
+        // the router has to use the factory
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            update("new_factory", "old_router"),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::InvalidRouter {
+                addr: "old_router".to_string()
+            }
+        );
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            update("new_factory", "new_router"),
+        )
+        .unwrap();
+
+        env.block.time = env.block.time.plus_seconds(DEFAULT_CONFIG_TIMELOCK);
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::ExecutePendingConfig {},
+        )
+        .unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.factory_addr, Addr::unchecked("new_factory"));
+        assert_eq!(config.router_addr, Addr::unchecked("new_router"));
+    }
+
+    #[test]
+    fn does_reject_update_while_pending() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let add_tokens = || ExecuteMsg::AddWhitelistedTokens {
+            tokens: vec![AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            }],
+        };
+
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::UpdateConfig {
+                max_hops: None,
+                max_spread: Some(Decimal::percent(10)),
+                protocol_fee: None,
+                fee_collector: None,
+                max_referral_fee_bps: None,
+                keeper_config: None,
+                allowed_pair_types: None,
+                pair_type_max_spreads: None,
+                venues: None,
+                guardian: None,
+                config_timelock: None,
+                factory_addr: None,
+                router_addr: None,
+                allowed_initial_assets: None,
+                allowed_target_assets: None,
+                min_dca_amounts: None,
+                max_orders_per_user: None,
+                order_deposit: None,
+            },
+        )
+        .unwrap();
+
+        // queueing tokens would discard the pending update
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            add_tokens(),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::PendingConfigExists {});
+        assert_eq!(
+            PENDING_CONFIG
+                .load(&deps.storage)
+                .unwrap()
+                .update
+                .max_spread,
+            Some(Decimal::percent(10))
+        );
+
+        // the tokens can be queued once the pending update is cancelled
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("factory_owner", &[]),
+            ExecuteMsg::CancelPendingConfig {},
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env,
+            mock_info("factory_owner", &[]),
+            add_tokens(),
+        )
+        .unwrap();
+        assert!(PENDING_CONFIG
+            .load(&deps.storage)
+            .unwrap()
+            .update
+            .add_whitelisted_tokens
+            .is_some());
+    }
 }
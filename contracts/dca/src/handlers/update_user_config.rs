@@ -30,11 +30,16 @@ where
 ///
 /// * `max_spread` - A [`Decimal`] value wrapped in an [`Option`] which represents the new maximum
 /// spread for each DCA purchase. If `None`, the user will use the config set by the contract.
+///
+/// * `max_price_impact` - A [`Decimal`] value wrapped in an [`Option`] which represents the new
+/// maximum price impact of each DCA purchase. If `None`, the price impact is not checked unless
+/// the DCA order sets a maximum.
 pub fn update_user_config(
     deps: DepsMut,
     info: MessageInfo,
     max_hops: Option<u32>,
     max_spread: Option<Decimal>,
+    max_price_impact: Option<Decimal>,
 ) -> Result<Response, ContractError> {
     let config = USER_CONFIG
         .may_load(deps.storage, &info.sender)?
@@ -43,15 +48,19 @@ pub fn update_user_config(
     let new_config = UserConfig {
         max_hops,
         max_spread,
+        max_price_impact,
         ..config
     };
 
+    new_config.validate()?;
+
     USER_CONFIG.save(deps.storage, &info.sender, &new_config)?;
 
     Ok(Response::new().add_attributes(vec![
         attr("action", "update_user_config"),
         attr("max_hops", serde_option(max_hops)),
         attr("max_spread", serde_option(max_spread)),
+        attr("max_price_impact", serde_option(max_price_impact)),
     ]))
 }
 
@@ -69,7 +78,8 @@ mod tests {
 
     use crate::{
         contract::execute,
-        state::{UserConfig, USER_CONFIG},
+        error::ContractError,
+        state::{UserConfig, MAX_SPREAD, USER_CONFIG},
         tests::{mock_creator, mock_instantiate},
     };
 
@@ -80,6 +90,7 @@ mod tests {
         let msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(6),
             max_spread: Some(Decimal::from_str("0.025").unwrap()),
+            max_price_impact: Some(Decimal::percent(10)),
         };
 
         // does send the write response
@@ -89,7 +100,8 @@ mod tests {
             Response::new().add_attributes(vec![
                 attr("action", "update_user_config"),
                 attr("max_hops", "6"),
-                attr("max_spread", "0.025")
+                attr("max_spread", "0.025"),
+                attr("max_price_impact", "0.1")
             ])
         );
 
@@ -103,7 +115,8 @@ mod tests {
                 last_id: 0,
                 max_hops: Some(6),
                 max_spread: Some(Decimal::from_str("0.025").unwrap()),
-                tip_balance: vec![]
+                tip_balance: vec![],
+                max_price_impact: Some(Decimal::percent(10)),
             }
         )
     }
@@ -125,6 +138,7 @@ mod tests {
         let msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(6),
             max_spread: Some(Decimal::from_str("0.025").unwrap()),
+            max_price_impact: None,
         };
 
         // add tip
@@ -155,10 +169,12 @@ mod tests {
         let update_msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(4),
             max_spread: Some(Decimal::from_str("0.025").unwrap()),
+            max_price_impact: None,
         };
         let reset_msg = ExecuteMsg::UpdateUserConfig {
             max_hops: Some(6),
             max_spread: None,
+            max_price_impact: None,
         };
 
         // does reset the config
@@ -175,8 +191,46 @@ mod tests {
                 last_id: 0,
                 max_hops: Some(6),
                 max_spread: None,
-                tip_balance: vec![]
+                tip_balance: vec![],
+                max_price_impact: None,
             }
         )
     }
+
+    #[test]
+    fn does_validate_overrides() {
+        let mut deps = mock_dependencies();
+
+        let msg = |max_hops, max_spread| ExecuteMsg::UpdateUserConfig {
+            max_hops,
+            max_spread,
+            max_price_impact: None,
+        };
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            msg(Some(0), None),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::InvalidMaxHops {});
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            msg(None, Some(MAX_SPREAD + Decimal::permille(1))),
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::MaxSpreadTooLarge { max: MAX_SPREAD });
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_creator(),
+            msg(Some(1), Some(MAX_SPREAD)),
+        )
+        .unwrap();
+    }
 }
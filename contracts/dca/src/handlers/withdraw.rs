@@ -1,13 +1,11 @@
 use astroport::asset::{Asset, AssetInfo};
 use cosmwasm_std::{attr, coins, BankMsg, DepsMut, MessageInfo, Response};
 
-use crate::{
-    error::ContractError,
-    state::{CONFIG, USER_CONFIG},
-};
+use crate::{error::ContractError, state::USER_CONFIG};
 
 /// ## Description
-/// Withdraws a users bot tip from the contract.
+/// Withdraws a users bot tip from the contract, including tips in assets that are no longer
+/// whitelisted fee assets.
 ///
 /// Returns a [`ContractError`] as a failure, otherwise returns a [`Response`] with the specified
 /// attributes if the operation was successful.
@@ -26,15 +24,9 @@ pub fn withdraw(
         .may_load(deps.storage, &info.sender)?
         .unwrap_or_default();
 
-    let config = CONFIG.load(deps.storage)?;
-
     let mut send_msgs: Vec<BankMsg> = vec![];
 
     for asset in assets {
-        if !config.is_whitelisted_fee_asset(&asset.info) {
-            return Err(ContractError::NonWhitelistedTipAsset { asset: asset.info });
-        }
-
         let user_balance = user_config
             .tip_balance
             .iter_mut()
@@ -247,7 +239,7 @@ mod tests {
     }
 
     #[test]
-    fn cant_withdraw_non_whitelisted_asset() {
+    fn cant_withdraw_undeposited_asset() {
         let (mut deps, env) = mock_instantiate(
             Addr::unchecked("factory"),
             Addr::unchecked("router"),
@@ -278,7 +270,7 @@ mod tests {
         .unwrap_err();
         assert_eq!(
             res,
-            ContractError::NonWhitelistedTipAsset {
+            ContractError::TipAssetNotDeposited {
                 asset: withdraw_asset.info
             }
         )
@@ -6,6 +6,13 @@ mod handlers;
 mod queries;
 
 mod get_token_allowance;
+mod matching;
+mod migration;
+mod price_impact;
+mod route;
+mod storage_overlay;
+mod twap;
+mod venue;
 
 #[cfg(test)]
 pub(crate) mod tests;
@@ -0,0 +1,593 @@
+use astroport::{
+    asset::{Asset, AssetInfo},
+    querier::query_pair_info,
+    router::SwapOperation,
+};
+use cosmwasm_std::{
+    attr, to_binary, Addr, CosmosMsg, Env, Event, QuerierWrapper, StdError, StdResult, Storage,
+    Uint128, WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use crate::{
+    error::ContractError,
+    state::{
+        record_execution_return, BatchSwap, Config, PurchaseRecord, MAX_TWAP_WINDOW,
+        PURCHASE_HISTORY,
+    },
+    twap::{average_price, observe_price},
+};
+
+/// ## Description
+/// Matches batched swaps of opposite DCA purchases through the same Astroport pair against each
+/// other at the TWAP of the pair, so that only the residual amounts need to be swapped.
+///
+/// The TWAP is calculated since the most recent stored observation of the cumulative price of the
+/// pair that is at least as old as the minimum TWAP window, so that the price cannot be set by
+/// observing the pair right before a match. Swaps are not matched if there is no such observation,
+/// or if it is older than the maximum TWAP window so that the price would be stale.
+///
+/// The matched amounts are removed from the batched swaps, and swaps that were matched completely
+/// are removed. Returns the messages settling the matches, where the contributions of all matches
+/// are collected before they are paid out, and an event for each match.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `querier` - A [`QuerierWrapper`] used to query the factory and the pairs.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `config` - The contract [`Config`].
+///
+/// * `swaps` - The [`Vec<BatchSwap>`] of the batched execution.
+pub fn match_opposite_swaps(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    config: &Config,
+    swaps: &mut Vec<BatchSwap>,
+) -> Result<(Vec<CosmosMsg>, Vec<Event>), ContractError> {
+    let mut transfers = vec![];
+    let mut payouts = vec![];
+    let mut events = vec![];
+
+    for i in 0..swaps.len() {
+        for j in (i + 1)..swaps.len() {
+            let (offer, ask) = match single_hop(&swaps[i]) {
+                Some(assets) => assets,
+                None => break,
+            };
+            if single_hop(&swaps[j]) != Some((ask, offer))
+                || swaps[i].offer_asset.amount.is_zero()
+                || swaps[j].offer_asset.amount.is_zero()
+            {
+                continue;
+            }
+
+            let (offer, ask) = (offer.clone(), ask.clone());
+            let pair_addr = match query_pair_info(
+                querier,
+                config.factory_addr.clone(),
+                &[offer.clone(), ask.clone()],
+            ) {
+                Ok(pair) => pair.contract_addr,
+                Err(_) => continue,
+            };
+
+            let (price_numerator, price_denominator) =
+                match observe_twap(storage, querier, env, &pair_addr, &offer)? {
+                    Some(price) => price,
+                    None => continue,
+                };
+
+            // the amounts matched at the TWAP, limited by the smaller side
+            let offer_amount = swaps[i].offer_asset.amount;
+            let ask_amount = swaps[j].offer_asset.amount;
+            let offer_value = offer_amount.multiply_ratio(price_numerator, price_denominator);
+            let (matched_offer, matched_ask) = match offer_value <= ask_amount {
+                true => (offer_amount, offer_value),
+                false => (
+                    ask_amount.multiply_ratio(price_denominator, price_numerator),
+                    ask_amount,
+                ),
+            };
+
+            if matched_offer.is_zero() || matched_ask.is_zero() {
+                continue;
+            }
+
+            // split the matched amounts between the purchases of each swap, where rounding never
+            // takes more than the matched amount, and each side receives exactly what the other
+            // side contributed
+            let offer_contributions = split(matched_offer, &swaps[i]);
+            let ask_contributions = split(matched_ask, &swaps[j]);
+            let offer_matched = offer_contributions.iter().sum::<Uint128>();
+            let ask_matched = ask_contributions.iter().sum::<Uint128>();
+
+            let contract_addr = &env.contract.address;
+            let time = env.block.time.seconds();
+            for (swap, contributions, received) in [
+                (i, &offer_contributions, (&ask, ask_matched)),
+                (j, &ask_contributions, (&offer, offer_matched)),
+            ] {
+                let (swap_transfers, swap_payouts) = fill(
+                    storage,
+                    querier,
+                    contract_addr,
+                    &mut swaps[swap],
+                    contributions,
+                    &Asset {
+                        info: received.0.clone(),
+                        amount: received.1,
+                    },
+                    time,
+                )?;
+                transfers.extend(swap_transfers);
+                payouts.extend(swap_payouts);
+            }
+
+            events.push(Event::new("dca_match").add_attributes(vec![
+                attr("offer_asset", Asset {
+                    info: offer,
+                    amount: offer_matched,
+                }.to_string()),
+                attr("ask_asset", Asset {
+                    info: ask,
+                    amount: ask_matched,
+                }.to_string()),
+            ]));
+        }
+    }
+
+    swaps.retain(|swap| !swap.offer_asset.amount.is_zero());
+
+    transfers.extend(payouts);
+    Ok((transfers, events))
+}
+
+/// ## Description
+/// Returns the offered and purchased asset of a batched swap if it is a single Astroport swap.
+fn single_hop(swap: &BatchSwap) -> Option<(&AssetInfo, &AssetInfo)> {
+    match swap.hops.as_slice() {
+        [SwapOperation::AstroSwap {
+            offer_asset_info,
+            ask_asset_info,
+        }] => Some((offer_asset_info, ask_asset_info)),
+        _ => None,
+    }
+}
+
+/// ## Description
+/// Observes the cumulative price of `offer_asset_info` in the pair at `pair_addr`, returning the
+/// TWAP since the stored observation as a ratio of the purchased asset per offered asset.
+///
+/// Returns [`None`] if there is no stored observation to anchor the TWAP, or if it is older than
+/// [`MAX_TWAP_WINDOW`] so that the TWAP is no longer current.
+fn observe_twap(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    env: &Env,
+    pair_addr: &Addr,
+    offer_asset_info: &AssetInfo,
+) -> StdResult<Option<(Uint128, Uint128)>> {
+    let now = env.block.time.seconds();
+
+    let price = match observe_price(storage, querier, now, pair_addr, offer_asset_info)? {
+        (price_cumulative, Some(anchor))
+            if now.saturating_sub(anchor.observed_at) <= MAX_TWAP_WINDOW =>
+        {
+            average_price(&anchor, price_cumulative, now)?
+        }
+        _ => return Ok(None),
+    };
+
+    Ok(Some(price).filter(|(numerator, _)| !numerator.is_zero()))
+}
+
+/// ## Description
+/// Splits `amount` between the purchases of a batched swap proportionally to their share in the
+/// swap, rounding down.
+fn split(amount: Uint128, swap: &BatchSwap) -> Vec<Uint128> {
+    swap.shares
+        .iter()
+        .map(|share| amount.multiply_ratio(share.amount, swap.offer_asset.amount))
+        .collect()
+}
+
+/// ## Description
+/// Fills the `contributions` of the purchases of a batched swap in a match, which receive
+/// `received` proportionally to their contribution with the last contributing purchase receiving
+/// the rounding remainder, and removes them from the swap. The share of each purchase performed by
+/// a registered keeper is added to the return of its keeper execution.
+///
+/// Returns the messages transferring the contributions in cw20 tokens to the contract, and the
+/// messages paying the received asset out to the users.
+fn fill(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    contract_addr: &Addr,
+    swap: &mut BatchSwap,
+    contributions: &[Uint128],
+    received: &Asset,
+    time: u64,
+) -> Result<(Vec<CosmosMsg>, Vec<CosmosMsg>), ContractError> {
+    let contributed = contributions.iter().sum::<Uint128>();
+    let last = contributions
+        .iter()
+        .rposition(|contribution| !contribution.is_zero());
+
+    let mut transfers = vec![];
+    let mut payouts = vec![];
+    let mut paid = Uint128::zero();
+    for (i, (share, contribution)) in swap.shares.iter_mut().zip(contributions).enumerate() {
+        if contribution.is_zero() {
+            continue;
+        }
+
+        // native tokens are already held by the contract
+        if let AssetInfo::Token {
+            contract_addr: token,
+        } = &swap.offer_asset.info
+        {
+            transfers.push(
+                WasmMsg::Execute {
+                    contract_addr: token.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                        owner: share.user.to_string(),
+                        recipient: contract_addr.to_string(),
+                        amount: *contribution,
+                    })?,
+                }
+                .into(),
+            );
+        }
+
+        let return_asset = Asset {
+            info: received.info.clone(),
+            amount: match Some(i) == last {
+                true => received.amount.checked_sub(paid)?,
+                false => received.amount.multiply_ratio(*contribution, contributed),
+            },
+        };
+        paid = paid.checked_add(return_asset.amount)?;
+
+        if let Some(execution_id) = share.execution_id {
+            record_execution_return(storage, execution_id, return_asset.amount)?;
+        }
+
+        PURCHASE_HISTORY.update::<_, StdError>(storage, (&share.user, share.id), |history| {
+            let mut history = history.unwrap_or_default();
+            history.push(PurchaseRecord {
+                offer_asset: Asset {
+                    info: swap.offer_asset.info.clone(),
+                    amount: *contribution,
+                },
+                return_asset: Some(return_asset.clone()),
+                executed_at: time,
+                matched: true,
+                protocol_fee: share.protocol_fee.take(),
+            });
+            Ok(history)
+        })?;
+
+        if !return_asset.amount.is_zero() {
+            payouts.push(return_asset.into_msg(querier, share.user.clone())?);
+        }
+
+        share.amount = share.amount.checked_sub(*contribution)?;
+    }
+
+    swap.offer_asset.amount = swap.offer_asset.amount.checked_sub(contributed)?;
+    swap.shares.retain(|share| !share.amount.is_zero());
+
+    Ok((transfers, payouts))
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::{
+        asset::{Asset, AssetInfo, PairInfo},
+        factory::{PairType, QueryMsg as FactoryQueryMsg},
+        pair::CumulativePricesResponse,
+        router::SwapOperation,
+    };
+    use cosmwasm_std::{
+        from_binary, testing::MockQuerier, to_binary, Addr, BankMsg, Coin, ContractResult,
+        CosmosMsg, Decimal, QuerierWrapper, SystemResult, Uint128, WasmQuery,
+    };
+
+    use crate::{
+        state::{
+            BatchShare, BatchSwap, KeeperExecution, PriceObservation, PriceObservations,
+            PurchaseRecord, CONFIG, KEEPER_EXECUTIONS, MAX_TWAP_WINDOW, MIN_TWAP_WINDOW,
+            PRICE_OBSERVATIONS, PURCHASE_HISTORY,
+        },
+        tests::mock_instantiate,
+    };
+
+    use super::match_opposite_swaps;
+
+    /// Mocks the factory and a pair where, over the 1_000 seconds since an observation of a
+    /// cumulative price of zero, 1 uluna was worth 2 ukrw on average
+    fn mock_pair(querier: &mut MockQuerier) {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let assets = [
+            Asset {
+                amount: Uint128::new(1_000_000),
+                info: uluna,
+            },
+            Asset {
+                amount: Uint128::new(2_000_000),
+                info: ukrw,
+            },
+        ];
+        querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+                match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    _ => unimplemented!(),
+                }
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "pair" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&CumulativePricesResponse {
+                        assets: assets.clone(),
+                        total_share: Uint128::new(1_000_000),
+                        price0_cumulative_last: Uint128::new(2_000_000_000),
+                        price1_cumulative_last: Uint128::new(500_000_000),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => unimplemented!(),
+        });
+    }
+
+    fn swap(
+        offer_asset_info: &AssetInfo,
+        ask_asset_info: &AssetInfo,
+        shares: Vec<(&str, u128)>,
+    ) -> BatchSwap {
+        BatchSwap {
+            offer_asset: Asset {
+                info: offer_asset_info.clone(),
+                amount: Uint128::new(shares.iter().map(|(_, amount)| amount).sum()),
+            },
+            target_asset: ask_asset_info.clone(),
+            hops: vec![SwapOperation::AstroSwap {
+                offer_asset_info: offer_asset_info.clone(),
+                ask_asset_info: ask_asset_info.clone(),
+            }],
+            max_spread: Decimal::percent(5),
+            max_price_impact: None,
+            shares: shares
+                .into_iter()
+                .map(|(user, amount)| BatchShare {
+                    user: Addr::unchecked(user),
+                    id: 1,
+                    amount: Uint128::new(amount),
+                    protocol_fee: None,
+                    execution_id: None,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn does_match_opposite_swaps() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        mock_pair(&mut deps.querier);
+
+        let now = env.block.time.seconds();
+        let observation = PriceObservation {
+            price_cumulative: Uint128::zero(),
+            observed_at: now - 1_000,
+        };
+        PRICE_OBSERVATIONS
+            .save(
+                &mut deps.storage,
+                (&Addr::unchecked("pair"), uluna.to_string()),
+                &PriceObservations {
+                    anchor: None,
+                    latest: observation.clone(),
+                },
+            )
+            .unwrap();
+
+        let mut swaps = vec![
+            swap(&uluna, &ukrw, vec![("alice", 10_000)]),
+            swap(&ukrw, &uluna, vec![("bob", 20_000), ("carol", 10_000)]),
+        ];
+        let bob_fee = Asset {
+            info: ukrw.clone(),
+            amount: Uint128::new(200),
+        };
+        swaps[1].shares[0].protocol_fee = Some(bob_fee.clone());
+
+        // the purchase of carol was performed by a registered keeper
+        swaps[1].shares[1].execution_id = Some(1);
+        KEEPER_EXECUTIONS
+            .save(
+                &mut deps.storage,
+                1,
+                &KeeperExecution {
+                    keeper: Addr::unchecked("keeper"),
+                    user: Addr::unchecked("carol"),
+                    order_id: 1,
+                    offer_asset: Asset {
+                        info: ukrw.clone(),
+                        amount: Uint128::new(10_000),
+                    },
+                    hops: swaps[1].hops.clone(),
+                    return_amount: Uint128::zero(),
+                    executed_at: now,
+                    snapshots: None,
+                },
+            )
+            .unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let querier = QuerierWrapper::new(&deps.querier);
+        let (messages, events) =
+            match_opposite_swaps(&mut deps.storage, &querier, &env, &config, &mut swaps).unwrap();
+
+        // the 10_000 uluna of alice are matched against 19_999 ukrw, rounded down from 20_000, where
+        // carol receives the remainder of the uluna of alice
+        let send = |to: &str, amount: u128, denom: &str| -> CosmosMsg {
+            BankMsg::Send {
+                to_address: to.to_string(),
+                amount: vec![Coin::new(amount, denom)],
+            }
+            .into()
+        };
+        assert_eq!(
+            messages,
+            vec![
+                send("alice", 19_999, "ukrw"),
+                send("bob", 6_666, "uluna"),
+                send("carol", 3_334, "uluna"),
+            ]
+        );
+        assert_eq!(events.len(), 1);
+
+        // only the residual ukrw is left to be swapped, where the protocol fee of bob was already
+        // recorded with the matched part of the purchase
+        let mut residual = swap(&ukrw, &uluna, vec![("bob", 6_667), ("carol", 3_334)]);
+        residual.shares[1].execution_id = Some(1);
+        assert_eq!(swaps, vec![residual]);
+
+        // the matched part of the purchase of carol is recorded in its keeper execution
+        assert_eq!(
+            KEEPER_EXECUTIONS
+                .load(&deps.storage, 1)
+                .unwrap()
+                .return_amount,
+            Uint128::new(3_334)
+        );
+
+        let history = PURCHASE_HISTORY
+            .load(&deps.storage, (&Addr::unchecked("alice"), 1))
+            .unwrap();
+        assert_eq!(
+            history,
+            vec![PurchaseRecord {
+                offer_asset: Asset {
+                    info: uluna.clone(),
+                    amount: Uint128::new(10_000),
+                },
+                return_asset: Some(Asset {
+                    info: ukrw.clone(),
+                    amount: Uint128::new(19_999),
+                }),
+                executed_at: now,
+                matched: true,
+                protocol_fee: None,
+            }]
+        );
+
+        let history = PURCHASE_HISTORY
+            .load(&deps.storage, (&Addr::unchecked("bob"), 1))
+            .unwrap();
+        assert_eq!(history[0].protocol_fee, Some(bob_fee));
+
+        // the observation anchors the next TWAPs until the new observation is old enough, so that
+        // matching cannot shorten their window
+        let observations = PRICE_OBSERVATIONS
+            .load(&deps.storage, (&Addr::unchecked("pair"), uluna.to_string()))
+            .unwrap();
+        assert_eq!(observations.anchor, Some(observation));
+        assert_eq!(observations.latest.observed_at, now);
+    }
+
+    #[test]
+    fn does_not_match_outside_twap_window() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+        mock_pair(&mut deps.querier);
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let key = (&Addr::unchecked("pair"), uluna.to_string());
+        let now = env.block.time.seconds();
+        let config = CONFIG.load(&deps.storage).unwrap();
+
+        for (observed_at, replaced) in [
+            // the window is too short to resist manipulation, so the observation is kept
+            (now - MIN_TWAP_WINDOW + 1, false),
+            // the window is too long for the price to be current, so the observation is replaced
+            // and only anchors TWAPs that are not used for matching
+            (now - MAX_TWAP_WINDOW - 1, true),
+        ] {
+            PRICE_OBSERVATIONS
+                .save(
+                    &mut deps.storage,
+                    key.clone(),
+                    &PriceObservations {
+                        anchor: None,
+                        latest: PriceObservation {
+                            price_cumulative: Uint128::zero(),
+                            observed_at,
+                        },
+                    },
+                )
+                .unwrap();
+
+            let mut swaps = vec![
+                swap(&uluna, &ukrw, vec![("alice", 10_000)]),
+                swap(&ukrw, &uluna, vec![("bob", 20_000)]),
+            ];
+            let querier = QuerierWrapper::new(&deps.querier);
+            let (messages, events) =
+                match_opposite_swaps(&mut deps.storage, &querier, &env, &config, &mut swaps)
+                    .unwrap();
+            assert!(messages.is_empty());
+            assert!(events.is_empty());
+            assert_eq!(swaps.len(), 2);
+
+            let observation = PRICE_OBSERVATIONS.load(&deps.storage, key.clone()).unwrap();
+            assert_eq!(
+                observation.latest.observed_at,
+                match replaced {
+                    true => now,
+                    false => observed_at,
+                }
+            );
+        }
+    }
+}
@@ -0,0 +1,555 @@
+use astroport::{
+    asset::{addr_validate_to_lower, Asset, AssetInfo},
+    querier::query_factory_config,
+};
+use astroport_dca::dca::{AllowedAssets, FeeAsset, MigrateMsg};
+use cosmwasm_std::{Addr, Decimal, DepsMut, Uint128};
+use cw_storage_plus::Item;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ContractError,
+    state::{
+        default_allowed_pair_types, Config, CONFIG, DEFAULT_CONFIG_TIMELOCK,
+        DEFAULT_MAX_ORDERS_PER_USER, MAX_SPREAD,
+    },
+};
+
+/// A semantic version of the contract as a `(major, minor, patch)` triple
+pub type Version = (u64, u64, u64);
+
+/// A state migration which upgrades the state stored by the previous version of the contract
+type Migration = fn(DepsMut, &MigrateMsg) -> Result<(), ContractError>;
+
+/// The state migrations of every version which changed the format of the stored state, in
+/// ascending order of their versions
+const MIGRATIONS: &[(Version, Migration)] = &[((2, 0, 0), migrate_from_v1_0)];
+
+/// Stores the contract configuration of version 1.0
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ConfigV1_0 {
+    pub max_hops: u32,
+    pub max_spread: Decimal,
+    pub whitelisted_fee_assets: Vec<Asset>,
+    pub whitelisted_tokens: Vec<AssetInfo>,
+    pub factory_addr: Addr,
+    pub router_addr: Addr,
+}
+
+/// The contract configuration of version 1.0
+pub const CONFIG_V1_0: Item<ConfigV1_0> = Item::new("config");
+
+/// ## Description
+/// Parses a semantic version such as `1.0.0` into a [`Version`].
+///
+/// Returns a [`ContractError`] if the version is not made of three numeric components.
+/// ## Arguments
+/// * `version` - The version to parse.
+pub fn parse_version(version: &str) -> Result<Version, ContractError> {
+    let invalid = || ContractError::InvalidVersion {
+        version: version.to_string(),
+    };
+
+    let mut parts = version.split('.').map(|part| part.parse::<u64>());
+    match (parts.next(), parts.next(), parts.next(), parts.next()) {
+        (Some(Ok(major)), Some(Ok(minor)), Some(Ok(patch)), None) => Ok((major, minor, patch)),
+        _ => Err(invalid()),
+    }
+}
+
+/// ## Description
+/// Runs the state migration of every version after `from` up to and including `to`, in order.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `from` - The [`Version`] of the contract the state was stored by.
+///
+/// * `to` - The [`Version`] of the contract the state is migrated to.
+///
+/// * `msg` - The [`MigrateMsg`] the contract is migrated with.
+pub fn migrate_state(
+    mut deps: DepsMut,
+    from: Version,
+    to: Version,
+    msg: &MigrateMsg,
+) -> Result<(), ContractError> {
+    for (version, migration) in MIGRATIONS {
+        if from < *version && *version <= to {
+            migration(deps.branch(), msg)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Sets the optional configuration parameters of a [`MigrateMsg`] after the state was migrated,
+/// and validates the resulting configuration.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `msg` - The [`MigrateMsg`] the contract is migrated with.
+pub fn apply_migrate_msg(deps: DepsMut, msg: MigrateMsg) -> Result<(), ContractError> {
+    let mut config = CONFIG.load(deps.storage)?;
+
+    if let Some(owner) = msg.owner {
+        config.owner = addr_validate_to_lower(deps.api, &owner)?;
+    }
+
+    if let Some(guardian) = msg.guardian {
+        config.guardian = Some(addr_validate_to_lower(deps.api, &guardian)?);
+    }
+
+    if let Some(config_timelock) = msg.config_timelock {
+        config.config_timelock = config_timelock;
+    }
+
+    if let Some(max_orders_per_user) = msg.max_orders_per_user {
+        config.max_orders_per_user = max_orders_per_user;
+    }
+
+    config.validate(deps.api)?;
+
+    CONFIG.save(deps.storage, &config)?;
+
+    Ok(())
+}
+
+/// ## Description
+/// Migrates the state of version 1.0 to version 2.0.
+///
+/// The configuration gains an owner, which is the owner of the factory unless set in the
+/// [`MigrateMsg`], and the fee assets tip their previous amount for each hop. Every other new
+/// field of the configuration takes the value which keeps the previous behaviour. The
+/// configurations of the users and the DCA orders are not rewritten, as their new fields default
+/// to being unset when they are loaded, so that the migration does not depend on the amount of
+/// users.
+///
+/// As version 1.0 did not validate its configuration, fee assets without a tip and duplicate fee
+/// assets and tokens are removed, the maximum spread is capped at [`MAX_SPREAD`] and the maximum
+/// amount of hops is raised to at least one, so that the configuration passes validation.
+/// ## Arguments
+/// * `deps` - A [`DepsMut`] that contains the dependencies.
+///
+/// * `msg` - The [`MigrateMsg`] the contract is migrated with.
+fn migrate_from_v1_0(deps: DepsMut, msg: &MigrateMsg) -> Result<(), ContractError> {
+    let config = CONFIG_V1_0.load(deps.storage)?;
+
+    let mut whitelisted_fee_assets: Vec<FeeAsset> = vec![];
+    for asset in config.whitelisted_fee_assets {
+        if !asset.amount.is_zero()
+            && !whitelisted_fee_assets
+                .iter()
+                .any(|fee_asset| fee_asset.info == asset.info)
+        {
+            whitelisted_fee_assets.push(FeeAsset {
+                info: asset.info,
+                base_fee: Uint128::zero(),
+                per_hop_fee: asset.amount,
+                batch_surcharge: None,
+            });
+        }
+    }
+
+    let mut whitelisted_tokens: Vec<AssetInfo> = vec![];
+    for token in config.whitelisted_tokens {
+        if !whitelisted_tokens.contains(&token) {
+            whitelisted_tokens.push(token);
+        }
+    }
+
+    let owner = match &msg.owner {
+        Some(owner) => addr_validate_to_lower(deps.api, owner)?,
+        None => query_factory_config(&deps.querier, config.factory_addr.clone())?.owner,
+    };
+
+    CONFIG.save(
+        deps.storage,
+        &Config {
+            owner,
+            guardian: None,
+            max_hops: config.max_hops.max(1),
+            max_spread: config.max_spread.min(MAX_SPREAD),
+            whitelisted_fee_assets,
+            whitelisted_tokens,
+            factory_addr: config.factory_addr,
+            router_addr: config.router_addr,
+            protocol_fee: Decimal::zero(),
+            fee_collector: None,
+            max_referral_fee_bps: 0,
+            keeper_config: None,
+            allowed_pair_types: default_allowed_pair_types(),
+            pair_type_max_spreads: vec![],
+            venues: vec![],
+            config_timelock: DEFAULT_CONFIG_TIMELOCK,
+            allowed_initial_assets: AllowedAssets::All {},
+            allowed_target_assets: AllowedAssets::All {},
+            min_dca_amounts: vec![],
+            max_orders_per_user: DEFAULT_MAX_ORDERS_PER_USER,
+            order_deposit: None,
+        },
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        factory::PairType,
+    };
+    use astroport_dca::dca::{AllowedAssets, DcaInfo, FeeAsset, MigrateMsg};
+    use cosmwasm_std::{attr, testing::mock_env, Addr, Decimal, Uint128};
+    use cw2::{get_contract_version, set_contract_version};
+    use cw_storage_plus::Map;
+    use serde::{Deserialize, Serialize};
+
+    use crate::{
+        contract::migrate,
+        error::ContractError,
+        state::{
+            Config, UserConfig, CONFIG, DEFAULT_CONFIG_TIMELOCK, DEFAULT_MAX_ORDERS_PER_USER,
+            USER_CONFIG, USER_DCA,
+        },
+        tests::mock_instantiate,
+    };
+
+    use super::{parse_version, ConfigV1_0, CONFIG_V1_0};
+
+    /// Stores the configuration of a user of version 1.0
+    #[derive(Serialize, Deserialize)]
+    struct UserConfigV1_0 {
+        last_id: u64,
+        max_hops: Option<u32>,
+        max_spread: Option<Decimal>,
+        tip_balance: Vec<Asset>,
+    }
+
+    /// Stores a DCA order of version 1.0
+    #[derive(Serialize, Deserialize)]
+    struct DcaInfoV1_0 {
+        id: u64,
+        initial_asset: Asset,
+        target_asset: AssetInfo,
+        interval: u64,
+        last_purchase: u64,
+        dca_amount: Uint128,
+    }
+
+    const USER_CONFIG_V1_0: Map<&Addr, UserConfigV1_0> = Map::new("user_config");
+    const USER_DCA_V1_0: Map<&Addr, Vec<DcaInfoV1_0>> = Map::new("user_dca");
+
+    #[test]
+    fn does_parse_versions() {
+        assert_eq!(parse_version("1.0.0").unwrap(), (1, 0, 0));
+        assert_eq!(parse_version("2.10.3").unwrap(), (2, 10, 3));
+
+        for version in ["1.0", "1.0.0.0", "1.0.x", ""] {
+            assert_eq!(
+                parse_version(version).unwrap_err(),
+                ContractError::InvalidVersion {
+                    version: version.to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn does_migrate_from_v1_0() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        // replace the state with a snapshot of version 1.0.0
+        set_contract_version(&mut deps.storage, "astroport-dca", "1.0.0").unwrap();
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+
+        CONFIG_V1_0
+            .save(
+                &mut deps.storage,
+                &ConfigV1_0 {
+                    max_hops: 3,
+                    max_spread: Decimal::percent(5),
+                    whitelisted_fee_assets: vec![Asset {
+                        info: uusd.clone(),
+                        amount: Uint128::new(1_000),
+                    }],
+                    whitelisted_tokens: vec![uluna.clone(), uusd.clone()],
+                    factory_addr: Addr::unchecked("factory"),
+                    router_addr: Addr::unchecked("router"),
+                },
+            )
+            .unwrap();
+
+        let user = Addr::unchecked("user");
+        let tip_balance = vec![Asset {
+            info: uusd.clone(),
+            amount: Uint128::new(5_000),
+        }];
+        USER_CONFIG_V1_0
+            .save(
+                &mut deps.storage,
+                &user,
+                &UserConfigV1_0 {
+                    last_id: 1,
+                    max_hops: Some(2),
+                    max_spread: None,
+                    tip_balance: tip_balance.clone(),
+                },
+            )
+            .unwrap();
+
+        let initial_asset = Asset {
+            info: uusd.clone(),
+            amount: Uint128::new(100_000),
+        };
+        USER_DCA_V1_0
+            .save(
+                &mut deps.storage,
+                &user,
+                &vec![DcaInfoV1_0 {
+                    id: 1,
+                    initial_asset: initial_asset.clone(),
+                    target_asset: uluna.clone(),
+                    interval: 3600,
+                    last_purchase: 0,
+                    dca_amount: Uint128::new(10_000),
+                }],
+            )
+            .unwrap();
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+        assert_eq!(
+            res.attributes,
+            vec![
+                attr("action", "migrate"),
+                attr("from_version", "1.0.0"),
+                attr("to_version", env!("CARGO_PKG_VERSION")),
+            ]
+        );
+
+        let version = get_contract_version(&deps.storage).unwrap();
+        assert_eq!(version.contract, "astroport-dca");
+        assert_eq!(version.version, env!("CARGO_PKG_VERSION"));
+
+        // the owner is initialized to the factory owner and the fee amount is tipped per hop
+        assert_eq!(
+            CONFIG.load(&deps.storage).unwrap(),
+            Config {
+                owner: Addr::unchecked("factory_owner"),
+                guardian: None,
+                max_hops: 3,
+                max_spread: Decimal::percent(5),
+                whitelisted_fee_assets: vec![FeeAsset {
+                    info: uusd.clone(),
+                    base_fee: Uint128::zero(),
+                    per_hop_fee: Uint128::new(1_000),
+                    batch_surcharge: None,
+                }],
+                whitelisted_tokens: vec![uluna.clone(), uusd],
+                factory_addr: Addr::unchecked("factory"),
+                router_addr: Addr::unchecked("router"),
+                protocol_fee: Decimal::zero(),
+                fee_collector: None,
+                max_referral_fee_bps: 0,
+                keeper_config: None,
+                allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+                pair_type_max_spreads: vec![],
+                venues: vec![],
+                config_timelock: DEFAULT_CONFIG_TIMELOCK,
+                allowed_initial_assets: AllowedAssets::All {},
+                allowed_target_assets: AllowedAssets::All {},
+                min_dca_amounts: vec![],
+                max_orders_per_user: DEFAULT_MAX_ORDERS_PER_USER,
+                order_deposit: None,
+            }
+        );
+
+        // the configurations of the users and the orders load with their new fields unset
+        assert_eq!(
+            USER_CONFIG.load(&deps.storage, &user).unwrap(),
+            UserConfig {
+                last_id: 1,
+                max_hops: Some(2),
+                max_spread: None,
+                tip_balance,
+                max_price_impact: None,
+            }
+        );
+
+        assert_eq!(
+            USER_DCA.load(&deps.storage, &user).unwrap(),
+            vec![DcaInfo {
+                id: 1,
+                initial_asset,
+                target_asset: uluna,
+                interval: 3600,
+                last_purchase: 0,
+                dca_amount: Uint128::new(10_000),
+                referral: None,
+                preferred_executor: None,
+                exclusivity_seconds: 0,
+                require_canonical_route: false,
+                max_price_impact: None,
+                deposit: None,
+            }]
+        );
+    }
+
+    #[test]
+    fn does_migrate_unvalidated_v1_0_config() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        set_contract_version(&mut deps.storage, "astroport-dca", "1.0.0").unwrap();
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+        let fee = |info: &AssetInfo, amount: u128| Asset {
+            info: info.clone(),
+            amount: Uint128::new(amount),
+        };
+
+        // version 1.0 accepted any configuration
+        CONFIG_V1_0
+            .save(
+                &mut deps.storage,
+                &ConfigV1_0 {
+                    max_hops: 0,
+                    max_spread: Decimal::percent(80),
+                    whitelisted_fee_assets: vec![
+                        fee(&uluna, 0),
+                        fee(&uusd, 1_000),
+                        fee(&uusd, 2_000),
+                    ],
+                    whitelisted_tokens: vec![uluna.clone(), uusd.clone(), uluna.clone()],
+                    factory_addr: Addr::unchecked("factory"),
+                    router_addr: Addr::unchecked("router"),
+                },
+            )
+            .unwrap();
+
+        migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.max_hops, 1);
+        assert_eq!(config.max_spread, Decimal::percent(50));
+        assert_eq!(
+            config.whitelisted_fee_assets,
+            vec![FeeAsset {
+                info: uusd.clone(),
+                base_fee: Uint128::zero(),
+                per_hop_fee: Uint128::new(1_000),
+                batch_surcharge: None,
+            }]
+        );
+        assert_eq!(config.whitelisted_tokens, vec![uluna, uusd]);
+    }
+
+    #[test]
+    fn does_set_migrate_msg_parameters() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        set_contract_version(&mut deps.storage, "astroport-dca", "1.0.0").unwrap();
+        CONFIG_V1_0
+            .save(
+                &mut deps.storage,
+                &ConfigV1_0 {
+                    max_hops: 3,
+                    max_spread: Decimal::percent(5),
+                    whitelisted_fee_assets: vec![],
+                    whitelisted_tokens: vec![],
+                    factory_addr: Addr::unchecked("factory"),
+                    router_addr: Addr::unchecked("router"),
+                },
+            )
+            .unwrap();
+
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                owner: Some("new_owner".to_string()),
+                guardian: Some("new_guardian".to_string()),
+                config_timelock: Some(3600),
+                max_orders_per_user: Some(10),
+            },
+        )
+        .unwrap();
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        assert_eq!(config.owner, Addr::unchecked("new_owner"));
+        assert_eq!(config.guardian, Some(Addr::unchecked("new_guardian")));
+        assert_eq!(config.config_timelock, 3600);
+        assert_eq!(config.max_orders_per_user, 10);
+    }
+
+    #[test]
+    fn cannot_migrate_other_contract() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        set_contract_version(&mut deps.storage, "crates.io:other-contract", "1.0.0").unwrap();
+
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err(),
+            ContractError::InvalidContractName {
+                name: "crates.io:other-contract".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn cannot_downgrade() {
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        set_contract_version(&mut deps.storage, "astroport-dca", "99.0.0").unwrap();
+
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg::default()).unwrap_err(),
+            ContractError::MigrationDowngrade {
+                from: "99.0.0".to_string(),
+                to: env!("CARGO_PKG_VERSION").to_string()
+            }
+        );
+
+        // the state is left untouched
+        assert_eq!(
+            get_contract_version(&deps.storage).unwrap().version,
+            "99.0.0"
+        );
+    }
+}
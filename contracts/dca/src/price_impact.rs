@@ -0,0 +1,380 @@
+use astroport::{
+    asset::{Asset, AssetInfo, PairInfo},
+    factory::PairType,
+    pair::{PoolResponse, QueryMsg as PairQueryMsg, SimulationResponse},
+    querier::query_pair_info,
+    router::SwapOperation,
+};
+use astroport_dca::dca::SwapVenue;
+use cosmwasm_std::{Addr, Decimal, QuerierWrapper, Uint128};
+
+use crate::{error::ContractError, state::Config, venue::venue_factory};
+
+/// The fraction of the reserve of the offered asset that is simulated on a stable pair to find its
+/// spot price, which is small enough to have no meaningful price impact
+const STABLE_SPOT_PROBE: u128 = 10_000;
+
+/// ## Description
+/// Calculates the return of swapping `offer_asset` through the hop route at the spot price of the
+/// pairs the swap venue swaps through, without any price impact.
+///
+/// Router venues swap through the pairs of their factory, while pair venues swap through
+/// themselves. The spot price of xyk pairs is the ratio of their reserves, while the spot price of
+/// stable pairs is found by simulating a small fraction of their reserve. Returns
+/// [`ContractError::UnknownPriceImpact`] if a hop is not an Astroport swap through an xyk or stable
+/// pair of the venue.
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the venue, the factory and the pairs.
+///
+/// * `config` - The contract [`Config`], containing the Astroport factory and router.
+///
+/// * `venue` - The [`SwapVenue`] performing the hop route.
+///
+/// * `offer_asset` - The [`Asset`] being swapped.
+///
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+pub fn spot_return(
+    querier: &QuerierWrapper,
+    config: &Config,
+    venue: &SwapVenue,
+    offer_asset: &Asset,
+    hops: &[SwapOperation],
+) -> Result<Uint128, ContractError> {
+    let factory_addr =
+        venue_factory(querier, config, venue).map_err(|_| ContractError::UnknownPriceImpact {})?;
+
+    let mut amount = offer_asset.amount;
+    for hop in hops {
+        let (offer_asset_info, ask_asset_info) = match hop {
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } => (offer_asset_info, ask_asset_info),
+            SwapOperation::NativeSwap { .. } => return Err(ContractError::UnknownPriceImpact {}),
+        };
+
+        let pair = hop_pair(
+            querier,
+            factory_addr.as_ref(),
+            venue,
+            offer_asset_info,
+            ask_asset_info,
+        )?;
+
+        let pool: PoolResponse = querier
+            .query_wasm_smart(&pair.contract_addr, &PairQueryMsg::Pool {})
+            .map_err(|_| ContractError::UnknownPriceImpact {})?;
+        let reserve = |info| {
+            pool.assets
+                .iter()
+                .find(|asset| &asset.info == info)
+                .map(|asset| asset.amount)
+                .filter(|amount| !amount.is_zero())
+                .ok_or(ContractError::UnknownPriceImpact {})
+        };
+
+        amount = match pair.pair_type {
+            PairType::Xyk {} => {
+                amount.multiply_ratio(reserve(ask_asset_info)?, reserve(offer_asset_info)?)
+            }
+            PairType::Stable {} => {
+                // the price of stable pairs is not the ratio of their reserves, so a swap too small
+                // to move it is simulated instead, where only its commission is not price impact
+                let probe = reserve(offer_asset_info)? / Uint128::new(STABLE_SPOT_PROBE);
+                let simulation: SimulationResponse = querier
+                    .query_wasm_smart(
+                        &pair.contract_addr,
+                        &PairQueryMsg::Simulation {
+                            offer_asset: Asset {
+                                info: offer_asset_info.clone(),
+                                amount: probe,
+                            },
+                        },
+                    )
+                    .map_err(|_| ContractError::UnknownPriceImpact {})?;
+                let probe_return = simulation
+                    .return_amount
+                    .checked_add(simulation.commission_amount)?;
+
+                if probe.is_zero() || probe_return.is_zero() {
+                    return Err(ContractError::UnknownPriceImpact {});
+                }
+
+                amount.multiply_ratio(probe_return, probe)
+            }
+            _ => return Err(ContractError::UnknownPriceImpact {}),
+        };
+    }
+
+    Ok(amount)
+}
+
+/// ## Description
+/// Returns the pair the swap venue swaps a hop through, which is the pair of its factory for
+/// router venues, or the venue itself for pair venues. Returns
+/// [`ContractError::UnknownPriceImpact`] if the venue has no pair between the assets.
+fn hop_pair(
+    querier: &QuerierWrapper,
+    factory_addr: Option<&Addr>,
+    venue: &SwapVenue,
+    offer_asset_info: &AssetInfo,
+    ask_asset_info: &AssetInfo,
+) -> Result<PairInfo, ContractError> {
+    let pair: PairInfo = match factory_addr {
+        Some(factory_addr) => query_pair_info(
+            querier,
+            factory_addr.clone(),
+            &[offer_asset_info.clone(), ask_asset_info.clone()],
+        ),
+        None => querier.query_wasm_smart(&venue.contract_addr, &PairQueryMsg::Pair {}),
+    }
+    .map_err(|_| ContractError::UnknownPriceImpact {})?;
+
+    if !pair.asset_infos.contains(offer_asset_info) || !pair.asset_infos.contains(ask_asset_info) {
+        return Err(ContractError::UnknownPriceImpact {});
+    }
+
+    Ok(pair)
+}
+
+/// ## Description
+/// Checks that the price impact of swapping `offer_asset` through the hop route for
+/// `return_amount` does not exceed `max_price_impact`, where the price impact is the fraction the
+/// return is below the [`spot_return`].
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the venue, the factory and the pairs.
+///
+/// * `config` - The contract [`Config`], containing the Astroport factory and router.
+///
+/// * `venue` - The [`SwapVenue`] performing the hop route.
+///
+/// * `offer_asset` - The [`Asset`] being swapped.
+///
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+///
+/// * `return_amount` - The simulated return of the swap as a [`Uint128`].
+///
+/// * `max_price_impact` - The maximum price impact as a [`Decimal`].
+pub fn assert_price_impact(
+    querier: &QuerierWrapper,
+    config: &Config,
+    venue: &SwapVenue,
+    offer_asset: &Asset,
+    hops: &[SwapOperation],
+    return_amount: Uint128,
+    max_price_impact: Decimal,
+) -> Result<(), ContractError> {
+    let spot = spot_return(querier, config, venue, offer_asset, hops)?;
+    if return_amount >= spot {
+        return Ok(());
+    }
+
+    let price_impact = Decimal::from_ratio(spot - return_amount, spot);
+    if price_impact > max_price_impact {
+        return Err(ContractError::PriceImpactTooHigh {
+            price_impact,
+            max_price_impact,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::{
+        asset::{Asset, AssetInfo, PairInfo},
+        factory::{PairType, QueryMsg as FactoryQueryMsg},
+        pair::{PoolResponse, QueryMsg as PairQueryMsg, SimulationResponse},
+        router::SwapOperation,
+    };
+    use astroport_dca::dca::{SwapVenue, SwapVenueType};
+    use cosmwasm_std::{
+        from_binary, to_binary, Addr, ContractResult, Decimal, QuerierWrapper, SystemResult,
+        Uint128, WasmQuery,
+    };
+
+    use crate::{error::ContractError, state::CONFIG, tests::mock_instantiate};
+
+    use super::{assert_price_impact, spot_return};
+
+    #[test]
+    fn does_assert_price_impact() {
+        let (mut deps, _) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+        let config = CONFIG.load(&deps.storage).unwrap();
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+
+        // uluna and ukrw are traded in an xyk pair at 1 uluna for 2 ukrw, while ukrw and uusd are
+        // traded in a stable pair at 2 ukrw for 1 uusd, away from the ratio of its reserves
+        let pool = |reserves: [(&AssetInfo, u128); 2]| PoolResponse {
+            assets: reserves.map(|(info, amount)| Asset {
+                info: info.clone(),
+                amount: Uint128::new(amount),
+            }),
+            total_share: Uint128::new(1_000_000),
+        };
+        let xyk_pool = pool([(&uluna, 1_000_000), (&ukrw, 2_000_000)]);
+        let stable_pool = pool([(&ukrw, 5_000_000), (&uusd, 5_000_000)]);
+        let xyk_pair = PairInfo {
+            asset_infos: [uluna.clone(), ukrw.clone()],
+            contract_addr: Addr::unchecked("pair"),
+            liquidity_token: Addr::unchecked("lp_token"),
+            pair_type: PairType::Xyk {},
+        };
+
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+                match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => {
+                        let (contract_addr, pair_type) = match asset_infos.iter().any(|info| {
+                            info == &AssetInfo::NativeToken {
+                                denom: "uusd".to_string(),
+                            }
+                        }) {
+                            true => ("stable_pair", PairType::Stable {}),
+                            false => ("pair", PairType::Xyk {}),
+                        };
+
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&PairInfo {
+                                asset_infos,
+                                contract_addr: Addr::unchecked(contract_addr),
+                                liquidity_token: Addr::unchecked("lp_token"),
+                                pair_type,
+                            })
+                            .unwrap(),
+                        ))
+                    }
+                    _ => unimplemented!(),
+                }
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "pair" => {
+                match from_binary(msg).unwrap() {
+                    PairQueryMsg::Pair {} => {
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&xyk_pair).unwrap()))
+                    }
+                    PairQueryMsg::Pool {} => {
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&xyk_pool).unwrap()))
+                    }
+                    _ => unimplemented!(),
+                }
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "stable_pair" => {
+                match from_binary(msg).unwrap() {
+                    PairQueryMsg::Pool {} => {
+                        SystemResult::Ok(ContractResult::Ok(to_binary(&stable_pool).unwrap()))
+                    }
+                    // the stable pair returns half of the offer amount, one unit of which is
+                    // taken as its commission
+                    PairQueryMsg::Simulation { offer_asset } => {
+                        let amount = offer_asset.amount / Uint128::new(2);
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&SimulationResponse {
+                                return_amount: amount - Uint128::new(1),
+                                spread_amount: Uint128::zero(),
+                                commission_amount: Uint128::new(1),
+                            })
+                            .unwrap(),
+                        ))
+                    }
+                    _ => unimplemented!(),
+                }
+            }
+            _ => unimplemented!(),
+        });
+
+        let querier = QuerierWrapper::new(&deps.querier);
+        let router = config.venue(None).unwrap();
+        let offer_asset = Asset {
+            info: uluna.clone(),
+            amount: Uint128::new(10_000),
+        };
+        let hops = vec![
+            SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: ukrw.clone(),
+            },
+            SwapOperation::AstroSwap {
+                offer_asset_info: ukrw.clone(),
+                ask_asset_info: uusd.clone(),
+            },
+        ];
+
+        assert_eq!(
+            spot_return(&querier, &config, &router, &offer_asset, &hops).unwrap(),
+            Uint128::new(10_000)
+        );
+
+        // 9_500 is 5% below the spot return of 10_000
+        assert_price_impact(
+            &querier,
+            &config,
+            &router,
+            &offer_asset,
+            &hops,
+            Uint128::new(9_500),
+            Decimal::percent(5),
+        )
+        .unwrap();
+
+        let res = assert_price_impact(
+            &querier,
+            &config,
+            &router,
+            &offer_asset,
+            &hops,
+            Uint128::new(9_500),
+            Decimal::percent(4),
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            ContractError::PriceImpactTooHigh {
+                price_impact: Decimal::percent(5),
+                max_price_impact: Decimal::percent(4),
+            }
+        );
+
+        // pair venues are priced from their own pool, and cannot price a pair they are not
+        let pair = SwapVenue {
+            name: "pair".to_string(),
+            venue_type: SwapVenueType::AstroportPair {},
+            contract_addr: Addr::unchecked("pair"),
+        };
+        assert_eq!(
+            spot_return(&querier, &config, &pair, &offer_asset, &hops[..1]).unwrap(),
+            Uint128::new(20_000)
+        );
+
+        let res = spot_return(&querier, &config, &pair, &offer_asset, &hops).unwrap_err();
+        assert_eq!(res, ContractError::UnknownPriceImpact {});
+
+        // native swaps cannot be priced from the pools
+        let res = spot_return(
+            &querier,
+            &config,
+            &router,
+            &offer_asset,
+            &[SwapOperation::NativeSwap {
+                offer_denom: "uluna".to_string(),
+                ask_denom: "ukrw".to_string(),
+            }],
+        )
+        .unwrap_err();
+        assert_eq!(res, ContractError::UnknownPriceImpact {});
+    }
+}
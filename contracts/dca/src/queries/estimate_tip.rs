@@ -0,0 +1,245 @@
+use astroport::asset::{addr_validate_to_lower, Asset};
+use cosmwasm_std::{Deps, StdError, StdResult};
+
+use crate::state::{CONFIG, USER_CONFIG, USER_DCA};
+
+/// ## Description
+/// Returns the full tip a bot would receive for performing a DCA purchase of a user with the
+/// specified amount of hops.
+///
+/// The result is returned in a [`Vec<Asset>`] object containing the tip for each whitelisted fee
+/// asset that the user has a large enough tip balance to pay.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `user` - The users lowercase address as a [`String`].
+///
+/// * `id` - A [`u64`] representing the ID of the DCA order for the user.
+///
+/// * `hops` - A [`u32`] representing the amount of hops the DCA purchase would be performed with.
+pub fn estimate_tip(deps: Deps, user: String, id: u64, hops: u32) -> StdResult<Vec<Asset>> {
+    let user_address = addr_validate_to_lower(deps.api, &user)?;
+
+    // check that the order exists
+    let orders = USER_DCA
+        .may_load(deps.storage, &user_address)?
+        .unwrap_or_default();
+    if !orders.iter().any(|order| order.id == id) {
+        return Err(StdError::generic_err(
+            "The user does not have an order with the specified id",
+        ));
+    }
+
+    let config = CONFIG.load(deps.storage)?;
+    let user_config = USER_CONFIG
+        .may_load(deps.storage, &user_address)?
+        .unwrap_or_default();
+
+    let mut tips = vec![];
+    for fee_asset in config.whitelisted_fee_assets {
+        let tip = fee_asset.tip(hops)?;
+
+        // only include tips that the user can pay
+        let can_pay = user_config
+            .tip_balance
+            .iter()
+            .any(|balance| balance.info == fee_asset.info && balance.amount >= tip);
+        if can_pay {
+            tips.push(Asset {
+                info: fee_asset.info,
+                amount: tip,
+            });
+        }
+    }
+
+    Ok(tips)
+}
+
+#[cfg(test)]
+mod test {
+    use std::str::FromStr;
+
+    use astroport::{
+        asset::{Asset, AssetInfo},
+        factory::PairType,
+    };
+    use astroport_dca::dca::{AllowedAssets, DcaInfo, FeeAsset, QueryMsg};
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env},
+        Addr, Decimal, StdError, Uint128,
+    };
+
+    use crate::{
+        contract::query,
+        state::{Config, UserConfig, CONFIG, USER_CONFIG, USER_DCA},
+    };
+
+    #[test]
+    fn does_estimate_tip() {
+        let mut deps = mock_dependencies();
+
+        let user = Addr::unchecked("user_addr");
+
+        CONFIG
+            .save(
+                &mut deps.storage,
+                &Config {
+                    owner: Addr::unchecked("owner"),
+                    factory_addr: Addr::unchecked("factory"),
+                    router_addr: Addr::unchecked("router"),
+                    max_hops: 4,
+                    max_spread: Decimal::from_str("0.05").unwrap(),
+                    whitelisted_fee_assets: vec![
+                        FeeAsset {
+                            info: AssetInfo::NativeToken {
+                                denom: "uluna".to_string(),
+                            },
+                            base_fee: Uint128::new(10_000),
+                            per_hop_fee: Uint128::new(5_000),
+                            batch_surcharge: None,
+                        },
+                        FeeAsset {
+                            info: AssetInfo::NativeToken {
+                                denom: "ujpy".to_string(),
+                            },
+                            base_fee: Uint128::new(50_000),
+                            per_hop_fee: Uint128::new(25_000),
+                            batch_surcharge: None,
+                        },
+                    ],
+                    whitelisted_tokens: vec![],
+                    protocol_fee: Decimal::zero(),
+                    fee_collector: None,
+                    max_referral_fee_bps: 0,
+                    keeper_config: None,
+                    allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+                    pair_type_max_spreads: vec![],
+                    venues: vec![],
+                    guardian: None,
+                    config_timelock: 86_400,
+                    allowed_initial_assets: AllowedAssets::All {},
+                    allowed_target_assets: AllowedAssets::All {},
+                    min_dca_amounts: vec![],
+                    max_orders_per_user: 50,
+                    order_deposit: None,
+                },
+            )
+            .unwrap();
+
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &user,
+                &UserConfig {
+                    tip_balance: vec![
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: "uluna".to_string(),
+                            },
+                            amount: Uint128::new(100_000),
+                        },
+                        Asset {
+                            info: AssetInfo::NativeToken {
+                                denom: "ujpy".to_string(),
+                            },
+                            amount: Uint128::new(100_000),
+                        },
+                    ],
+                    ..UserConfig::default()
+                },
+            )
+            .unwrap();
+
+        USER_DCA
+            .save(
+                &mut deps.storage,
+                &user,
+                &vec![DcaInfo {
+                    id: 1,
+                    initial_asset: Asset {
+                        info: AssetInfo::NativeToken {
+                            denom: "uluna".to_string(),
+                        },
+                        amount: Uint128::new(100_000),
+                    },
+                    target_asset: AssetInfo::NativeToken {
+                        denom: "ukrw".to_string(),
+                    },
+                    interval: 500,
+                    last_purchase: 0,
+                    dca_amount: Uint128::new(10_000),
+                    referral: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: 0,
+                    require_canonical_route: false,
+                    max_price_impact: None,
+                    deposit: None,
+                }],
+            )
+            .unwrap();
+
+        let estimate = |hops: u32| -> Vec<Asset> {
+            from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::EstimateTip {
+                        user: user.to_string(),
+                        id: 1,
+                        hops,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        // both tips can be paid for a single hop
+        assert_eq!(
+            estimate(1),
+            vec![
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "uluna".to_string(),
+                    },
+                    amount: Uint128::new(15_000),
+                },
+                Asset {
+                    info: AssetInfo::NativeToken {
+                        denom: "ujpy".to_string(),
+                    },
+                    amount: Uint128::new(75_000),
+                }
+            ]
+        );
+
+        // the ujpy tip of 50_000 + 25_000 * 3 can no longer be paid by the user
+        assert_eq!(
+            estimate(3),
+            vec![Asset {
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+                amount: Uint128::new(25_000),
+            }]
+        );
+
+        // errors for a nonexistent order
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::EstimateTip {
+                user: user.into_string(),
+                id: 2,
+                hops: 1,
+            },
+        )
+        .unwrap_err();
+        assert_eq!(
+            res,
+            StdError::generic_err("The user does not have an order with the specified id")
+        );
+    }
+}
@@ -3,7 +3,7 @@ use cosmwasm_std::{Deps, StdResult};
 use crate::state::{Config, CONFIG};
 
 /// ## Description
-/// Returns the contract configuration set by the factory address owner or contract instantiator.
+/// Returns the contract configuration set by the contract owner or contract instantiator.
 ///
 /// The result is returned in a [`Config`] object.
 ///
@@ -17,8 +17,8 @@ pub fn get_config(deps: Deps) -> StdResult<Config> {
 mod test {
     use std::str::FromStr;
 
-    use astroport::asset::{Asset, AssetInfo};
-    use astroport_dca::dca::QueryMsg;
+    use astroport::{asset::AssetInfo, factory::PairType};
+    use astroport_dca::dca::{AllowedAssets, FeeAsset, QueryMsg};
     use cosmwasm_std::{
         from_binary,
         testing::{mock_dependencies, mock_env},
@@ -35,19 +35,36 @@ mod test {
         let mut deps = mock_dependencies();
 
         let saved_config = Config {
+            owner: Addr::unchecked("owner"),
             factory_addr: Addr::unchecked("factory"),
             max_hops: 4,
             max_spread: Decimal::from_str("0.05").unwrap(),
             router_addr: Addr::unchecked("router"),
-            whitelisted_fee_assets: vec![Asset {
-                amount: Uint128::new(20_000),
+            whitelisted_fee_assets: vec![FeeAsset {
                 info: astroport::asset::AssetInfo::NativeToken {
                     denom: "ujpy".to_string(),
                 },
+                base_fee: Uint128::zero(),
+                per_hop_fee: Uint128::new(20_000),
+                batch_surcharge: None,
             }],
             whitelisted_tokens: vec![AssetInfo::NativeToken {
                 denom: "ukrw".to_string(),
             }],
+            protocol_fee: Decimal::zero(),
+            fee_collector: None,
+            max_referral_fee_bps: 0,
+            keeper_config: None,
+            allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+            pair_type_max_spreads: vec![],
+            venues: vec![],
+            guardian: None,
+            config_timelock: 86_400,
+            allowed_initial_assets: AllowedAssets::All {},
+            allowed_target_assets: AllowedAssets::All {},
+            min_dca_amounts: vec![],
+            max_orders_per_user: 50,
+            order_deposit: None,
         };
 
         CONFIG.save(&mut deps.storage, &saved_config).unwrap();
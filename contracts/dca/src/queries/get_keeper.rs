@@ -0,0 +1,20 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::{Keeper, KEEPERS};
+
+/// ## Description
+/// Returns the registration of a keeper.
+///
+/// The result is returned in a [`Option<Keeper>`] object, which is [`None`] if the address is not
+/// a registered keeper.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `keeper` - The keepers lowercase address as a [`String`].
+pub fn get_keeper(deps: Deps, keeper: String) -> StdResult<Option<Keeper>> {
+    let keeper_address = addr_validate_to_lower(deps.api, &keeper)?;
+
+    KEEPERS.may_load(deps.storage, &keeper_address)
+}
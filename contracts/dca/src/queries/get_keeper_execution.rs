@@ -0,0 +1,17 @@
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::{KeeperExecution, KEEPER_EXECUTIONS};
+
+/// ## Description
+/// Returns the record of a DCA purchase performed by a registered keeper, which can be challenged
+/// during the challenge period.
+///
+/// The result is returned in a [`KeeperExecution`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `id` - A [`u64`] representing the ID of the keeper execution.
+pub fn get_keeper_execution(deps: Deps, id: u64) -> StdResult<KeeperExecution> {
+    KEEPER_EXECUTIONS.load(deps.storage, id)
+}
@@ -0,0 +1,22 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::{KeeperStats, KEEPER_STATS};
+
+/// ## Description
+/// Returns the amount of DCA purchases performed and the tips earned by an address.
+///
+/// The result is returned in a [`KeeperStats`] object, which is empty if the address has not
+/// performed any DCA purchases yet.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `keeper` - The keepers lowercase address as a [`String`].
+pub fn get_keeper_stats(deps: Deps, keeper: String) -> StdResult<KeeperStats> {
+    let keeper_address = addr_validate_to_lower(deps.api, &keeper)?;
+
+    Ok(KEEPER_STATS
+        .may_load(deps.storage, &keeper_address)?
+        .unwrap_or_default())
+}
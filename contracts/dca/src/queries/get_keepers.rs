@@ -0,0 +1,117 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{Addr, Deps, Order, StdResult};
+use cw_storage_plus::Bound;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::state::{KeeperStats, KEEPER_STATS};
+
+/// The default amount of keepers returned in a page
+const DEFAULT_LIMIT: u32 = 10;
+/// The maximum amount of keepers returned in a page
+const MAX_LIMIT: u32 = 30;
+
+/// Describes the statistics of an address that has performed DCA purchases
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperInfo {
+    /// The address that performed the DCA purchases
+    pub keeper: Addr,
+    /// The statistics of the address
+    pub stats: KeeperStats,
+}
+
+/// ## Description
+/// Returns the statistics of every address that has performed DCA purchases, ordered by address.
+///
+/// The result is returned in a [`Vec<KeeperInfo>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `start_after` - The address to start the page after as an [`Option<String>`].
+///
+/// * `limit` - The maximum amount of keepers to return as an [`Option<u32>`].
+pub fn get_keepers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<Vec<KeeperInfo>> {
+    let start_after = start_after
+        .map(|addr| addr_validate_to_lower(deps.api, &addr))
+        .transpose()?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+
+    KEEPER_STATS
+        .range(
+            deps.storage,
+            start_after.as_ref().map(Bound::exclusive),
+            None,
+            Order::Ascending,
+        )
+        .take(limit)
+        .map(|item| item.map(|(keeper, stats)| KeeperInfo { keeper, stats }))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use astroport_dca::dca::QueryMsg;
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env},
+        Addr,
+    };
+
+    use crate::{
+        contract::query,
+        state::{KeeperStats, KEEPER_STATS},
+    };
+
+    use super::KeeperInfo;
+
+    #[test]
+    fn does_paginate_keepers() {
+        let mut deps = mock_dependencies();
+
+        for (keeper, purchases) in [("keeper_a", 3), ("keeper_b", 1), ("keeper_c", 2)] {
+            KEEPER_STATS
+                .save(
+                    &mut deps.storage,
+                    &Addr::unchecked(keeper),
+                    &KeeperStats {
+                        purchases,
+                        tips: vec![],
+                        last_execution: 100,
+                    },
+                )
+                .unwrap();
+        }
+
+        let get_keepers = |start_after: Option<&str>, limit: Option<u32>| -> Vec<String> {
+            let keepers: Vec<KeeperInfo> = from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::Keepers {
+                        start_after: start_after.map(ToString::to_string),
+                        limit,
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap();
+
+            keepers
+                .into_iter()
+                .map(|k| k.keeper.into_string())
+                .collect()
+        };
+
+        assert_eq!(
+            get_keepers(None, None),
+            vec!["keeper_a", "keeper_b", "keeper_c"]
+        );
+        assert_eq!(get_keepers(None, Some(2)), vec!["keeper_a", "keeper_b"]);
+        assert_eq!(get_keepers(Some("keeper_b"), None), vec!["keeper_c"]);
+    }
+}
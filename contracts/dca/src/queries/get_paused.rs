@@ -0,0 +1,15 @@
+use astroport_dca::dca::PauseScope;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::PAUSED_SCOPES;
+
+/// ## Description
+/// Returns the scopes of the contract that are paused.
+///
+/// The result is returned in a [`Vec<PauseScope>`] object.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+pub fn get_paused(deps: Deps) -> StdResult<Vec<PauseScope>> {
+    Ok(PAUSED_SCOPES.may_load(deps.storage)?.unwrap_or_default())
+}
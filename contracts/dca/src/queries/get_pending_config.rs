@@ -0,0 +1,15 @@
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::{PendingConfig, PENDING_CONFIG};
+
+/// ## Description
+/// Returns the configuration update queued by the contract owner.
+///
+/// The result is returned in a [`Option<PendingConfig>`] object, which is [`None`] if no update
+/// is queued.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+pub fn get_pending_config(deps: Deps) -> StdResult<Option<PendingConfig>> {
+    PENDING_CONFIG.may_load(deps.storage)
+}
@@ -0,0 +1,23 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::{PurchaseRecord, PURCHASE_HISTORY};
+
+/// ## Description
+/// Returns the DCA purchases performed for the DCA order `id` of `user`.
+///
+/// The result is returned in a [`Vec<PurchaseRecord>`] object, ordered from the oldest purchase.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `user` - The address of the user as a [`String`].
+///
+/// * `id` - A [`u64`] representing the ID of the DCA order of the user.
+pub fn get_purchase_history(deps: Deps, user: String, id: u64) -> StdResult<Vec<PurchaseRecord>> {
+    let user = addr_validate_to_lower(deps.api, &user)?;
+
+    Ok(PURCHASE_HISTORY
+        .may_load(deps.storage, (&user, id))?
+        .unwrap_or_default())
+}
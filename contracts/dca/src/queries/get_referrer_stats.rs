@@ -0,0 +1,83 @@
+use astroport::asset::addr_validate_to_lower;
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::{ReferrerStats, REFERRER_STATS};
+
+/// ## Description
+/// Returns the volume originated and the fees earned by a referrer of DCA orders.
+///
+/// The result is returned in a [`ReferrerStats`] object, which is empty if the referrer has not
+/// earned any fees yet.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `referrer` - The referrers lowercase address as a [`String`].
+pub fn get_referrer_stats(deps: Deps, referrer: String) -> StdResult<ReferrerStats> {
+    let referrer_address = addr_validate_to_lower(deps.api, &referrer)?;
+
+    Ok(REFERRER_STATS
+        .may_load(deps.storage, &referrer_address)?
+        .unwrap_or_default())
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::asset::{Asset, AssetInfo};
+    use astroport_dca::dca::QueryMsg;
+    use cosmwasm_std::{
+        from_binary,
+        testing::{mock_dependencies, mock_env},
+        Addr, Uint128,
+    };
+
+    use crate::{
+        contract::query,
+        state::{ReferrerStats, REFERRER_STATS},
+    };
+
+    #[test]
+    fn does_get_referrer_stats() {
+        let mut deps = mock_dependencies();
+
+        let stats = ReferrerStats {
+            purchases: 2,
+            volume: vec![Asset {
+                amount: Uint128::new(20_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            earnings: vec![Asset {
+                amount: Uint128::new(40),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+        };
+
+        let key = Addr::unchecked("frontend");
+        REFERRER_STATS
+            .save(&mut deps.storage, &key, &stats)
+            .unwrap();
+
+        let get_stats = |referrer: &str| -> ReferrerStats {
+            from_binary(
+                &query(
+                    deps.as_ref(),
+                    mock_env(),
+                    QueryMsg::ReferrerStats {
+                        referrer: referrer.to_string(),
+                    },
+                )
+                .unwrap(),
+            )
+            .unwrap()
+        };
+
+        assert_eq!(get_stats("frontend"), stats);
+
+        // unknown referrers have empty stats
+        assert_eq!(get_stats("other_frontend"), ReferrerStats::default());
+    }
+}
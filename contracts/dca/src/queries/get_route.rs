@@ -0,0 +1,24 @@
+use astroport::{asset::AssetInfo, router::SwapOperation};
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::state::{route_key, ROUTES};
+
+/// ## Description
+/// Returns the canonical hop route from `from` to `to`.
+///
+/// The result is returned in a [`Option<Vec<SwapOperation>>`] object, which is [`None`] if no
+/// route is registered.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `from` - The [`AssetInfo`] of the asset being offered.
+///
+/// * `to` - The [`AssetInfo`] of the asset being purchased.
+pub fn get_route(
+    deps: Deps,
+    from: AssetInfo,
+    to: AssetInfo,
+) -> StdResult<Option<Vec<SwapOperation>>> {
+    ROUTES.may_load(deps.storage, route_key(&from, &to))
+}
@@ -47,6 +47,7 @@ mod test {
                     denom: "uluna".to_string(),
                 },
             }],
+            max_price_impact: None,
         };
 
         let key = Addr::unchecked("user_addr");
@@ -24,6 +24,7 @@ pub fn get_user_dca_orders(deps: Deps, env: Env, user: String) -> StdResult<Vec<
         .into_iter()
         .map(|order| {
             Ok(DcaQueryInfo {
+                exclusive_executor: order.exclusive_executor(env.block.time.seconds()).cloned(),
                 order: order.clone(),
                 token_allowance: match &order.initial_asset.info {
                     AssetInfo::NativeToken { .. } => order.initial_asset.amount,
@@ -99,6 +100,12 @@ mod test {
                     interval: 1_000,
                     dca_amount: Uint128::new(10_000),
                     first_purchase: None,
+                    referrer: None,
+                    referral_fee_bps: None,
+                    preferred_executor: None,
+                    exclusivity_seconds: None,
+                    require_canonical_route: None,
+                    max_price_impact: None,
                 },
                 &match asset.info {
                     AssetInfo::NativeToken { denom } => coins(20_000, denom),
@@ -180,9 +187,16 @@ mod test {
                         last_purchase: 0,
                         target_asset: AssetInfo::NativeToken {
                             denom: "ukrw".to_string()
-                        }
+                        },
+                        referral: None,
+                        preferred_executor: None,
+                        exclusivity_seconds: 0,
+                        require_canonical_route: false,
+                        max_price_impact: None,
+                        deposit: None,
                     },
-                    token_allowance: Uint128::new(20_000)
+                    token_allowance: Uint128::new(20_000),
+                    exclusive_executor: None,
                 },
                 DcaQueryInfo {
                     order: DcaInfo {
@@ -198,9 +212,16 @@ mod test {
                         },
                         interval: 1_000,
                         last_purchase: 0,
-                        dca_amount: Uint128::new(10_000)
+                        dca_amount: Uint128::new(10_000),
+                        referral: None,
+                        preferred_executor: None,
+                        exclusivity_seconds: 0,
+                        require_canonical_route: false,
+                        max_price_impact: None,
+                        deposit: None,
                     },
-                    token_allowance: Uint128::new(10_000)
+                    token_allowance: Uint128::new(10_000),
+                    exclusive_executor: None,
                 }
             ]
         );
@@ -1,7 +1,29 @@
+mod estimate_tip;
 mod get_config;
+mod get_keeper;
+mod get_keeper_execution;
+mod get_keeper_stats;
+mod get_keepers;
+mod get_paused;
+mod get_pending_config;
+mod get_purchase_history;
+mod get_referrer_stats;
+mod get_route;
 mod get_user_config;
 mod get_user_dca_orders;
+mod simulate_dca_purchase;
 
+pub use estimate_tip::estimate_tip;
 pub use get_config::get_config;
+pub use get_keeper::get_keeper;
+pub use get_keeper_execution::get_keeper_execution;
+pub use get_keeper_stats::get_keeper_stats;
+pub use get_keepers::get_keepers;
+pub use get_paused::get_paused;
+pub use get_pending_config::get_pending_config;
+pub use get_purchase_history::get_purchase_history;
+pub use get_referrer_stats::get_referrer_stats;
+pub use get_route::get_route;
 pub use get_user_config::get_user_config;
 pub use get_user_dca_orders::get_user_dca_orders;
+pub use simulate_dca_purchase::simulate_dca_purchase;
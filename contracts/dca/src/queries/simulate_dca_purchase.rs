@@ -0,0 +1,231 @@
+use astroport_dca::dca::SimulateDcaPurchaseResponse;
+use cosmwasm_std::{Deps, DepsMut, Env, StdError, StdResult};
+
+use crate::{
+    handlers::{prepare_purchase, PerformDcaPurchase, PreparedPurchase},
+    state::USER_DCA,
+    storage_overlay::StorageOverlay,
+    venue::simulate,
+};
+
+/// ## Description
+/// Dry runs a DCA purchase as if it were performed by `executor`, performing every validation of
+/// the purchase without writing to the storage of the contract.
+///
+/// The result is returned in a [`SimulateDcaPurchaseResponse`] object, or a [`StdError`] with the
+/// message of the [`ContractError`](crate::error::ContractError) the purchase would fail with.
+///
+/// ## Arguments
+/// * `deps` - A [`Deps`] that contains the dependencies.
+///
+/// * `env` - The [`Env`] of the blockchain.
+///
+/// * `executor` - The address as a [`String`] that would perform the DCA purchase.
+///
+/// * `purchase` - The [`PerformDcaPurchase`] being simulated.
+pub fn simulate_dca_purchase(
+    deps: Deps,
+    env: Env,
+    executor: String,
+    purchase: PerformDcaPurchase,
+) -> StdResult<SimulateDcaPurchaseResponse> {
+    let executor = deps.api.addr_validate(&executor)?;
+    let id = purchase.id;
+    let tips = purchase.fee_redeem.clone();
+
+    // the purchase writes to the overlay, which is discarded after the simulation
+    let mut storage = StorageOverlay::new(deps.storage);
+    let PreparedPurchase {
+        user,
+        offer_asset,
+        hops,
+        venue,
+        ..
+    } = prepare_purchase(
+        DepsMut {
+            storage: &mut storage,
+            api: deps.api,
+            querier: deps.querier,
+        },
+        &env,
+        &executor,
+        purchase,
+        false,
+    )
+    .map_err(|err| StdError::generic_err(err.to_string()))?;
+
+    let return_amount = simulate(&deps.querier, &venue, &offer_asset, &hops)?;
+
+    let order = USER_DCA
+        .may_load(&storage, &user)?
+        .unwrap_or_default()
+        .into_iter()
+        .find(|order| order.id == id);
+
+    Ok(SimulateDcaPurchaseResponse {
+        offer_asset,
+        hops,
+        return_amount,
+        tips,
+        order,
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::{
+        asset::{Asset, AssetInfo, PairInfo},
+        factory::{PairType, QueryMsg as FactoryQueryMsg},
+        router::{SimulateSwapOperationsResponse, SwapOperation},
+    };
+    use astroport_dca::dca::{DcaInfo, QueryMsg, SimulateDcaPurchaseResponse};
+    use cosmwasm_std::{
+        from_binary, to_binary, Addr, ContractResult, StdError, SystemResult, Uint128, WasmQuery,
+    };
+
+    use crate::{
+        contract::query,
+        state::{UserConfig, USER_CONFIG, USER_DCA},
+        tests::mock_instantiate,
+    };
+
+    #[test]
+    fn does_simulate_purchase() {
+        let (mut deps, env) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![Asset {
+                amount: Uint128::new(15_000),
+                info: AssetInfo::NativeToken {
+                    denom: "uluna".to_string(),
+                },
+            }],
+            vec![],
+        );
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } => match contract_addr.as_str() {
+                "factory" => match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    _ => unimplemented!(),
+                },
+                "router" => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&SimulateSwapOperationsResponse {
+                        amount: Uint128::new(19_000),
+                    })
+                    .unwrap(),
+                )),
+                _ => unimplemented!(),
+            },
+            _ => unimplemented!(),
+        });
+
+        let user = Addr::unchecked("user");
+        USER_CONFIG
+            .save(
+                &mut deps.storage,
+                &user,
+                &UserConfig {
+                    last_id: 1,
+                    max_hops: None,
+                    max_spread: None,
+                    tip_balance: vec![Asset {
+                        amount: Uint128::new(20_000),
+                        info: uluna.clone(),
+                    }],
+                    max_price_impact: None,
+                },
+            )
+            .unwrap();
+
+        let order = DcaInfo {
+            id: 1,
+            dca_amount: Uint128::new(10_000),
+            initial_asset: Asset {
+                info: uluna.clone(),
+                amount: Uint128::new(30_000),
+            },
+            target_asset: ukrw.clone(),
+            interval: 500,
+            last_purchase: 0,
+            referral: None,
+            preferred_executor: None,
+            exclusivity_seconds: 0,
+            require_canonical_route: false,
+            max_price_impact: None,
+            deposit: None,
+        };
+        USER_DCA
+            .save(&mut deps.storage, &user, &vec![order.clone()])
+            .unwrap();
+
+        let hops = vec![SwapOperation::AstroSwap {
+            offer_asset_info: uluna.clone(),
+            ask_asset_info: ukrw,
+        }];
+        let msg = |tip: u128| QueryMsg::SimulateDcaPurchase {
+            user: user.to_string(),
+            id: 1,
+            hops: Some(hops.clone()),
+            fee_redeem: vec![Asset {
+                amount: Uint128::new(tip),
+                info: uluna.clone(),
+            }],
+            venue: None,
+            executor: "bot_addr".to_string(),
+        };
+
+        let res: SimulateDcaPurchaseResponse =
+            from_binary(&query(deps.as_ref(), env.clone(), msg(15_000)).unwrap()).unwrap();
+        assert_eq!(
+            res,
+            SimulateDcaPurchaseResponse {
+                offer_asset: Asset {
+                    info: uluna.clone(),
+                    amount: Uint128::new(10_000),
+                },
+                hops: hops.clone(),
+                return_amount: Uint128::new(19_000),
+                tips: vec![Asset {
+                    amount: Uint128::new(15_000),
+                    info: uluna.clone(),
+                }],
+                order: Some(DcaInfo {
+                    initial_asset: Asset {
+                        info: uluna.clone(),
+                        amount: Uint128::new(20_000),
+                    },
+                    last_purchase: env.block.time.seconds(),
+                    ..order.clone()
+                }),
+            }
+        );
+
+        // the order is left unchanged
+        assert_eq!(USER_DCA.load(&deps.storage, &user).unwrap(), vec![order]);
+
+        // returns the error the purchase would fail with
+        let res = query(deps.as_ref(), env, msg(20_000)).unwrap_err();
+        assert_eq!(
+            res,
+            StdError::generic_err(
+                "Tip redemption of 20000uluna is larger than the full tip of 15000uluna"
+            )
+        );
+    }
+}
@@ -0,0 +1,601 @@
+use std::collections::VecDeque;
+
+use astroport::{
+    asset::{Asset, AssetInfo, PairInfo},
+    factory::PairType,
+    querier::query_pair_info,
+    router::SwapOperation,
+};
+use astroport_dca::dca::SwapVenue;
+use cosmwasm_std::{Addr, QuerierWrapper, StdResult, Uint128};
+
+use crate::{
+    error::ContractError,
+    state::Config,
+    venue::{simulate, venue_factory},
+};
+
+/// The maximum amount of candidate routes that are simulated when discovering a route
+const MAX_ROUTE_CANDIDATES: usize = 10;
+
+/// The maximum amount of paths that are explored when discovering a route
+const MAX_ROUTE_PATHS: usize = 100;
+
+/// The maximum amount of pairs that are queried from the factory when discovering a route
+const MAX_ROUTE_PAIR_QUERIES: usize = 50;
+
+/// ## Description
+/// Returns the [`AssetInfo`] of the asset offered in a swap operation.
+fn get_offer_asset_info(hop: &SwapOperation) -> AssetInfo {
+    match hop {
+        SwapOperation::NativeSwap { offer_denom, .. } => AssetInfo::NativeToken {
+            denom: offer_denom.clone(),
+        },
+        SwapOperation::AstroSwap {
+            offer_asset_info, ..
+        } => offer_asset_info.clone(),
+    }
+}
+
+/// ## Description
+/// Queries the pair between `from` and `to` from the factory at `factory_addr`.
+fn query_pair(
+    querier: &QuerierWrapper,
+    factory_addr: &Addr,
+    from: &AssetInfo,
+    to: &AssetInfo,
+) -> StdResult<PairInfo> {
+    query_pair_info(querier, factory_addr.clone(), &[from.clone(), to.clone()])
+}
+
+/// ## Description
+/// Checks that the first hop of the route offers `offer_asset_info` and that every other hop
+/// offers the asset returned by the previous hop.
+///
+/// Returns a [`ContractError::InvalidFirstHop`] or [`ContractError::DisconnectedHop`] if the route
+/// does not chain.
+/// ## Arguments
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+///
+/// * `offer_asset_info` - The [`AssetInfo`] of the asset being swapped.
+pub fn assert_route_chains(
+    hops: &[SwapOperation],
+    offer_asset_info: &AssetInfo,
+) -> Result<(), ContractError> {
+    let mut previous_ask = offer_asset_info.clone();
+    for (i, hop) in hops.iter().enumerate() {
+        if get_offer_asset_info(hop) != previous_ask {
+            return Err(match i {
+                0 => ContractError::InvalidFirstHop {
+                    initial_asset: offer_asset_info.clone(),
+                },
+                _ => ContractError::DisconnectedHop { hop: i },
+            });
+        }
+
+        previous_ask = hop.get_target_asset_info();
+    }
+
+    Ok(())
+}
+
+/// ## Description
+/// Checks that every Astroport swap in the route swaps through an existing pair of an allowed pair
+/// type of the factory at `factory_addr`.
+///
+/// Returns the [`PairType`] of the pair swapped through in each hop, or [`None`] for native swaps,
+/// as a [`Vec<Option<PairType>>`]. Returns a [`ContractError::NonexistentPair`] or
+/// [`ContractError::PairTypeNotAllowed`] if a swap does not go through an allowed pair.
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the factory.
+///
+/// * `config` - The contract [`Config`], containing the allowed pair types.
+///
+/// * `factory_addr` - The address of the factory of the pairs swapped through.
+///
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+pub fn assert_pairs_allowed(
+    querier: &QuerierWrapper,
+    config: &Config,
+    factory_addr: &Addr,
+    hops: &[SwapOperation],
+) -> Result<Vec<Option<PairType>>, ContractError> {
+    let mut pair_types = vec![];
+    for hop in hops {
+        match hop {
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } => {
+                let pair = query_pair(querier, factory_addr, offer_asset_info, ask_asset_info)
+                    .map_err(|_| ContractError::NonexistentPair {
+                        offer: offer_asset_info.clone(),
+                        ask: ask_asset_info.clone(),
+                    })?;
+
+                if !config.is_allowed_pair_type(&pair.pair_type) {
+                    return Err(ContractError::PairTypeNotAllowed {
+                        pair_type: pair.pair_type,
+                    });
+                }
+
+                pair_types.push(Some(pair.pair_type));
+            }
+            SwapOperation::NativeSwap { .. } => pair_types.push(None),
+        }
+    }
+
+    Ok(pair_types)
+}
+
+/// ## Description
+/// Finds the hop route from `offer_asset_info` to `target_asset` with the best simulated return
+/// for `offer_amount`.
+///
+/// Routes are discovered with a breadth-first search over the pairs of an allowed pair type of the
+/// factory of the venue between the whitelisted tokens, so that the shortest routes are considered
+/// first. The search explores at most [`MAX_ROUTE_PATHS`] paths and queries at most
+/// [`MAX_ROUTE_PAIR_QUERIES`] pairs, after which at most [`MAX_ROUTE_CANDIDATES`] routes of up to
+/// `max_hops` hops are simulated on the swap venue. Pair venues only swap directly through
+/// themselves.
+///
+/// Returns a [`ContractError::NoRouteFound`] if no route could be simulated.
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the venue and its factory.
+///
+/// * `config` - The contract [`Config`], containing the whitelisted tokens that can be used as
+/// middle hops.
+///
+/// * `venue` - The [`SwapVenue`] the route is performed through.
+///
+/// * `offer_asset_info` - The [`AssetInfo`] of the asset being swapped.
+///
+/// * `target_asset` - The [`AssetInfo`] of the asset being purchased.
+///
+/// * `offer_amount` - The [`Uint128`] amount being swapped.
+///
+/// * `max_hops` - The maximum amount of hops in a route.
+pub fn find_best_route(
+    querier: &QuerierWrapper,
+    config: &Config,
+    venue: &SwapVenue,
+    offer_asset_info: &AssetInfo,
+    target_asset: &AssetInfo,
+    offer_amount: Uint128,
+    max_hops: u32,
+) -> Result<Vec<SwapOperation>, ContractError> {
+    let factory_addr = match venue_factory(querier, config, venue)? {
+        Some(factory_addr) => factory_addr,
+        None if max_hops > 0 => {
+            return Ok(vec![SwapOperation::AstroSwap {
+                offer_asset_info: offer_asset_info.clone(),
+                ask_asset_info: target_asset.clone(),
+            }])
+        }
+        None => return Err(ContractError::NoRouteFound {}),
+    };
+
+    let mut pair_queries = 0;
+    let mut pair_exists: Vec<([AssetInfo; 2], bool)> = vec![];
+    let mut has_pair = |from: &AssetInfo, to: &AssetInfo| -> bool {
+        let known = pair_exists.iter().find(|(assets, _)| {
+            (&assets[0] == from && &assets[1] == to) || (&assets[0] == to && &assets[1] == from)
+        });
+
+        match known {
+            Some((_, exists)) => *exists,
+            // pairs that cannot be queried anymore are not considered
+            None if pair_queries >= MAX_ROUTE_PAIR_QUERIES => false,
+            None => {
+                pair_queries += 1;
+                let exists = matches!(
+                    query_pair(querier, &factory_addr, from, to),
+                    Ok(pair) if config.is_allowed_pair_type(&pair.pair_type)
+                );
+                pair_exists.push(([from.clone(), to.clone()], exists));
+                exists
+            }
+        }
+    };
+
+    // breadth-first search over the paths from the offer asset, where every asset in a path
+    // besides the offer and target asset is a whitelisted token
+    let mut candidates: Vec<Vec<AssetInfo>> = vec![];
+    let mut queue = VecDeque::from([vec![offer_asset_info.clone()]]);
+    let mut explored_paths = 0;
+    while let Some(path) = queue.pop_front() {
+        if candidates.len() >= MAX_ROUTE_CANDIDATES || explored_paths >= MAX_ROUTE_PATHS {
+            break;
+        }
+        explored_paths += 1;
+
+        if path.len() as u32 > max_hops {
+            continue;
+        }
+
+        let last = &path[path.len() - 1];
+
+        if has_pair(last, target_asset) {
+            let mut route = path.clone();
+            route.push(target_asset.clone());
+            candidates.push(route);
+        }
+
+        // only extend paths that still have room for a hop to the target asset
+        if (path.len() as u32) < max_hops {
+            for token in &config.whitelisted_tokens {
+                if token != target_asset && !path.contains(token) && has_pair(last, token) {
+                    let mut next = path.clone();
+                    next.push(token.clone());
+                    queue.push_back(next);
+                }
+            }
+        }
+    }
+
+    // simulate each candidate and pick the route with the best return
+    let offer_asset = Asset {
+        info: offer_asset_info.clone(),
+        amount: offer_amount,
+    };
+    let mut best: Option<(Uint128, Vec<SwapOperation>)> = None;
+    for candidate in candidates {
+        let operations: Vec<SwapOperation> = candidate
+            .windows(2)
+            .map(|pair| SwapOperation::AstroSwap {
+                offer_asset_info: pair[0].clone(),
+                ask_asset_info: pair[1].clone(),
+            })
+            .collect();
+
+        if let Ok(amount) = simulate(querier, venue, &offer_asset, &operations) {
+            let is_better = match &best {
+                Some((best_amount, _)) => amount > *best_amount,
+                None => true,
+            };
+
+            if is_better {
+                best = Some((amount, operations));
+            }
+        }
+    }
+
+    best.map(|(_, operations)| operations)
+        .ok_or(ContractError::NoRouteFound {})
+}
+
+#[cfg(test)]
+mod test {
+    use std::{cell::Cell, rc::Rc};
+
+    use astroport::{
+        asset::{AssetInfo, PairInfo},
+        factory::{PairType, QueryMsg as FactoryQueryMsg},
+        router::{
+            ConfigResponse as RouterConfigResponse, QueryMsg as RouterQueryMsg,
+            SimulateSwapOperationsResponse, SwapOperation,
+        },
+    };
+    use astroport_dca::dca::{SwapVenue, SwapVenueType};
+    use cosmwasm_std::{
+        from_binary, to_binary, Addr, ContractResult, SystemError, SystemResult, Uint128, WasmQuery,
+    };
+
+    use crate::{error::ContractError, state::CONFIG, tests::mock_instantiate};
+
+    use super::{find_best_route, MAX_ROUTE_PAIR_QUERIES};
+
+    #[test]
+    fn does_find_best_route() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let uusd = AssetInfo::NativeToken {
+            denom: "uusd".to_string(),
+        };
+
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![uusd.clone()],
+        );
+
+        // uluna can be swapped to ukrw directly or through uusd, which returns more
+        let pairs = [
+            [uluna.clone(), ukrw.clone()],
+            [uluna.clone(), uusd.clone()],
+            [uusd.clone(), ukrw.clone()],
+        ];
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+                let asset_infos = match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => asset_infos,
+                    _ => unimplemented!(),
+                };
+                match pairs
+                    .iter()
+                    .any(|pair| pair.contains(&asset_infos[0]) && pair.contains(&asset_infos[1]))
+                {
+                    true => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    false => SystemResult::Ok(ContractResult::Err("Pair not found".to_string())),
+                }
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "router" => {
+                let operations = match from_binary(msg).unwrap() {
+                    RouterQueryMsg::SimulateSwapOperations { operations, .. } => operations,
+                    _ => unimplemented!(),
+                };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&SimulateSwapOperationsResponse {
+                        amount: Uint128::from(operations.len() as u128 * 1_000),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let venue = config.venue(None).unwrap();
+        let querier = deps.as_ref().querier;
+
+        assert_eq!(
+            find_best_route(
+                &querier,
+                &config,
+                &venue,
+                &uluna,
+                &ukrw,
+                Uint128::new(1_000),
+                2
+            )
+            .unwrap(),
+            vec![
+                SwapOperation::AstroSwap {
+                    offer_asset_info: uluna.clone(),
+                    ask_asset_info: uusd.clone(),
+                },
+                SwapOperation::AstroSwap {
+                    offer_asset_info: uusd,
+                    ask_asset_info: ukrw.clone(),
+                },
+            ]
+        );
+
+        // the route through uusd is too long with a single hop
+        assert_eq!(
+            find_best_route(
+                &querier,
+                &config,
+                &venue,
+                &uluna,
+                &ukrw,
+                Uint128::new(1_000),
+                1
+            )
+            .unwrap(),
+            vec![SwapOperation::AstroSwap {
+                offer_asset_info: uluna.clone(),
+                ask_asset_info: ukrw,
+            }]
+        );
+
+        // there is no pair to the target asset
+        let uatom = AssetInfo::NativeToken {
+            denom: "uatom".to_string(),
+        };
+        assert_eq!(
+            find_best_route(
+                &querier,
+                &config,
+                &venue,
+                &uluna,
+                &uatom,
+                Uint128::new(1_000),
+                2
+            )
+            .unwrap_err(),
+            ContractError::NoRouteFound {}
+        );
+    }
+
+    #[test]
+    fn does_limit_route_discovery() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let tokens: Vec<AssetInfo> = (0..30)
+            .map(|i| AssetInfo::NativeToken {
+                denom: format!("utoken{}", i),
+            })
+            .collect();
+
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            tokens,
+        );
+
+        // every pair exists besides the pairs to the target asset, so no route can be found
+        let pair_queries = Rc::new(Cell::new(0));
+        let factory_queries = pair_queries.clone();
+        let target = ukrw.clone();
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+                let asset_infos = match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => asset_infos,
+                    _ => unimplemented!(),
+                };
+                factory_queries.set(factory_queries.get() + 1);
+                match asset_infos.contains(&target) {
+                    false => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    true => SystemResult::Ok(ContractResult::Err("Pair not found".to_string())),
+                }
+            }
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let venue = config.venue(None).unwrap();
+        let querier = deps.as_ref().querier;
+
+        assert_eq!(
+            find_best_route(
+                &querier,
+                &config,
+                &venue,
+                &uluna,
+                &ukrw,
+                Uint128::new(1_000),
+                4
+            )
+            .unwrap_err(),
+            ContractError::NoRouteFound {}
+        );
+        assert_eq!(pair_queries.get(), MAX_ROUTE_PAIR_QUERIES);
+    }
+
+    #[test]
+    fn does_find_route_through_venue() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![],
+        );
+
+        // only the factory of the other DEX has a uluna-ukrw pair
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "dex_router" => {
+                match from_binary(msg).unwrap() {
+                    RouterQueryMsg::SimulateSwapOperations { offer_amount, .. } => {
+                        SystemResult::Ok(ContractResult::Ok(
+                            to_binary(&SimulateSwapOperationsResponse {
+                                amount: offer_amount,
+                            })
+                            .unwrap(),
+                        ))
+                    }
+                    _ => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&RouterConfigResponse {
+                            astroport_factory: "dex_factory".to_string(),
+                        })
+                        .unwrap(),
+                    )),
+                }
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "dex_factory" => {
+                let asset_infos = match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => asset_infos,
+                    _ => unimplemented!(),
+                };
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&PairInfo {
+                        asset_infos,
+                        contract_addr: Addr::unchecked("dex_pair"),
+                        liquidity_token: Addr::unchecked("lp_token"),
+                        pair_type: PairType::Xyk {},
+                    })
+                    .unwrap(),
+                ))
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "factory" => {
+                SystemResult::Ok(ContractResult::Err("Pair not found".to_string()))
+            }
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+
+        let config = CONFIG.load(&deps.storage).unwrap();
+        let querier = deps.as_ref().querier;
+        let route = vec![SwapOperation::AstroSwap {
+            offer_asset_info: uluna.clone(),
+            ask_asset_info: ukrw.clone(),
+        }];
+
+        // the pairs of the other DEX are discovered through its own factory
+        let dex_venue = SwapVenue {
+            name: "dex".to_string(),
+            venue_type: SwapVenueType::Router {},
+            contract_addr: Addr::unchecked("dex_router"),
+        };
+        assert_eq!(
+            find_best_route(
+                &querier,
+                &config,
+                &dex_venue,
+                &uluna,
+                &ukrw,
+                Uint128::new(1_000),
+                2
+            )
+            .unwrap(),
+            route
+        );
+
+        // the Astroport factory has no pair
+        assert_eq!(
+            find_best_route(
+                &querier,
+                &config,
+                &config.venue(None).unwrap(),
+                &uluna,
+                &ukrw,
+                Uint128::new(1_000),
+                2
+            )
+            .unwrap_err(),
+            ContractError::NoRouteFound {}
+        );
+
+        // pair venues only swap directly through themselves
+        let pair_venue = SwapVenue {
+            name: "luna_krw".to_string(),
+            venue_type: SwapVenueType::AstroportPair {},
+            contract_addr: Addr::unchecked("pair"),
+        };
+        assert_eq!(
+            find_best_route(
+                &querier,
+                &config,
+                &pair_venue,
+                &uluna,
+                &ukrw,
+                Uint128::new(1_000),
+                2
+            )
+            .unwrap(),
+            route
+        );
+    }
+}
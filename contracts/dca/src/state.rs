@@ -1,42 +1,547 @@
-use astroport::asset::{Asset, AssetInfo};
-use cosmwasm_std::{Addr, Decimal};
+use astroport::{
+    asset::{addr_validate_to_lower, Asset, AssetInfo},
+    factory::PairType,
+    querier::query_factory_config,
+    router::{ConfigResponse as RouterConfigResponse, QueryMsg as RouterQueryMsg, SwapOperation},
+};
+use cosmwasm_std::{Addr, Api, Decimal, Deps, Order, QuerierWrapper, StdResult, Storage, Uint128};
 use cw_storage_plus::{Item, Map};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use astroport_dca::dca::DcaInfo;
+use astroport_dca::dca::{
+    AllowedAssets, DcaInfo, FeeAsset, KeeperConfig, PairTypeSpread, PauseScope, SwapVenue,
+    SwapVenueType,
+};
+
+use crate::error::ContractError;
+
+/// The maximum protocol fee that can be taken from each DCA purchase, which is 5%
+pub const MAX_PROTOCOL_FEE: Decimal = Decimal::raw(50_000_000_000_000_000);
+/// The maximum spread that can be set for DCA purchases, which is the maximum allowed by the
+/// Astroport pairs of 50%
+pub const MAX_SPREAD: Decimal = Decimal::raw(500_000_000_000_000_000);
+/// The maximum referral fee in basis points that can be allowed on DCA orders, which is 5%
+pub const MAX_REFERRAL_FEE_BPS: u16 = 500;
+/// The maximum time in seconds an ownership proposal can be claimed in, which is 14 days
+pub const MAX_PROPOSAL_TTL: u64 = 1_209_600;
+/// The default delay in seconds before a configuration update can be executed, which is 1 day
+pub const DEFAULT_CONFIG_TIMELOCK: u64 = 86_400;
+/// The default maximum amount of DCA orders a user can have at once
+pub const DEFAULT_MAX_ORDERS_PER_USER: u32 = 50;
+/// The minimum time in seconds a TWAP is calculated over, which is 5 minutes
+pub const MIN_TWAP_WINDOW: u64 = 300;
+/// The maximum time in seconds a TWAP is calculated over, which is 1 hour
+pub const MAX_TWAP_WINDOW: u64 = 3_600;
+/// The maximum amount of keeper executions past their challenge period that are removed each time
+/// a keeper execution is recorded
+pub const MAX_PRUNED_EXECUTIONS: usize = 10;
 
 /// Stores the main dca module parameters.
+///
+/// The fields added since the owner and the fee assets were introduced default to the value which
+/// keeps the previous behaviour, so that the configurations stored by earlier versions still load.
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct Config {
+    /// The owner of the contract, who can update its configuration
+    pub owner: Addr,
+    /// The guardian of the contract, who can only pause it in an emergency
+    #[serde(default)]
+    pub guardian: Option<Addr>,
     /// The maximum amount of hops to perform from `initial_asset` to `target_asset` when DCAing if
     /// the user does not specify a custom max hop amount
     pub max_hops: u32,
     /// The default for the maximum amount of spread in a swap
     pub max_spread: Decimal,
-    /// The whitelisted tokens that can be used for bot tips, along with the fee for each hop
-    pub whitelisted_fee_assets: Vec<Asset>,
+    /// The whitelisted tokens that can be used for bot tips, along with the tip paid in each
+    pub whitelisted_fee_assets: Vec<FeeAsset>,
     /// The whitelisted tokens that can be used in a DCA hop route
     pub whitelisted_tokens: Vec<AssetInfo>,
     /// The address of the Astroport factory contract
     pub factory_addr: Addr,
     /// The address of the Astroport router contract
     pub router_addr: Addr,
+    /// The fraction of each DCA purchase that is taken as a protocol fee before the swap is
+    /// performed, where a protocol fee of zero disables it
+    #[serde(default)]
+    pub protocol_fee: Decimal,
+    /// The address that receives the protocol fee, which is required if `protocol_fee` is not zero
+    #[serde(default)]
+    pub fee_collector: Option<Addr>,
+    /// The maximum referral fee in basis points that can be set on a DCA order
+    #[serde(default)]
+    pub max_referral_fee_bps: u16,
+    /// The permissioned keeper mode configuration, if keepers can register
+    #[serde(default)]
+    pub keeper_config: Option<KeeperConfig>,
+    /// The Astroport pair types that can be swapped through in a DCA hop route
+    #[serde(default = "default_allowed_pair_types")]
+    pub allowed_pair_types: Vec<PairType>,
+    /// The default maximum spread for each pair type, used instead of `max_spread` for hop routes
+    /// swapping through pairs of that type
+    #[serde(default)]
+    pub pair_type_max_spreads: Vec<PairTypeSpread>,
+    /// The whitelisted swap venues that DCA purchases can be performed through besides the
+    /// Astroport router at `router_addr`
+    #[serde(default)]
+    pub venues: Vec<SwapVenue>,
+    /// The delay in seconds between queueing a configuration update and it being executable, so
+    /// that users have time to cancel their orders
+    #[serde(default = "default_config_timelock")]
+    pub config_timelock: u64,
+    /// The assets that can be deposited as the initial asset of DCA orders
+    #[serde(default)]
+    pub allowed_initial_assets: AllowedAssets,
+    /// The assets that can be purchased as the target asset of DCA orders
+    #[serde(default)]
+    pub allowed_target_assets: AllowedAssets,
+    /// The minimum DCA amount of orders in each initial asset
+    #[serde(default)]
+    pub min_dca_amounts: Vec<Asset>,
+    /// The maximum amount of DCA orders a user can have at once
+    #[serde(default = "default_max_orders_per_user")]
+    pub max_orders_per_user: u32,
+    /// The refundable deposit paid for each DCA order in a whitelisted fee asset, if any
+    #[serde(default)]
+    pub order_deposit: Option<Asset>,
+}
+
+/// Returns the pair types that can be swapped through by default, which are the xyk and
+/// stableswap pairs
+pub fn default_allowed_pair_types() -> Vec<PairType> {
+    vec![PairType::Xyk {}, PairType::Stable {}]
+}
+
+fn default_config_timelock() -> u64 {
+    DEFAULT_CONFIG_TIMELOCK
+}
+
+fn default_max_orders_per_user() -> u32 {
+    DEFAULT_MAX_ORDERS_PER_USER
 }
 
 impl Config {
+    /// Checks that `sender` is the owner of the contract
+    pub fn assert_owner(&self, sender: &Addr) -> Result<(), ContractError> {
+        if sender != &self.owner {
+            return Err(ContractError::Unauthorized {});
+        }
+
+        Ok(())
+    }
+
+    /// Checks every invariant of the configuration, which is done whenever it is instantiated or
+    /// updated
+    pub fn validate(&self, api: &dyn Api) -> Result<(), ContractError> {
+        assert_valid_max_hops(self.max_hops)?;
+        assert_valid_max_spread(self.max_spread)?;
+        for spread in &self.pair_type_max_spreads {
+            assert_valid_max_spread(spread.max_spread)?;
+        }
+
+        self.assert_valid_whitelisted_tokens()?;
+        self.assert_valid_fee_assets()?;
+        self.assert_valid_fees()?;
+        self.assert_valid_keeper_config()?;
+        self.assert_valid_order_deposit()?;
+        self.assert_valid_venues(api)?;
+        self.assert_valid_guardian()?;
+
+        Ok(())
+    }
+
+    /// Checks that no token is whitelisted twice
+    pub fn assert_valid_whitelisted_tokens(&self) -> Result<(), ContractError> {
+        for (i, token) in self.whitelisted_tokens.iter().enumerate() {
+            if self.whitelisted_tokens[..i].contains(token) {
+                return Err(ContractError::DuplicateWhitelistedToken {
+                    token: token.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that no fee asset is whitelisted twice, and that every fee asset pays a tip
+    pub fn assert_valid_fee_assets(&self) -> Result<(), ContractError> {
+        for (i, fee_asset) in self.whitelisted_fee_assets.iter().enumerate() {
+            if self.whitelisted_fee_assets[..i]
+                .iter()
+                .any(|a| a.info == fee_asset.info)
+            {
+                return Err(ContractError::DuplicateFeeAsset {
+                    asset: fee_asset.info.clone(),
+                });
+            }
+
+            if fee_asset.base_fee.is_zero() && fee_asset.per_hop_fee.is_zero() {
+                return Err(ContractError::ZeroFeeAsset {
+                    asset: fee_asset.info.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
     /// Checks if a given `asset` is a whitelisted asset that can be used in a hop route
     pub fn is_whitelisted_asset(&self, asset: &AssetInfo) -> bool {
         self.whitelisted_tokens.contains(asset)
     }
 
+    /// Checks if pairs of a given `pair_type` can be swapped through in a hop route
+    pub fn is_allowed_pair_type(&self, pair_type: &PairType) -> bool {
+        self.allowed_pair_types.contains(pair_type)
+    }
+
+    /// Returns the default maximum spread for a hop route swapping through pairs of `pair_types`,
+    /// where native swaps have no pair type. This is the largest default of the hops, so that each
+    /// hop in the route can be performed
+    pub fn default_max_spread(&self, pair_types: &[Option<PairType>]) -> Decimal {
+        pair_types
+            .iter()
+            .map(|pair_type| {
+                self.pair_type_max_spreads
+                    .iter()
+                    .find(|spread| Some(&spread.pair_type) == pair_type.as_ref())
+                    .map_or(self.max_spread, |spread| spread.max_spread)
+            })
+            .max()
+            .unwrap_or(self.max_spread)
+    }
+
     /// Checks if a given `asset` is a whitelisted asset for paying bot tips
     pub fn is_whitelisted_fee_asset(&self, asset: &AssetInfo) -> bool {
         self.whitelisted_fee_assets.iter().any(|a| &a.info == asset)
     }
+
+    /// Returns the tip configuration for a given `asset` if it is a whitelisted fee asset
+    pub fn fee_asset(&self, asset: &AssetInfo) -> Option<&FeeAsset> {
+        self.whitelisted_fee_assets
+            .iter()
+            .find(|a| &a.info == asset)
+    }
+
+    /// Returns the protocol fee that is taken from a DCA purchase of `amount`
+    pub fn protocol_fee_amount(&self, amount: Uint128) -> Uint128 {
+        amount * self.protocol_fee
+    }
+
+    /// Checks that the protocol fee does not exceed [`MAX_PROTOCOL_FEE`], that a fee collector is
+    /// set if a protocol fee is being taken, and that the maximum referral fee does not exceed
+    /// [`MAX_REFERRAL_FEE_BPS`]
+    pub fn assert_valid_fees(&self) -> Result<(), ContractError> {
+        if self.protocol_fee > MAX_PROTOCOL_FEE {
+            return Err(ContractError::ProtocolFeeTooLarge {
+                max: MAX_PROTOCOL_FEE,
+            });
+        }
+
+        if !self.protocol_fee.is_zero() && self.fee_collector.is_none() {
+            return Err(ContractError::MissingFeeCollector {});
+        }
+
+        if self.max_referral_fee_bps > MAX_REFERRAL_FEE_BPS {
+            return Err(ContractError::ReferralFeeTooLarge {
+                max: MAX_REFERRAL_FEE_BPS,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Returns the whitelisted swap venue named `name`, or the Astroport router at `router_addr`
+    /// if no venue is specified
+    pub fn venue(&self, name: Option<&str>) -> Result<SwapVenue, ContractError> {
+        match name {
+            Some(name) => self
+                .venues
+                .iter()
+                .find(|venue| venue.name == name)
+                .cloned()
+                .ok_or_else(|| ContractError::NonexistentVenue {
+                    name: name.to_string(),
+                }),
+            None => Ok(SwapVenue {
+                name: "astroport".to_string(),
+                venue_type: SwapVenueType::AstroportRouter {},
+                contract_addr: self.router_addr.clone(),
+            }),
+        }
+    }
+
+    /// Checks that `factory_addr` is an Astroport factory and that `router_addr` is an Astroport
+    /// router of that factory, by querying their configurations
+    pub fn assert_valid_astroport_contracts(
+        &self,
+        querier: &QuerierWrapper,
+    ) -> Result<(), ContractError> {
+        query_factory_config(querier, self.factory_addr.clone()).map_err(|_| {
+            ContractError::InvalidFactory {
+                addr: self.factory_addr.to_string(),
+            }
+        })?;
+
+        let router_config: RouterConfigResponse = querier
+            .query_wasm_smart(&self.router_addr, &RouterQueryMsg::Config {})
+            .map_err(|_| ContractError::InvalidRouter {
+                addr: self.router_addr.to_string(),
+            })?;
+        if router_config.astroport_factory != self.factory_addr.as_str() {
+            return Err(ContractError::InvalidRouter {
+                addr: self.router_addr.to_string(),
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the address of every swap venue is valid and that no two venues share a name
+    pub fn assert_valid_venues(&self, api: &dyn Api) -> Result<(), ContractError> {
+        for (i, venue) in self.venues.iter().enumerate() {
+            api.addr_validate(venue.contract_addr.as_str())?;
+
+            if self.venues[..i].iter().any(|v| v.name == venue.name) {
+                return Err(ContractError::DuplicateVenue {
+                    name: venue.name.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the guardian is not the owner, as the guardian exists to pause the contract
+    /// separately from the owner
+    pub fn assert_valid_guardian(&self) -> Result<(), ContractError> {
+        if self.guardian.as_ref() == Some(&self.owner) {
+            return Err(ContractError::GuardianIsOwner {});
+        }
+
+        Ok(())
+    }
+
+    /// Checks that a DCA order can purchase `target_asset` with `initial_asset`, spending at least
+    /// the minimum DCA amount of `initial_asset` each purchase
+    pub fn assert_allowed_order(
+        &self,
+        initial_asset: &AssetInfo,
+        target_asset: &AssetInfo,
+        dca_amount: Uint128,
+    ) -> Result<(), ContractError> {
+        if !self.allowed_initial_assets.allows(initial_asset) {
+            return Err(ContractError::InitialAssetNotAllowed {
+                asset: initial_asset.clone(),
+            });
+        }
+
+        if !self.allowed_target_assets.allows(target_asset) {
+            return Err(ContractError::TargetAssetNotAllowed {
+                asset: target_asset.clone(),
+            });
+        }
+
+        if let Some(min) = self
+            .min_dca_amounts
+            .iter()
+            .find(|min| &min.info == initial_asset)
+        {
+            if dca_amount < min.amount {
+                return Err(ContractError::DcaAmountTooSmall { min: min.clone() });
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the keeper configuration if only registered keepers can perform DCA purchases
+    pub fn enabled_keeper_config(&self) -> Option<&KeeperConfig> {
+        self.keeper_config.as_ref().filter(|c| c.enabled)
+    }
+
+    /// Checks that the keeper bond is a whitelisted fee asset and that the slash threshold is less
+    /// than one
+    pub fn assert_valid_keeper_config(&self) -> Result<(), ContractError> {
+        if let Some(keeper_config) = &self.keeper_config {
+            if !self.is_whitelisted_fee_asset(&keeper_config.bond.info) {
+                return Err(ContractError::NonWhitelistedTipAsset {
+                    asset: keeper_config.bond.info.clone(),
+                });
+            }
+
+            if keeper_config.slash_threshold >= Decimal::one() {
+                return Err(ContractError::InvalidSlashThreshold {});
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Checks that the order deposit is paid in a whitelisted fee asset
+    pub fn assert_valid_order_deposit(&self) -> Result<(), ContractError> {
+        if let Some(order_deposit) = &self.order_deposit {
+            if !self.is_whitelisted_fee_asset(&order_deposit.info) {
+                return Err(ContractError::NonWhitelistedTipAsset {
+                    asset: order_deposit.info.clone(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Stores the parameters of a configuration update, where any excluded value is left unchanged
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct UpdateConfigParameters {
+    /// The new maximum amount of hops per swap if the user does not specify a value.
+    pub max_hops: Option<u32>,
+    /// The [`Vec<AssetInfo>`] tokens to add to the whitelisted tokens that can be used in a hop
+    /// route for DCA purchases, which are queued with `AddWhitelistedTokens`.
+    pub add_whitelisted_tokens: Option<Vec<AssetInfo>>,
+    /// The new [`Decimal`] maximum spread for each DCA purchase if the user does not specify a
+    /// value.
+    pub max_spread: Option<Decimal>,
+    /// The new [`Decimal`] fraction of each DCA purchase that is taken as a protocol fee.
+    pub protocol_fee: Option<Decimal>,
+    /// The new address of the protocol fee collector as a [`String`].
+    pub fee_collector: Option<String>,
+    /// The new maximum referral fee in basis points that can be set on a DCA order.
+    pub max_referral_fee_bps: Option<u16>,
+    /// The new [`KeeperConfig`] for the permissioned keeper mode.
+    pub keeper_config: Option<KeeperConfig>,
+    /// The new [`Vec<PairType>`] Astroport pair types that can be swapped through in a hop route.
+    pub allowed_pair_types: Option<Vec<PairType>>,
+    /// The new [`Vec<PairTypeSpread>`] default maximum spreads for each pair type.
+    pub pair_type_max_spreads: Option<Vec<PairTypeSpread>>,
+    /// The new [`Vec<SwapVenue>`] whitelisted swap venues.
+    pub venues: Option<Vec<SwapVenue>>,
+    /// The new address of the guardian as a [`String`].
+    pub guardian: Option<String>,
+    /// The new delay in seconds before configuration updates can be executed.
+    pub config_timelock: Option<u64>,
+    /// The new address of the Astroport factory contract as a [`String`].
+    pub factory_addr: Option<String>,
+    /// The new address of the Astroport router contract as a [`String`].
+    pub router_addr: Option<String>,
+    /// The new [`AllowedAssets`] that can be deposited as the initial asset of DCA orders.
+    pub allowed_initial_assets: Option<AllowedAssets>,
+    /// The new [`AllowedAssets`] that can be purchased as the target asset of DCA orders.
+    pub allowed_target_assets: Option<AllowedAssets>,
+    /// The new [`Vec<Asset>`] minimum DCA amounts of orders in each initial asset.
+    pub min_dca_amounts: Option<Vec<Asset>>,
+    /// The new maximum amount of DCA orders a user can have at once.
+    pub max_orders_per_user: Option<u32>,
+    /// The new [`Asset`] refundable deposit for each DCA order, where a zero amount disables it.
+    pub order_deposit: Option<Asset>,
+}
+
+impl UpdateConfigParameters {
+    /// Applies the update to `config`, checking that the resulting configuration is valid. A new
+    /// factory or router is validated by querying it
+    pub fn apply(&self, deps: Deps, config: &mut Config) -> Result<(), ContractError> {
+        let api = deps.api;
+
+        if let Some(max_hops) = self.max_hops {
+            config.max_hops = max_hops;
+        }
+
+        for token in self.add_whitelisted_tokens.iter().flatten() {
+            if !config.is_whitelisted_asset(token) {
+                config.whitelisted_tokens.push(token.clone());
+            }
+        }
+
+        if let Some(max_spread) = self.max_spread {
+            config.max_spread = max_spread;
+        }
+
+        if let Some(protocol_fee) = self.protocol_fee {
+            config.protocol_fee = protocol_fee;
+        }
+
+        if let Some(fee_collector) = &self.fee_collector {
+            config.fee_collector = Some(addr_validate_to_lower(api, fee_collector)?);
+        }
+
+        if let Some(max_referral_fee_bps) = self.max_referral_fee_bps {
+            config.max_referral_fee_bps = max_referral_fee_bps;
+        }
+
+        if let Some(keeper_config) = &self.keeper_config {
+            config.keeper_config = Some(keeper_config.clone());
+        }
+
+        if let Some(allowed_pair_types) = &self.allowed_pair_types {
+            config.allowed_pair_types = allowed_pair_types.clone();
+        }
+
+        if let Some(pair_type_max_spreads) = &self.pair_type_max_spreads {
+            config.pair_type_max_spreads = pair_type_max_spreads.clone();
+        }
+
+        if let Some(venues) = &self.venues {
+            config.venues = venues.clone();
+        }
+
+        if let Some(guardian) = &self.guardian {
+            config.guardian = Some(addr_validate_to_lower(api, guardian)?);
+        }
+
+        if let Some(config_timelock) = self.config_timelock {
+            config.config_timelock = config_timelock;
+        }
+
+        if let Some(factory_addr) = &self.factory_addr {
+            config.factory_addr = addr_validate_to_lower(api, factory_addr)?;
+        }
+
+        if let Some(router_addr) = &self.router_addr {
+            config.router_addr = addr_validate_to_lower(api, router_addr)?;
+        }
+
+        if let Some(allowed_initial_assets) = &self.allowed_initial_assets {
+            config.allowed_initial_assets = allowed_initial_assets.clone();
+        }
+
+        if let Some(allowed_target_assets) = &self.allowed_target_assets {
+            config.allowed_target_assets = allowed_target_assets.clone();
+        }
+
+        if let Some(min_dca_amounts) = &self.min_dca_amounts {
+            config.min_dca_amounts = min_dca_amounts.clone();
+        }
+
+        if let Some(max_orders_per_user) = self.max_orders_per_user {
+            config.max_orders_per_user = max_orders_per_user;
+        }
+
+        if let Some(order_deposit) = &self.order_deposit {
+            config.order_deposit = Some(order_deposit.clone()).filter(|d| !d.amount.is_zero());
+        }
+
+        if self.factory_addr.is_some() || self.router_addr.is_some() {
+            config.assert_valid_astroport_contracts(&deps.querier)?;
+        }
+
+        config.validate(api)?;
+
+        Ok(())
+    }
+}
+
+/// Stores a configuration update queued by the contract owner
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingConfig {
+    /// The queued configuration update
+    pub update: UpdateConfigParameters,
+    /// The time from which the update can be executed
+    pub activation_time: u64,
 }
 
-/// Stores the users custom configuration
+/// Stores the users custom configuration, where the fields added since version 1.0 are optional so
+/// that the configurations of existing users still load
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
 pub struct UserConfig {
     /// A unique identifier for DCA orders, increments each time `create_order` is called.
@@ -49,11 +554,616 @@ pub struct UserConfig {
     pub max_spread: Option<Decimal>,
     /// The tip balance the user has deposited for their tips when performing DCA purchases
     pub tip_balance: Vec<Asset>,
+    /// The maximum price impact of a DCA purchase compared to the spot price of the pools it swaps
+    /// through, which is not checked if unset
+    #[serde(default)]
+    pub max_price_impact: Option<Decimal>,
+}
+
+impl UserConfig {
+    /// Checks that the overrides of the user are within the same bounds as the configuration of
+    /// the contract
+    pub fn validate(&self) -> Result<(), ContractError> {
+        if let Some(max_hops) = self.max_hops {
+            assert_valid_max_hops(max_hops)?;
+        }
+
+        if let Some(max_spread) = self.max_spread {
+            assert_valid_max_spread(max_spread)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Stores the volume originated and the fees earned by a referrer
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReferrerStats {
+    /// The amount of DCA purchases performed for orders made through the referrer
+    pub purchases: u64,
+    /// The total amount of each initial asset spent in DCA purchases made through the referrer
+    pub volume: Vec<Asset>,
+    /// The total referral fees earned in each initial asset
+    pub earnings: Vec<Asset>,
+}
+
+impl ReferrerStats {
+    /// Records a DCA purchase of `volume` which paid the referrer a fee of `fee`
+    pub fn record_purchase(&mut self, volume: &Asset, fee: &Asset) {
+        self.purchases += 1;
+        add_to_balance(&mut self.volume, volume);
+        add_to_balance(&mut self.earnings, fee);
+    }
+}
+
+/// Stores the statistics of an address performing DCA purchases
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq, JsonSchema)]
+pub struct KeeperStats {
+    /// The amount of DCA purchases performed successfully
+    pub purchases: u64,
+    /// The total tips earned in each fee asset
+    pub tips: Vec<Asset>,
+    /// The time the last DCA purchase was performed
+    pub last_execution: u64,
+}
+
+impl KeeperStats {
+    /// Records a DCA purchase performed at `time` which paid the `tips`
+    pub fn record_purchase(&mut self, tips: &[Asset], time: u64) {
+        self.purchases += 1;
+        for tip in tips {
+            add_to_balance(&mut self.tips, tip);
+        }
+        self.last_execution = time;
+    }
+}
+
+/// Stores the registration of a keeper
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Keeper {
+    /// The bond deposited by the keeper
+    pub bond: Asset,
+    /// The time the keeper started unbonding, if they have
+    pub unbonding_since: Option<u64>,
+}
+
+/// Stores the state of a hop route pair at the time of a DCA purchase
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct HopSnapshot {
+    /// The address of the pair swapped through
+    pub pair_addr: Addr,
+    /// The cumulative price of the offered asset in the pair
+    pub price_cumulative: Uint128,
+    /// The observation of the cumulative price before the DCA purchase, which the TWAP reference
+    /// of the purchase is calculated since
+    pub anchor: PriceObservation,
+}
+
+/// Stores a DCA purchase performed by a registered keeper, which can be challenged during the
+/// challenge period
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperExecution {
+    /// The keeper who performed the DCA purchase
+    pub keeper: Addr,
+    /// The user the DCA purchase was performed for
+    pub user: Addr,
+    /// The ID of the DCA order of the user
+    pub order_id: u64,
+    /// The asset swapped in the DCA purchase
+    pub offer_asset: Asset,
+    /// The hop route the DCA purchase was performed with
+    pub hops: Vec<SwapOperation>,
+    /// The amount of the target asset the user received for the DCA purchase, as measured by the
+    /// contract once its swap was performed, including its shares of batched swaps and matches
+    pub return_amount: Uint128,
+    /// The time the DCA purchase was performed
+    pub executed_at: u64,
+    /// The state of each pair in the reference route of the purchase at the time of the purchase,
+    /// which is the canonical route of its assets or the best Astroport route between them, or
+    /// [`None`] if there is no reference route or it contains pairs without an observation to
+    /// anchor the TWAP
+    pub snapshots: Option<Vec<HopSnapshot>>,
+}
+
+/// Stores a DCA purchase performed for a user, along with the share it received of a batched swap
+/// or of an opposite DCA purchase it was matched against
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PurchaseRecord {
+    /// The asset swapped for the DCA purchase
+    pub offer_asset: Asset,
+    /// The share of the output of the batched swap the user received, the output of the swap of a
+    /// single DCA purchase performed by a registered keeper, or [`None`] for other single DCA
+    /// purchases whose swap venue sends the purchased asset to the user directly
+    pub return_asset: Option<Asset>,
+    /// The time the DCA purchase was performed
+    pub executed_at: u64,
+    /// Whether the DCA purchase was matched against opposite DCA purchases at the TWAP instead of
+    /// being swapped
+    pub matched: bool,
+    /// The protocol fee taken from the DCA purchase, if any
+    #[serde(default)]
+    pub protocol_fee: Option<Asset>,
+}
+
+/// Stores an observation of the cumulative price of an asset in a pair, which the TWAP used to
+/// match opposite DCA purchases is calculated from
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceObservation {
+    /// The cumulative price of the asset in the pair
+    pub price_cumulative: Uint128,
+    /// The time the cumulative price was observed
+    pub observed_at: u64,
+}
+
+/// Stores the observations of the cumulative price of an asset in a pair that anchor TWAPs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PriceObservations {
+    /// The observation TWAPs are currently calculated since, which is at least
+    /// [`MIN_TWAP_WINDOW`] seconds older than the latest observation, if there is one
+    pub anchor: Option<PriceObservation>,
+    /// The latest observation, which becomes the anchor once it is [`MIN_TWAP_WINDOW`] seconds old
+    pub latest: PriceObservation,
+}
+
+/// Stores a DCA purchase performed by a registered keeper whose swap is being performed, so that
+/// the amount the swap returned can be measured
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingPurchase {
+    /// The user the DCA purchase is performed for
+    pub user: Addr,
+    /// The ID of the DCA order of the user
+    pub id: u64,
+    /// The asset being purchased
+    pub target_asset: AssetInfo,
+    /// The ID of the keeper execution recording the DCA purchase
+    pub execution_id: u64,
+    /// The balance of the contract in the target asset before the swap was performed
+    pub balance_before: Uint128,
+}
+
+/// Stores the share of a DCA purchase in a batched swap
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchShare {
+    /// The user the DCA purchase is performed for
+    pub user: Addr,
+    /// The ID of the DCA order of the user
+    pub id: u64,
+    /// The amount of the offered asset the DCA purchase added to the swap
+    pub amount: Uint128,
+    /// The protocol fee taken from the DCA purchase, which is recorded with the first share of the
+    /// purchase that is filled
+    pub protocol_fee: Option<Asset>,
+    /// The ID of the keeper execution recording the DCA purchase, if it was performed by a
+    /// registered keeper, which the amounts received by the purchase are added to
+    pub execution_id: Option<u64>,
+}
+
+/// Stores a single swap of the summed amount of DCA purchases with the same assets and hop route
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchSwap {
+    /// The summed asset swapped for the DCA purchases
+    pub offer_asset: Asset,
+    /// The asset being purchased
+    pub target_asset: AssetInfo,
+    /// The hop route to swap through
+    pub hops: Vec<SwapOperation>,
+    /// The maximum spread of the swap, which is the smallest maximum spread of the DCA purchases
+    pub max_spread: Decimal,
+    /// The maximum price impact of the swap, which is the smallest maximum price impact of the DCA
+    /// purchases setting one
+    pub max_price_impact: Option<Decimal>,
+    /// The share of each DCA purchase in the swap
+    pub shares: Vec<BatchShare>,
+}
+
+/// Stores the batched swaps of a batched execution that are being performed
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingBatch {
+    /// The swaps left to perform, starting with the swap currently being performed
+    pub swaps: Vec<BatchSwap>,
+    /// The balance of the contract in the target asset of the current swap before it was
+    /// performed
+    pub balance_before: Uint128,
+}
+
+/// Stores a proposal to transfer the ownership of the contract
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct OwnershipProposal {
+    /// The proposed new owner
+    pub owner: Addr,
+    /// The time until which the new owner can claim the ownership
+    pub ttl: u64,
+}
+
+/// Returns the key of the canonical route from `from` to `to` in [`ROUTES`]
+pub fn route_key(from: &AssetInfo, to: &AssetInfo) -> (String, String) {
+    (from.to_string(), to.to_string())
+}
+
+/// Checks that a canonical route from `from` to `to` is registered in [`ROUTES`], for orders that
+/// can only be purchased with it
+pub fn assert_canonical_route_exists(
+    storage: &dyn Storage,
+    from: &AssetInfo,
+    to: &AssetInfo,
+) -> Result<(), ContractError> {
+    if !ROUTES.has(storage, route_key(from, to)) {
+        return Err(ContractError::NonexistentRoute {});
+    }
+
+    Ok(())
+}
+
+/// Removes up to [`MAX_PRUNED_EXECUTIONS`] of the oldest keeper executions whose challenge period
+/// has passed at `now`, as they can no longer be challenged
+pub fn prune_keeper_executions(
+    storage: &mut dyn Storage,
+    now: u64,
+    challenge_period: u64,
+) -> StdResult<()> {
+    let executions = KEEPER_EXECUTIONS
+        .range(storage, None, None, Order::Ascending)
+        .take(MAX_PRUNED_EXECUTIONS)
+        .collect::<StdResult<Vec<_>>>()?;
+
+    // executions are recorded in the order they were performed in
+    for (id, execution) in executions {
+        if execution.executed_at.saturating_add(challenge_period) >= now {
+            break;
+        }
+
+        KEEPER_EXECUTIONS.remove(storage, id);
+    }
+
+    Ok(())
+}
+
+/// Adds `amount` to the amount the keeper execution `id` returned, as DCA purchases in batched
+/// executions receive their purchased asset in parts from matches and from their batched swap
+pub fn record_execution_return(
+    storage: &mut dyn Storage,
+    id: u64,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    KEEPER_EXECUTIONS.update::<_, ContractError>(storage, id, |execution| {
+        let mut execution = execution.ok_or(ContractError::NonexistentExecution {})?;
+        execution.return_amount = execution.return_amount.checked_add(amount)?;
+        Ok(execution)
+    })?;
+
+    Ok(())
+}
+
+/// Checks that `scope` of the contract is not paused
+pub fn assert_not_paused(storage: &dyn Storage, scope: PauseScope) -> Result<(), ContractError> {
+    if PAUSED_SCOPES
+        .may_load(storage)?
+        .unwrap_or_default()
+        .contains(&scope)
+    {
+        return Err(ContractError::Paused { scope });
+    }
+
+    Ok(())
+}
+
+/// Checks that `max_hops` allows DCA purchases of at least a single hop
+pub fn assert_valid_max_hops(max_hops: u32) -> Result<(), ContractError> {
+    if max_hops == 0 {
+        return Err(ContractError::InvalidMaxHops {});
+    }
+
+    Ok(())
+}
+
+/// Checks that `max_spread` does not exceed [`MAX_SPREAD`]
+pub fn assert_valid_max_spread(max_spread: Decimal) -> Result<(), ContractError> {
+    if max_spread > MAX_SPREAD {
+        return Err(ContractError::MaxSpreadTooLarge { max: MAX_SPREAD });
+    }
+
+    Ok(())
+}
+
+/// Adds `asset` to the matching asset in `balances`, or pushes it if it is not present
+fn add_to_balance(balances: &mut Vec<Asset>, asset: &Asset) {
+    match balances.iter_mut().find(|a| a.info == asset.info) {
+        Some(balance) => balance.amount += asset.amount,
+        None => balances.push(asset.clone()),
+    }
 }
 
 /// The contract configuration
 pub const CONFIG: Item<Config> = Item::new("config");
+/// The pending proposal to transfer the ownership of the contract
+pub const OWNERSHIP_PROPOSAL: Item<OwnershipProposal> = Item::new("ownership_proposal");
+/// The configuration update queued by the contract owner
+pub const PENDING_CONFIG: Item<PendingConfig> = Item::new("pending_config");
+/// The scopes of the contract that are paused
+pub const PAUSED_SCOPES: Item<Vec<PauseScope>> = Item::new("paused_scopes");
 /// The configuration set by each user
 pub const USER_CONFIG: Map<&Addr, UserConfig> = Map::new("user_config");
 /// The DCA orders for a user
 pub const USER_DCA: Map<&Addr, Vec<DcaInfo>> = Map::new("user_dca");
+/// The statistics for each referrer of DCA orders
+pub const REFERRER_STATS: Map<&Addr, ReferrerStats> = Map::new("referrer_stats");
+/// The statistics for each address performing DCA purchases
+pub const KEEPER_STATS: Map<&Addr, KeeperStats> = Map::new("keeper_stats");
+/// The registered keepers
+pub const KEEPERS: Map<&Addr, Keeper> = Map::new("keepers");
+/// The DCA purchases performed by registered keepers, keyed by their ID
+pub const KEEPER_EXECUTIONS: Map<u64, KeeperExecution> = Map::new("keeper_executions");
+/// The ID of the last DCA purchase performed by a registered keeper
+pub const LAST_EXECUTION_ID: Item<u64> = Item::new("last_execution_id");
+/// The canonical hop routes registered by the contract owner, keyed by the offered and target asset
+pub const ROUTES: Map<(String, String), Vec<SwapOperation>> = Map::new("routes");
+/// The batched swaps being performed in the current batched execution
+pub const PENDING_BATCH: Item<PendingBatch> = Item::new("pending_batch");
+/// The DCA purchase performed by a registered keeper whose swap is currently being performed
+pub const PENDING_PURCHASE: Item<PendingPurchase> = Item::new("pending_purchase");
+/// The observations of the cumulative price of each asset in a pair, keyed by the pair address and
+/// the asset
+pub const PRICE_OBSERVATIONS: Map<(&Addr, String), PriceObservations> =
+    Map::new("price_observations");
+/// The DCA purchases performed for each DCA order of a user
+pub const PURCHASE_HISTORY: Map<(&Addr, u64), Vec<PurchaseRecord>> = Map::new("purchase_history");
+
+#[cfg(test)]
+mod test {
+    use astroport::{asset::AssetInfo, factory::PairType};
+    use astroport_dca::dca::{
+        AllowedAssets, FeeAsset, KeeperConfig, PairTypeSpread, SwapVenue, SwapVenueType,
+    };
+    use cosmwasm_std::{
+        from_slice,
+        testing::{MockApi, MockStorage},
+        Addr, Decimal, Uint128,
+    };
+
+    use crate::error::ContractError;
+
+    use super::{
+        prune_keeper_executions, Asset, Config, KeeperExecution, DEFAULT_CONFIG_TIMELOCK,
+        DEFAULT_MAX_ORDERS_PER_USER, KEEPER_EXECUTIONS, MAX_PROTOCOL_FEE, MAX_PRUNED_EXECUTIONS,
+        MAX_SPREAD,
+    };
+
+    /// Breaks an invariant of a valid configuration, which then fails with the error
+    type Violation = (fn(&mut Config), ContractError);
+
+    fn native(denom: &str) -> AssetInfo {
+        AssetInfo::NativeToken {
+            denom: denom.to_string(),
+        }
+    }
+
+    fn fee_asset(denom: &str, base_fee: u128) -> FeeAsset {
+        FeeAsset {
+            info: native(denom),
+            base_fee: Uint128::new(base_fee),
+            per_hop_fee: Uint128::zero(),
+            batch_surcharge: None,
+        }
+    }
+
+    fn venue(name: &str) -> SwapVenue {
+        SwapVenue {
+            name: name.to_string(),
+            venue_type: SwapVenueType::AstroportPair {},
+            contract_addr: Addr::unchecked("venue"),
+        }
+    }
+
+    #[test]
+    fn does_validate_every_combination_of_invariants() {
+        let valid = Config {
+            owner: Addr::unchecked("owner"),
+            guardian: None,
+            max_hops: 3,
+            max_spread: Decimal::percent(5),
+            whitelisted_fee_assets: vec![fee_asset("uluna", 1_000)],
+            whitelisted_tokens: vec![native("uluna"), native("ukrw")],
+            factory_addr: Addr::unchecked("factory"),
+            router_addr: Addr::unchecked("router"),
+            protocol_fee: MAX_PROTOCOL_FEE,
+            fee_collector: Some(Addr::unchecked("fee_collector")),
+            max_referral_fee_bps: 0,
+            keeper_config: Some(KeeperConfig {
+                enabled: true,
+                bond: Asset {
+                    info: native("uluna"),
+                    amount: Uint128::new(1_000_000),
+                },
+                slash_threshold: Decimal::percent(10),
+                challenge_period: 86_400,
+            }),
+            allowed_pair_types: vec![PairType::Xyk {}],
+            pair_type_max_spreads: vec![PairTypeSpread {
+                pair_type: PairType::Stable {},
+                max_spread: MAX_SPREAD,
+            }],
+            venues: vec![venue("pair")],
+            config_timelock: 0,
+            allowed_initial_assets: AllowedAssets::All {},
+            allowed_target_assets: AllowedAssets::All {},
+            min_dca_amounts: vec![],
+            max_orders_per_user: 1,
+            order_deposit: Some(Asset {
+                info: native("uluna"),
+                amount: Uint128::new(1_000),
+            }),
+        };
+
+        // each entry breaks a single invariant, in the order the invariants are checked
+        let violations: Vec<Violation> = vec![
+            (|c| c.max_hops = 0, ContractError::InvalidMaxHops {}),
+            (
+                |c| c.max_spread = MAX_SPREAD + Decimal::permille(1),
+                ContractError::MaxSpreadTooLarge { max: MAX_SPREAD },
+            ),
+            (
+                |c| c.pair_type_max_spreads[0].max_spread = Decimal::one(),
+                ContractError::MaxSpreadTooLarge { max: MAX_SPREAD },
+            ),
+            (
+                |c| c.whitelisted_tokens.push(native("ukrw")),
+                ContractError::DuplicateWhitelistedToken {
+                    token: native("ukrw"),
+                },
+            ),
+            (
+                |c| c.whitelisted_fee_assets.push(fee_asset("uluna", 2_000)),
+                ContractError::DuplicateFeeAsset {
+                    asset: native("uluna"),
+                },
+            ),
+            (
+                |c| c.whitelisted_fee_assets.push(fee_asset("uusd", 0)),
+                ContractError::ZeroFeeAsset {
+                    asset: native("uusd"),
+                },
+            ),
+            (
+                |c| c.protocol_fee = MAX_PROTOCOL_FEE + Decimal::permille(1),
+                ContractError::ProtocolFeeTooLarge {
+                    max: MAX_PROTOCOL_FEE,
+                },
+            ),
+            (
+                |c| {
+                    if let Some(keeper_config) = &mut c.keeper_config {
+                        keeper_config.slash_threshold = Decimal::one();
+                    }
+                },
+                ContractError::InvalidSlashThreshold {},
+            ),
+            (
+                |c| {
+                    if let Some(order_deposit) = &mut c.order_deposit {
+                        order_deposit.info = native("ujpy");
+                    }
+                },
+                ContractError::NonWhitelistedTipAsset {
+                    asset: native("ujpy"),
+                },
+            ),
+            (
+                |c| c.venues.push(venue("pair")),
+                ContractError::DuplicateVenue {
+                    name: "pair".to_string(),
+                },
+            ),
+            (
+                |c| c.guardian = Some(Addr::unchecked("owner")),
+                ContractError::GuardianIsOwner {},
+            ),
+        ];
+
+        let api = MockApi::default();
+        for combination in 0..1u32 << violations.len() {
+            let mut config = valid.clone();
+            let mut expected = None;
+            for (i, (violate, err)) in violations.iter().enumerate() {
+                if combination & (1 << i) != 0 {
+                    violate(&mut config);
+                    expected = expected.or(Some(err));
+                }
+            }
+
+            // the configuration is only valid if no invariant is broken, and otherwise fails with
+            // the error of the first broken invariant
+            assert_eq!(
+                config.validate(&api).err().as_ref(),
+                expected,
+                "combination {:#b}",
+                combination
+            );
+        }
+    }
+
+    #[test]
+    fn does_prune_expired_keeper_executions() {
+        let mut storage = MockStorage::new();
+
+        let execution = |executed_at: u64| KeeperExecution {
+            keeper: Addr::unchecked("keeper"),
+            user: Addr::unchecked("user"),
+            order_id: 1,
+            offer_asset: Asset {
+                info: native("uluna"),
+                amount: Uint128::new(10_000),
+            },
+            hops: vec![],
+            return_amount: Uint128::new(20_000),
+            executed_at,
+            snapshots: None,
+        };
+
+        // the executions of the first 130 seconds are past their challenge period at 1_130, which
+        // are more than can be pruned at once
+        let count = MAX_PRUNED_EXECUTIONS as u64 + 5;
+        for id in 1..=count {
+            KEEPER_EXECUTIONS
+                .save(&mut storage, id, &execution(id * 10))
+                .unwrap();
+        }
+
+        // only a limited amount of executions is pruned at once
+        prune_keeper_executions(&mut storage, 1_130, 1_000).unwrap();
+        for id in 1..=count {
+            assert_eq!(
+                KEEPER_EXECUTIONS.has(&storage, id),
+                id > MAX_PRUNED_EXECUTIONS as u64
+            );
+        }
+
+        prune_keeper_executions(&mut storage, 1_130, 1_000).unwrap();
+        for id in 1..=count {
+            assert_eq!(KEEPER_EXECUTIONS.has(&storage, id), id * 10 >= 130);
+        }
+    }
+
+    #[test]
+    fn does_load_config_without_new_fields() {
+        // a configuration stored before any of the optional fields were added
+        let config: Config = from_slice(
+            br#"{
+                "owner": "owner",
+                "max_hops": 3,
+                "max_spread": "0.05",
+                "whitelisted_fee_assets": [],
+                "whitelisted_tokens": [],
+                "factory_addr": "factory",
+                "router_addr": "router"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config,
+            Config {
+                owner: Addr::unchecked("owner"),
+                guardian: None,
+                max_hops: 3,
+                max_spread: Decimal::percent(5),
+                whitelisted_fee_assets: vec![],
+                whitelisted_tokens: vec![],
+                factory_addr: Addr::unchecked("factory"),
+                router_addr: Addr::unchecked("router"),
+                protocol_fee: Decimal::zero(),
+                fee_collector: None,
+                max_referral_fee_bps: 0,
+                keeper_config: None,
+                allowed_pair_types: vec![PairType::Xyk {}, PairType::Stable {}],
+                pair_type_max_spreads: vec![],
+                venues: vec![],
+                config_timelock: DEFAULT_CONFIG_TIMELOCK,
+                allowed_initial_assets: AllowedAssets::All {},
+                allowed_target_assets: AllowedAssets::All {},
+                min_dca_amounts: vec![],
+                max_orders_per_user: DEFAULT_MAX_ORDERS_PER_USER,
+                order_deposit: None,
+            }
+        );
+    }
+}
@@ -0,0 +1,197 @@
+use std::{cmp::Ordering, collections::BTreeMap, iter::Peekable, ops::Bound};
+
+use cosmwasm_std::{Order, Record, Storage};
+
+/// Wraps a read-only [`Storage`], keeping all writes in memory instead of applying them, so that
+/// executions can be dry run in queries.
+pub struct StorageOverlay<'a> {
+    storage: &'a dyn Storage,
+    /// The values written to each key, where removed keys are [`None`]
+    changes: BTreeMap<Vec<u8>, Option<Vec<u8>>>,
+}
+
+impl<'a> StorageOverlay<'a> {
+    pub fn new(storage: &'a dyn Storage) -> Self {
+        Self {
+            storage,
+            changes: BTreeMap::new(),
+        }
+    }
+}
+
+impl<'a> Storage for StorageOverlay<'a> {
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        match self.changes.get(key) {
+            Some(value) => value.clone(),
+            None => self.storage.get(key),
+        }
+    }
+
+    fn range<'b>(
+        &'b self,
+        start: Option<&[u8]>,
+        end: Option<&[u8]>,
+        order: Order,
+    ) -> Box<dyn Iterator<Item = Record> + 'b> {
+        let bounds = (
+            start.map_or(Bound::Unbounded, |start| Bound::Included(start.to_vec())),
+            end.map_or(Bound::Unbounded, |end| Bound::Excluded(end.to_vec())),
+        );
+        // an empty range is described if start is not less than end, which BTreeMap panics on
+        let changes: Changes = match (start.zip(end), order) {
+            (Some((start, end)), _) if start >= end => Box::new(std::iter::empty()),
+            (_, Order::Ascending) => Box::new(self.changes.range(bounds)),
+            (_, Order::Descending) => Box::new(self.changes.range(bounds).rev()),
+        };
+
+        Box::new(MergedRange {
+            ascending: matches!(order, Order::Ascending),
+            records: self.storage.range(start, end, order).peekable(),
+            changes: changes.peekable(),
+        })
+    }
+
+    fn set(&mut self, key: &[u8], value: &[u8]) {
+        self.changes.insert(key.to_vec(), Some(value.to_vec()));
+    }
+
+    fn remove(&mut self, key: &[u8]) {
+        self.changes.insert(key.to_vec(), None);
+    }
+}
+
+/// The changes written over a range of keys, in the order of the range
+type Changes<'b> = Box<dyn Iterator<Item = (&'b Vec<u8>, &'b Option<Vec<u8>>)> + 'b>;
+
+/// Iterates the records of the underlying storage merged with the changes written over them, both
+/// of which are iterated in the same order, without collecting either into memory
+struct MergedRange<'b> {
+    ascending: bool,
+    records: Peekable<Box<dyn Iterator<Item = Record> + 'b>>,
+    changes: Peekable<Changes<'b>>,
+}
+
+impl<'b> Iterator for MergedRange<'b> {
+    type Item = Record;
+
+    fn next(&mut self) -> Option<Record> {
+        loop {
+            // a change comes first if its key is reached before the next record in the range order
+            let change_first = match (self.records.peek(), self.changes.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => false,
+                (None, Some(_)) => true,
+                (Some((record_key, _)), Some((change_key, _))) => {
+                    let ordering = match self.ascending {
+                        true => change_key.as_slice().cmp(record_key.as_slice()),
+                        false => record_key.as_slice().cmp(change_key.as_slice()),
+                    };
+
+                    // a change of the same key replaces the record
+                    if ordering == Ordering::Equal {
+                        self.records.next();
+                    }
+                    ordering != Ordering::Greater
+                }
+            };
+
+            if !change_first {
+                return self.records.next();
+            }
+
+            // removed keys are skipped
+            if let Some((key, Some(value))) = self.changes.next() {
+                return Some((key.clone(), value.clone()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use cosmwasm_std::{testing::MockStorage, Order, Storage};
+
+    use super::StorageOverlay;
+
+    #[test]
+    fn does_not_write_through() {
+        let mut storage = MockStorage::new();
+        storage.set(b"a", b"1");
+        storage.set(b"b", b"2");
+
+        let mut overlay = StorageOverlay::new(&storage);
+        overlay.set(b"a", b"3");
+        overlay.remove(b"b");
+        overlay.set(b"c", b"4");
+
+        assert_eq!(overlay.get(b"a"), Some(b"3".to_vec()));
+        assert_eq!(overlay.get(b"b"), None);
+        assert_eq!(
+            overlay
+                .range(None, None, Order::Descending)
+                .collect::<Vec<_>>(),
+            vec![
+                (b"c".to_vec(), b"4".to_vec()),
+                (b"a".to_vec(), b"3".to_vec())
+            ]
+        );
+        assert_eq!(
+            overlay
+                .range(Some(b"b"), Some(b"a"), Order::Ascending)
+                .count(),
+            0
+        );
+
+        // the underlying storage is left unchanged
+        assert_eq!(storage.get(b"a"), Some(b"1".to_vec()));
+        assert_eq!(storage.get(b"b"), Some(b"2".to_vec()));
+        assert_eq!(storage.get(b"c"), None);
+    }
+
+    #[test]
+    fn does_merge_changes_into_range() {
+        let mut storage = MockStorage::new();
+        for key in [b"a", b"c", b"e", b"g"] {
+            storage.set(key, b"1");
+        }
+
+        let mut overlay = StorageOverlay::new(&storage);
+        overlay.set(b"b", b"2");
+        overlay.set(b"c", b"2");
+        overlay.remove(b"e");
+        overlay.remove(b"f");
+        overlay.set(b"h", b"2");
+
+        let range = |start: Option<&[u8]>, end: Option<&[u8]>, order: Order| {
+            overlay
+                .range(start, end, order)
+                .map(|(key, value)| (String::from_utf8(key).unwrap(), value))
+                .collect::<Vec<_>>()
+        };
+        let records = |records: &[(&str, &[u8])]| {
+            records
+                .iter()
+                .map(|(key, value)| (key.to_string(), value.to_vec()))
+                .collect::<Vec<_>>()
+        };
+
+        let all = [
+            ("a", &b"1"[..]),
+            ("b", b"2"),
+            ("c", b"2"),
+            ("g", b"1"),
+            ("h", b"2"),
+        ];
+        assert_eq!(range(None, None, Order::Ascending), records(&all));
+
+        let mut reversed = all.to_vec();
+        reversed.reverse();
+        assert_eq!(range(None, None, Order::Descending), records(&reversed));
+
+        // the bounds apply to both the records and the changes
+        assert_eq!(
+            range(Some(b"b"), Some(b"h"), Order::Descending),
+            records(&[("g", b"1"), ("c", b"2"), ("b", b"2")])
+        );
+    }
+}
@@ -0,0 +1,50 @@
+use astroport::{
+    asset::PairInfo,
+    factory::{ConfigResponse, PairType, QueryMsg as FactoryQueryMsg},
+};
+use cosmwasm_std::{
+    from_binary, testing::MockQuerier, to_binary, Addr, ContractResult, SystemError, SystemResult,
+    WasmQuery,
+};
+
+/// Mocks the Astroport factory at `factory_addr` in `querier`, where an xyk pair exists between
+/// any two assets.
+pub fn mock_factory(querier: &mut MockQuerier, factory_addr: &Addr) {
+    let factory_addr = factory_addr.to_string();
+
+    querier.update_wasm(move |query| match query {
+        WasmQuery::Smart { contract_addr, msg } if contract_addr == &factory_addr => {
+            match from_binary(msg) {
+                Ok(FactoryQueryMsg::Pair { asset_infos }) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&PairInfo {
+                        asset_infos,
+                        contract_addr: Addr::unchecked("pair"),
+                        liquidity_token: Addr::unchecked("lp_token"),
+                        pair_type: PairType::Xyk {},
+                    })
+                    .unwrap(),
+                )),
+                Ok(FactoryQueryMsg::Config {}) => SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&ConfigResponse {
+                        owner: Addr::unchecked("factory_owner"),
+                        pair_configs: vec![],
+                        token_code_id: 0,
+                        fee_address: None,
+                        generator_address: None,
+                        whitelist_code_id: 0,
+                    })
+                    .unwrap(),
+                )),
+                _ => SystemResult::Err(SystemError::UnsupportedRequest {
+                    kind: "factory query".to_string(),
+                }),
+            }
+        }
+        WasmQuery::Smart { contract_addr, .. } => SystemResult::Err(SystemError::NoSuchContract {
+            addr: contract_addr.clone(),
+        }),
+        _ => SystemResult::Err(SystemError::UnsupportedRequest {
+            kind: "wasm query".to_string(),
+        }),
+    });
+}
@@ -1,16 +1,31 @@
 use astroport::asset::{Asset, AssetInfo};
-use astroport_dca::dca::InstantiateMsg;
+use astroport_dca::dca::{FeeAsset, InstantiateMsg};
 use cosmwasm_std::{
     testing::{mock_dependencies, mock_env, MockApi, MockQuerier, MockStorage},
-    Addr, Env, OwnedDeps,
+    Addr, Env, OwnedDeps, Uint128,
 };
 use cw_multi_test::{App, Executor};
 
 use crate::contract::instantiate;
 
-use super::mock_creator;
+use super::{mock_creator, mock_factory};
 
-/// Instantiates the dca module.
+/// Converts `per_hop_fees` into whitelisted fee assets which only charge a tip per hop.
+pub fn per_hop_fee_assets(per_hop_fees: Vec<Asset>) -> Vec<FeeAsset> {
+    per_hop_fees
+        .into_iter()
+        .map(|fee| FeeAsset {
+            info: fee.info,
+            base_fee: Uint128::zero(),
+            per_hop_fee: fee.amount,
+            batch_surcharge: None,
+        })
+        .collect()
+}
+
+/// Instantiates the dca module, with `whitelisted_fee_assets` charging a tip per hop of their amount.
+///
+/// The factory at `factory_addr` is mocked to have an xyk pair between any two assets.
 pub fn mock_instantiate(
     factory_addr: Addr,
     router_addr: Addr,
@@ -27,19 +42,38 @@ pub fn mock_instantiate(
         env.clone(),
         creator,
         InstantiateMsg {
-            factory_addr: factory_addr.into_string(),
+            owner: "factory_owner".to_string(),
+            guardian: Some("guardian".to_string()),
+            config_timelock: None,
+            factory_addr: factory_addr.to_string(),
             router_addr: router_addr.into_string(),
             max_hops: 4,
             max_spread: "0.05".to_string(),
-            whitelisted_fee_assets,
+            whitelisted_fee_assets: per_hop_fee_assets(whitelisted_fee_assets),
             whitelisted_tokens,
+            protocol_fee: None,
+            fee_collector: None,
+            max_referral_fee_bps: 0,
+            keeper_config: None,
+            allowed_pair_types: None,
+            pair_type_max_spreads: None,
+            venues: None,
+            allowed_initial_assets: None,
+            allowed_target_assets: None,
+            min_dca_amounts: None,
+            max_orders_per_user: None,
+            order_deposit: None,
         },
     )
     .unwrap();
 
+    mock_factory(&mut deps.querier, &factory_addr);
+
     (deps, env)
 }
 
+/// Instantiates the dca module in `app`, with `whitelisted_fee_assets` charging a tip per hop of
+/// their amount.
 pub fn app_mock_instantiate(
     app: &mut App,
     dca_module_id: u64,
@@ -53,12 +87,27 @@ pub fn app_mock_instantiate(
         dca_module_id,
         creator.sender,
         &InstantiateMsg {
+            owner: "factory_owner".to_string(),
+            guardian: None,
+            config_timelock: None,
             factory_addr: factory_addr.into_string(),
             router_addr: router_addr.into_string(),
             max_hops: 4,
             max_spread: "0.05".to_string(),
-            whitelisted_fee_assets,
+            whitelisted_fee_assets: per_hop_fee_assets(whitelisted_fee_assets),
             whitelisted_tokens: vec![],
+            protocol_fee: None,
+            fee_collector: None,
+            max_referral_fee_bps: 0,
+            keeper_config: None,
+            allowed_pair_types: None,
+            pair_type_max_spreads: None,
+            venues: None,
+            allowed_initial_assets: None,
+            allowed_target_assets: None,
+            min_dca_amounts: None,
+            max_orders_per_user: None,
+            order_deposit: None,
         },
         &[],
         "dca_module",
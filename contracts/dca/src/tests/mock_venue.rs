@@ -0,0 +1,192 @@
+use astroport::{
+    asset::{AssetInfo, PairInfo},
+    factory::PairType,
+    pair::{ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg, SimulationResponse},
+    router::{
+        ConfigResponse as RouterConfigResponse, ExecuteMsg as RouterExecuteMsg,
+        QueryMsg as RouterQueryMsg, SimulateSwapOperationsResponse,
+    },
+};
+use cosmwasm_std::{
+    to_binary, Addr, BankMsg, Binary, Coin, Decimal, Deps, DepsMut, Env, MessageInfo, Response,
+    StdError, StdResult, Uint128,
+};
+use cw_multi_test::{App, ContractWrapper};
+use cw_storage_plus::Item;
+use serde::{Deserialize, Serialize};
+
+/// The instantiate message of a mock DEX router, which swaps any route at a fixed `rate` and
+/// reports `factory_addr` as the factory of its pairs
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MockRouterInstantiateMsg {
+    pub factory_addr: String,
+    pub rate: Decimal,
+}
+
+/// The instantiate message of a mock pair between `asset_infos`, which swaps at a fixed `rate`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MockPairInstantiateMsg {
+    pub asset_infos: [AssetInfo; 2],
+    pub pair_type: PairType,
+    pub rate: Decimal,
+}
+
+const ROUTER_CONFIG: Item<MockRouterInstantiateMsg> = Item::new("router_config");
+const PAIR_CONFIG: Item<MockPairInstantiateMsg> = Item::new("pair_config");
+
+/// Pays out `amount` of the native `ask_asset_info` at `rate` to `recipient` from the balance of
+/// the mock venue
+fn pay_out(
+    ask_asset_info: &AssetInfo,
+    amount: Uint128,
+    rate: Decimal,
+    recipient: String,
+) -> StdResult<Response> {
+    let denom = match ask_asset_info {
+        AssetInfo::NativeToken { denom } => denom.clone(),
+        AssetInfo::Token { .. } => return Err(StdError::generic_err("cw20 asks are unsupported")),
+    };
+
+    Ok(Response::new().add_message(BankMsg::Send {
+        to_address: recipient,
+        amount: vec![Coin {
+            denom,
+            amount: amount * rate,
+        }],
+    }))
+}
+
+/// Returns the amount of the single native coin sent along with a swap
+fn offer_amount(info: &MessageInfo) -> StdResult<Uint128> {
+    match info.funds.as_slice() {
+        [coin] => Ok(coin.amount),
+        _ => Err(StdError::generic_err("expected a single native coin")),
+    }
+}
+
+fn router_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockRouterInstantiateMsg,
+) -> StdResult<Response> {
+    ROUTER_CONFIG.save(deps.storage, &msg)?;
+    Ok(Response::new())
+}
+
+fn router_execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: RouterExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        RouterExecuteMsg::ExecuteSwapOperations { operations, to, .. } => {
+            let config = ROUTER_CONFIG.load(deps.storage)?;
+            let ask_asset_info = operations
+                .last()
+                .ok_or_else(|| StdError::generic_err("empty route"))?
+                .get_target_asset_info();
+
+            pay_out(
+                &ask_asset_info,
+                offer_amount(&info)?,
+                config.rate,
+                to.unwrap_or_else(|| info.sender.to_string()),
+            )
+        }
+        _ => Err(StdError::generic_err("unsupported router message")),
+    }
+}
+
+fn router_query(deps: Deps, _env: Env, msg: RouterQueryMsg) -> StdResult<Binary> {
+    let config = ROUTER_CONFIG.load(deps.storage)?;
+    match msg {
+        RouterQueryMsg::SimulateSwapOperations { offer_amount, .. } => {
+            to_binary(&SimulateSwapOperationsResponse {
+                amount: offer_amount * config.rate,
+            })
+        }
+        _ => to_binary(&RouterConfigResponse {
+            astroport_factory: config.factory_addr,
+        }),
+    }
+}
+
+fn pair_instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    msg: MockPairInstantiateMsg,
+) -> StdResult<Response> {
+    PAIR_CONFIG.save(deps.storage, &msg)?;
+    Ok(Response::new())
+}
+
+fn pair_execute(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: PairExecuteMsg,
+) -> StdResult<Response> {
+    match msg {
+        PairExecuteMsg::Swap {
+            offer_asset, to, ..
+        } => {
+            let config = PAIR_CONFIG.load(deps.storage)?;
+            let ask_asset_info = config
+                .asset_infos
+                .iter()
+                .find(|asset_info| **asset_info != offer_asset.info)
+                .ok_or_else(|| StdError::generic_err("asset not in pair"))?;
+
+            pay_out(
+                ask_asset_info,
+                offer_amount(&info)?,
+                config.rate,
+                to.unwrap_or_else(|| info.sender.to_string()),
+            )
+        }
+        _ => Err(StdError::generic_err("unsupported pair message")),
+    }
+}
+
+fn pair_query(deps: Deps, env: Env, msg: PairQueryMsg) -> StdResult<Binary> {
+    let config = PAIR_CONFIG.load(deps.storage)?;
+    match msg {
+        PairQueryMsg::Pair {} => to_binary(&PairInfo {
+            asset_infos: config.asset_infos,
+            contract_addr: env.contract.address,
+            liquidity_token: Addr::unchecked("lp_token"),
+            pair_type: config.pair_type,
+        }),
+        PairQueryMsg::Simulation { offer_asset } => to_binary(&SimulationResponse {
+            return_amount: offer_asset.amount * config.rate,
+            spread_amount: Uint128::zero(),
+            commission_amount: Uint128::zero(),
+        }),
+        _ => Err(StdError::generic_err("unsupported pair query")),
+    }
+}
+
+/// Stores a mock DEX router, instantiated with a [`MockRouterInstantiateMsg`], to the app
+pub fn store_mock_router_code(app: &mut App) -> u64 {
+    let contract = Box::new(ContractWrapper::new(
+        router_execute,
+        router_instantiate,
+        router_query,
+    ));
+
+    app.store_code(contract)
+}
+
+/// Stores a mock pair, instantiated with a [`MockPairInstantiateMsg`], to the app
+pub fn store_mock_pair_code(app: &mut App) -> u64 {
+    let contract = Box::new(ContractWrapper::new(
+        pair_execute,
+        pair_instantiate,
+        pair_query,
+    ));
+
+    app.store_code(contract)
+}
@@ -1,16 +1,22 @@
 mod mock_app;
 mod mock_creator;
+mod mock_factory;
 mod mock_instantiate;
+mod mock_venue;
 mod read_item;
 mod read_map;
 mod store_code;
 
 pub use mock_app::{mock_app, mock_app_with_balance};
 pub use mock_creator::mock_creator;
-pub use mock_instantiate::{app_mock_instantiate, mock_instantiate};
+pub use mock_factory::mock_factory;
+pub use mock_instantiate::{app_mock_instantiate, mock_instantiate, per_hop_fee_assets};
+pub use mock_venue::{
+    store_mock_pair_code, store_mock_router_code, MockPairInstantiateMsg, MockRouterInstantiateMsg,
+};
 pub use read_item::read_item;
 pub use read_map::read_map;
 pub use store_code::{
-    store_astroport_pair_code, store_cw20_token_code, store_dca_module_code, store_factory_code,
-    store_router_code,
+    store_astroport_pair_code, store_astroport_stable_pair_code, store_cw20_token_code,
+    store_dca_module_code, store_factory_code, store_router_code,
 };
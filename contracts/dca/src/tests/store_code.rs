@@ -1,11 +1,14 @@
 use cw_multi_test::{App, ContractWrapper};
 
-use crate::contract::{execute, instantiate, migrate, query};
+use crate::contract::{execute, instantiate, migrate, query, reply};
 
 /// Stores the DCA module contract to the app
 pub fn store_dca_module_code(app: &mut App) -> u64 {
-    let contract =
-        Box::new(ContractWrapper::new(execute, instantiate, query).with_migrate(migrate));
+    let contract = Box::new(
+        ContractWrapper::new(execute, instantiate, query)
+            .with_migrate(migrate)
+            .with_reply(reply),
+    );
 
     app.store_code(contract)
 }
@@ -59,3 +62,17 @@ pub fn store_astroport_pair_code(app: &mut App) -> u64 {
 
     app.store_code(contract)
 }
+
+/// Stores the Astroport stableswap pair to the app
+pub fn store_astroport_stable_pair_code(app: &mut App) -> u64 {
+    let contract = Box::new(
+        ContractWrapper::new(
+            astroport_pair_stable::contract::execute,
+            astroport_pair_stable::contract::instantiate,
+            astroport_pair_stable::contract::query,
+        )
+        .with_reply(astroport_pair_stable::contract::reply),
+    );
+
+    app.store_code(contract)
+}
@@ -0,0 +1,301 @@
+use astroport::{
+    asset::AssetInfo,
+    pair::{CumulativePricesResponse, QueryMsg as PairQueryMsg, TWAP_PRECISION},
+    querier::query_pair_info,
+    router::SwapOperation,
+};
+use cosmwasm_std::{Addr, QuerierWrapper, StdResult, Storage, Uint128};
+
+use crate::state::{
+    HopSnapshot, PriceObservation, PriceObservations, MIN_TWAP_WINDOW, PRICE_OBSERVATIONS,
+};
+
+/// ## Description
+/// Retrieves the cumulative price of `offer_asset_info` in the pair at `pair_addr` as a
+/// [`Uint128`].
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the pair.
+///
+/// * `pair_addr` - The address of the Astroport pair.
+///
+/// * `offer_asset_info` - The [`AssetInfo`] of the asset being offered to the pair.
+pub fn query_price_cumulative(
+    querier: &QuerierWrapper,
+    pair_addr: &Addr,
+    offer_asset_info: &AssetInfo,
+) -> StdResult<Uint128> {
+    let res: CumulativePricesResponse =
+        querier.query_wasm_smart(pair_addr, &PairQueryMsg::CumulativePrices {})?;
+
+    Ok(match &res.assets[0].info == offer_asset_info {
+        true => res.price0_cumulative_last,
+        false => res.price1_cumulative_last,
+    })
+}
+
+/// ## Description
+/// Observes the cumulative price of `offer_asset_info` in the pair at `pair_addr`, returning the
+/// current cumulative price along with the most recent stored observation that is at least
+/// [`MIN_TWAP_WINDOW`] seconds old to anchor a TWAP, if there is one.
+///
+/// The latest observation is only replaced once it is [`MIN_TWAP_WINDOW`] seconds old, at which
+/// point it becomes the anchor, so that observing the pair can never shorten the window of a TWAP
+/// below [`MIN_TWAP_WINDOW`] while pairs that are rarely observed are still anchored by their
+/// previous observation.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `querier` - A [`QuerierWrapper`] used to query the pair.
+///
+/// * `now` - The current time in seconds.
+///
+/// * `pair_addr` - The address of the Astroport pair.
+///
+/// * `offer_asset_info` - The [`AssetInfo`] of the asset being offered to the pair.
+pub fn observe_price(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    now: u64,
+    pair_addr: &Addr,
+    offer_asset_info: &AssetInfo,
+) -> StdResult<(Uint128, Option<PriceObservation>)> {
+    let key = (pair_addr, offer_asset_info.to_string());
+    let price_cumulative = query_price_cumulative(querier, pair_addr, offer_asset_info)?;
+    let latest = PriceObservation {
+        price_cumulative,
+        observed_at: now,
+    };
+
+    let anchor = match PRICE_OBSERVATIONS.may_load(storage, key.clone())? {
+        Some(observations)
+            if now.saturating_sub(observations.latest.observed_at) < MIN_TWAP_WINDOW =>
+        {
+            return Ok((price_cumulative, observations.anchor));
+        }
+        Some(observations) => Some(observations.latest),
+        None => None,
+    };
+
+    PRICE_OBSERVATIONS.save(
+        storage,
+        key,
+        &PriceObservations {
+            anchor: anchor.clone(),
+            latest,
+        },
+    )?;
+
+    Ok((price_cumulative, anchor))
+}
+
+/// ## Description
+/// Calculates the TWAP from the `anchor` observation until `price_cumulative` was observed at
+/// `now`, as a ratio of the purchased asset per offered asset.
+/// ## Arguments
+/// * `anchor` - The [`PriceObservation`] the TWAP starts at.
+///
+/// * `price_cumulative` - The cumulative price at the end of the TWAP.
+///
+/// * `now` - The time in seconds the TWAP ends at, which must be after the `anchor`.
+pub fn average_price(
+    anchor: &PriceObservation,
+    price_cumulative: Uint128,
+    now: u64,
+) -> StdResult<(Uint128, Uint128)> {
+    let precision = Uint128::new(10u128.pow(TWAP_PRECISION.into()));
+
+    // the average price over the window is the change in the cumulative price over time
+    Ok((
+        price_cumulative.wrapping_sub(anchor.price_cumulative),
+        precision.checked_mul(Uint128::from(now.saturating_sub(anchor.observed_at)))?,
+    ))
+}
+
+/// ## Description
+/// Takes a snapshot of the cumulative prices of each pair in the hop route, returning [`None`] if
+/// any of the hops is not an Astroport swap or its pair has no observation at least
+/// [`MIN_TWAP_WINDOW`] seconds old to anchor the TWAP reference.
+/// ## Arguments
+/// * `storage` - The [`Storage`] of the contract.
+///
+/// * `querier` - A [`QuerierWrapper`] used to query the factory and the pairs.
+///
+/// * `now` - The current time in seconds.
+///
+/// * `factory_addr` - The address of the Astroport factory contract.
+///
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+pub fn snapshot_hops(
+    storage: &mut dyn Storage,
+    querier: &QuerierWrapper,
+    now: u64,
+    factory_addr: &Addr,
+    hops: &[SwapOperation],
+) -> StdResult<Option<Vec<HopSnapshot>>> {
+    let mut snapshots = vec![];
+    for hop in hops {
+        let (offer_asset_info, ask_asset_info) = match hop {
+            SwapOperation::AstroSwap {
+                offer_asset_info,
+                ask_asset_info,
+            } => (offer_asset_info, ask_asset_info),
+            SwapOperation::NativeSwap { .. } => return Ok(None),
+        };
+
+        let pair_info = query_pair_info(
+            querier,
+            factory_addr.clone(),
+            &[offer_asset_info.clone(), ask_asset_info.clone()],
+        )?;
+
+        let (price_cumulative, anchor) = observe_price(
+            storage,
+            querier,
+            now,
+            &pair_info.contract_addr,
+            offer_asset_info,
+        )?;
+
+        match anchor {
+            Some(anchor) => snapshots.push(HopSnapshot {
+                pair_addr: pair_info.contract_addr,
+                price_cumulative,
+                anchor,
+            }),
+            None => return Ok(None),
+        }
+    }
+
+    Ok(Some(snapshots))
+}
+
+/// ## Description
+/// Calculates the amount `offer_amount` would have returned through the hop route at the time
+/// weighted average price of each pair from the anchor of its snapshot until the snapshot was
+/// taken at `executed_at`.
+/// ## Arguments
+/// * `offer_amount` - The [`Uint128`] amount offered to the first hop.
+///
+/// * `snapshots` - The [`Vec<HopSnapshot>`] taken for each hop.
+///
+/// * `executed_at` - The time in seconds the snapshots were taken.
+pub fn twap_return(
+    offer_amount: Uint128,
+    snapshots: &[HopSnapshot],
+    executed_at: u64,
+) -> StdResult<Uint128> {
+    let mut amount = offer_amount;
+    for snapshot in snapshots {
+        let (numerator, denominator) =
+            average_price(&snapshot.anchor, snapshot.price_cumulative, executed_at)?;
+        amount = amount.multiply_ratio(numerator, denominator);
+    }
+
+    Ok(amount)
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::{
+        asset::{Asset, AssetInfo, PairInfo},
+        factory::{PairType, QueryMsg as FactoryQueryMsg},
+        pair::CumulativePricesResponse,
+        router::SwapOperation,
+    };
+    use cosmwasm_std::{
+        from_binary, testing::mock_dependencies, to_binary, Addr, ContractResult, QuerierWrapper,
+        SystemResult, Uint128, WasmQuery,
+    };
+
+    use crate::state::{PriceObservation, MIN_TWAP_WINDOW};
+
+    use super::snapshot_hops;
+
+    #[test]
+    fn does_anchor_infrequent_purchases() {
+        let mut deps = mock_dependencies();
+
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let assets = [
+            Asset {
+                info: uluna.clone(),
+                amount: Uint128::new(1_000_000),
+            },
+            Asset {
+                info: ukrw.clone(),
+                amount: Uint128::new(2_000_000),
+            },
+        ];
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "factory" => {
+                match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    _ => unimplemented!(),
+                }
+            }
+            WasmQuery::Smart { contract_addr, .. } if contract_addr == "pair" => {
+                SystemResult::Ok(ContractResult::Ok(
+                    to_binary(&CumulativePricesResponse {
+                        assets: assets.clone(),
+                        total_share: Uint128::new(1_000_000),
+                        price0_cumulative_last: Uint128::new(2_000_000_000),
+                        price1_cumulative_last: Uint128::new(500_000_000),
+                    })
+                    .unwrap(),
+                ))
+            }
+            _ => unimplemented!(),
+        });
+
+        let hops = [SwapOperation::AstroSwap {
+            offer_asset_info: uluna,
+            ask_asset_info: ukrw,
+        }];
+        let factory_addr = Addr::unchecked("factory");
+        let mut snapshot = |now: u64| {
+            snapshot_hops(
+                &mut deps.storage,
+                &QuerierWrapper::new(&deps.querier),
+                now,
+                &factory_addr,
+                &hops,
+            )
+            .unwrap()
+            .map(|snapshots| snapshots[0].anchor.clone())
+        };
+
+        // the first purchase of an order with an interval of 24 hours has nothing to anchor it
+        let first = 1_000_000;
+        assert_eq!(snapshot(first), None);
+
+        // its next purchase is anchored by the previous purchase, however long ago it was
+        let observation = |observed_at| PriceObservation {
+            price_cumulative: Uint128::new(2_000_000_000),
+            observed_at,
+        };
+        let second = first + 86_400;
+        assert_eq!(snapshot(second - 10), Some(observation(first)));
+
+        // observing the pair right before a purchase does not shorten its window
+        assert_eq!(snapshot(second), Some(observation(first)));
+
+        // the latest observation only anchors once it is old enough
+        assert_eq!(
+            snapshot(second - 10 + MIN_TWAP_WINDOW),
+            Some(observation(second - 10))
+        );
+    }
+}
@@ -0,0 +1,561 @@
+use astroport::{
+    asset::{Asset, AssetInfo, PairInfo},
+    factory::PairType,
+    pair::{
+        Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg as PairQueryMsg,
+        SimulationResponse,
+    },
+    router::{
+        ConfigResponse as RouterConfigResponse, ExecuteMsg as RouterExecuteMsg,
+        QueryMsg as RouterQueryMsg, SimulateSwapOperationsResponse, SwapOperation,
+    },
+};
+use astroport_dca::dca::{SwapVenue, SwapVenueType};
+use cosmwasm_std::{
+    to_binary, Addr, Coin, CosmosMsg, Decimal, QuerierWrapper, StdError, StdResult, Uint128,
+    WasmMsg,
+};
+use cw20::Cw20ExecuteMsg;
+
+use crate::{error::ContractError, route::assert_pairs_allowed, state::Config};
+
+/// ## Description
+/// Returns the address of the factory of the pairs the swap venue swaps through, where routers
+/// other than the configured Astroport router, including those of other DEXes sharing its
+/// interface, return their factory in their configuration. Returns [`None`] for pair venues,
+/// which only swap through themselves.
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the venue.
+///
+/// * `config` - The contract [`Config`], containing the Astroport factory.
+///
+/// * `venue` - The [`SwapVenue`] swapping through the pairs.
+pub fn venue_factory(
+    querier: &QuerierWrapper,
+    config: &Config,
+    venue: &SwapVenue,
+) -> StdResult<Option<Addr>> {
+    match venue.venue_type {
+        SwapVenueType::AstroportRouter {} if venue.contract_addr == config.router_addr => {
+            Ok(Some(config.factory_addr.clone()))
+        }
+        SwapVenueType::AstroportPair {} => Ok(None),
+        SwapVenueType::AstroportRouter {} | SwapVenueType::Router {} => {
+            let router_config: RouterConfigResponse =
+                querier.query_wasm_smart(&venue.contract_addr, &RouterQueryMsg::Config {})?;
+
+            Ok(Some(Addr::unchecked(router_config.astroport_factory)))
+        }
+    }
+}
+
+/// ## Description
+/// Checks that the swap venue can perform the hop route, where every venue can only swap through
+/// existing pairs of an allowed pair type of its factory.
+///
+/// Returns the [`PairType`] of the pair swapped through in each hop, or [`None`] for native swaps,
+/// as a [`Vec<Option<PairType>>`].
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the venue, the factories and the pairs.
+///
+/// * `config` - The contract [`Config`], containing the allowed pair types.
+///
+/// * `venue` - The [`SwapVenue`] performing the hop route.
+///
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+pub fn assert_venue_route(
+    querier: &QuerierWrapper,
+    config: &Config,
+    venue: &SwapVenue,
+    hops: &[SwapOperation],
+) -> Result<Vec<Option<PairType>>, ContractError> {
+    if let Some(factory_addr) = venue_factory(querier, config, venue)? {
+        return assert_pairs_allowed(querier, config, &factory_addr, hops);
+    }
+
+    // pair venues have no factory and can only perform a single swap through themselves
+    let (offer_asset_info, ask_asset_info) = match hops {
+        [SwapOperation::AstroSwap {
+            offer_asset_info,
+            ask_asset_info,
+        }] => (offer_asset_info, ask_asset_info),
+        _ => return Err(ContractError::InvalidPairVenueRoute {}),
+    };
+
+    let pair: PairInfo = querier.query_wasm_smart(&venue.contract_addr, &PairQueryMsg::Pair {})?;
+    if !pair.asset_infos.contains(offer_asset_info) || !pair.asset_infos.contains(ask_asset_info) {
+        return Err(ContractError::InvalidPairVenueRoute {});
+    }
+
+    if !config.is_allowed_pair_type(&pair.pair_type) {
+        return Err(ContractError::PairTypeNotAllowed {
+            pair_type: pair.pair_type,
+        });
+    }
+
+    Ok(vec![Some(pair.pair_type)])
+}
+
+/// ## Description
+/// Creates the messages performing the hop route through the swap venue with `offer_asset` of the
+/// `user`, sending the purchased asset to the `recipient`.
+///
+/// Native tokens are sent from the contract, while cw20 tokens are transferred using the allowance
+/// of the `user`.
+/// ## Arguments
+/// * `venue` - The [`SwapVenue`] performing the hop route.
+///
+/// * `user` - The address of the user the DCA purchase is performed for.
+///
+/// * `recipient` - The address receiving the purchased asset.
+///
+/// * `offer_asset` - The [`Asset`] being swapped.
+///
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+///
+/// * `max_spread` - The maximum spread of the swaps.
+pub fn swap_msgs(
+    venue: &SwapVenue,
+    user: &Addr,
+    recipient: &Addr,
+    offer_asset: &Asset,
+    hops: &[SwapOperation],
+    max_spread: Decimal,
+) -> StdResult<Vec<CosmosMsg>> {
+    let funds = match &offer_asset.info {
+        AssetInfo::NativeToken { denom } => vec![Coin {
+            amount: offer_asset.amount,
+            denom: denom.clone(),
+        }],
+        AssetInfo::Token { .. } => vec![],
+    };
+
+    let mut messages = vec![];
+    match venue.venue_type {
+        SwapVenueType::AstroportRouter {} | SwapVenueType::Router {} => {
+            // send a TransferFrom request to the token to the router
+            if let AssetInfo::Token { contract_addr } = &offer_asset.info {
+                messages.push(
+                    WasmMsg::Execute {
+                        contract_addr: contract_addr.to_string(),
+                        funds: vec![],
+                        msg: to_binary(&Cw20ExecuteMsg::TransferFrom {
+                            owner: user.to_string(),
+                            recipient: venue.contract_addr.to_string(),
+                            amount: offer_asset.amount,
+                        })?,
+                    }
+                    .into(),
+                );
+            }
+
+            // tell the router to perform swap operations
+            messages.push(
+                WasmMsg::Execute {
+                    contract_addr: venue.contract_addr.to_string(),
+                    funds,
+                    msg: to_binary(&RouterExecuteMsg::ExecuteSwapOperations {
+                        operations: hops.to_vec(),
+                        minimum_receive: None,
+                        to: Some(recipient.to_string()),
+                        max_spread: Some(max_spread),
+                    })?,
+                }
+                .into(),
+            );
+        }
+        SwapVenueType::AstroportPair {} => {
+            let msg = match &offer_asset.info {
+                // send the token to the pair with a swap hook
+                AssetInfo::Token { contract_addr } => WasmMsg::Execute {
+                    contract_addr: contract_addr.to_string(),
+                    funds: vec![],
+                    msg: to_binary(&Cw20ExecuteMsg::SendFrom {
+                        owner: user.to_string(),
+                        contract: venue.contract_addr.to_string(),
+                        amount: offer_asset.amount,
+                        msg: to_binary(&PairCw20HookMsg::Swap {
+                            belief_price: None,
+                            max_spread: Some(max_spread),
+                            to: Some(recipient.to_string()),
+                        })?,
+                    })?,
+                },
+                AssetInfo::NativeToken { .. } => WasmMsg::Execute {
+                    contract_addr: venue.contract_addr.to_string(),
+                    funds,
+                    msg: to_binary(&PairExecuteMsg::Swap {
+                        offer_asset: offer_asset.clone(),
+                        belief_price: None,
+                        max_spread: Some(max_spread),
+                        to: Some(recipient.to_string()),
+                    })?,
+                },
+            };
+
+            messages.push(msg.into());
+        }
+    }
+
+    Ok(messages)
+}
+
+/// ## Description
+/// Simulates performing the hop route through the swap venue with `offer_asset`, returning the
+/// amount of the purchased asset as a [`Uint128`].
+/// ## Arguments
+/// * `querier` - A [`QuerierWrapper`] used to query the venue.
+///
+/// * `venue` - The [`SwapVenue`] performing the hop route.
+///
+/// * `offer_asset` - The [`Asset`] being swapped.
+///
+/// * `hops` - The hop route as a [`Vec<SwapOperation>`].
+pub fn simulate(
+    querier: &QuerierWrapper,
+    venue: &SwapVenue,
+    offer_asset: &Asset,
+    hops: &[SwapOperation],
+) -> StdResult<Uint128> {
+    match venue.venue_type {
+        SwapVenueType::AstroportRouter {} | SwapVenueType::Router {} => {
+            let res: SimulateSwapOperationsResponse = querier.query_wasm_smart(
+                &venue.contract_addr,
+                &RouterQueryMsg::SimulateSwapOperations {
+                    offer_amount: offer_asset.amount,
+                    operations: hops.to_vec(),
+                },
+            )?;
+
+            Ok(res.amount)
+        }
+        SwapVenueType::AstroportPair {} => {
+            if hops.len() != 1 {
+                return Err(StdError::generic_err(
+                    "A pair venue can only simulate a single swap",
+                ));
+            }
+
+            let res: SimulationResponse = querier.query_wasm_smart(
+                &venue.contract_addr,
+                &PairQueryMsg::Simulation {
+                    offer_asset: offer_asset.clone(),
+                },
+            )?;
+
+            Ok(res.return_amount)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use astroport::{
+        asset::{Asset, AssetInfo, PairInfo},
+        factory::{PairType, QueryMsg as FactoryQueryMsg},
+        pair::{Cw20HookMsg as PairCw20HookMsg, ExecuteMsg as PairExecuteMsg, QueryMsg},
+        router::{
+            ConfigResponse as RouterConfigResponse, QueryMsg as RouterQueryMsg, SwapOperation,
+        },
+    };
+    use astroport_dca::dca::{SwapVenue, SwapVenueType};
+    use cosmwasm_std::{
+        coins, from_binary, to_binary, Addr, ContractResult, CosmosMsg, Decimal, SystemError,
+        SystemResult, Uint128, WasmMsg, WasmQuery,
+    };
+    use cw20::Cw20ExecuteMsg;
+
+    use crate::{error::ContractError, state::CONFIG, tests::mock_instantiate};
+
+    use super::{assert_venue_route, swap_msgs, venue_factory};
+
+    fn pair_venue() -> SwapVenue {
+        SwapVenue {
+            name: "luna_krw".to_string(),
+            venue_type: SwapVenueType::AstroportPair {},
+            contract_addr: Addr::unchecked("pair"),
+        }
+    }
+
+    #[test]
+    fn does_swap_through_pair() {
+        let user = Addr::unchecked("user");
+        let hops = vec![SwapOperation::AstroSwap {
+            offer_asset_info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            ask_asset_info: AssetInfo::NativeToken {
+                denom: "ukrw".to_string(),
+            },
+        }];
+
+        // native tokens are sent to the pair with the swap
+        let offer_asset = Asset {
+            info: AssetInfo::NativeToken {
+                denom: "uluna".to_string(),
+            },
+            amount: Uint128::new(10_000),
+        };
+        assert_eq!(
+            swap_msgs(
+                &pair_venue(),
+                &user,
+                &user,
+                &offer_asset,
+                &hops,
+                Decimal::percent(5)
+            )
+            .unwrap(),
+            vec![CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: "pair".to_string(),
+                funds: coins(10_000, "uluna"),
+                msg: to_binary(&PairExecuteMsg::Swap {
+                    offer_asset: offer_asset.clone(),
+                    belief_price: None,
+                    max_spread: Some(Decimal::percent(5)),
+                    to: Some("user".to_string()),
+                })
+                .unwrap(),
+            })]
+        );
+
+        // cw20 tokens are sent to the pair from the user with a swap hook
+        let offer_asset = Asset {
+            info: AssetInfo::Token {
+                contract_addr: Addr::unchecked("token"),
+            },
+            amount: Uint128::new(10_000),
+        };
+        assert_eq!(
+            swap_msgs(
+                &pair_venue(),
+                &user,
+                &user,
+                &offer_asset,
+                &hops,
+                Decimal::percent(5)
+            )
+            .unwrap(),
+            vec![CosmosMsg::from(WasmMsg::Execute {
+                contract_addr: "token".to_string(),
+                funds: vec![],
+                msg: to_binary(&Cw20ExecuteMsg::SendFrom {
+                    owner: "user".to_string(),
+                    contract: "pair".to_string(),
+                    amount: Uint128::new(10_000),
+                    msg: to_binary(&PairCw20HookMsg::Swap {
+                        belief_price: None,
+                        max_spread: Some(Decimal::percent(5)),
+                        to: Some("user".to_string()),
+                    })
+                    .unwrap(),
+                })
+                .unwrap(),
+            })]
+        );
+    }
+
+    #[test]
+    fn does_validate_pair_venue_route() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let ujpy = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![ujpy.clone()],
+        );
+
+        let pair_assets = [uluna.clone(), ukrw.clone()];
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "pair" => {
+                match from_binary(msg).unwrap() {
+                    QueryMsg::Pair {} => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos: pair_assets.clone(),
+                            contract_addr: Addr::unchecked("pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Stable {},
+                        })
+                        .unwrap(),
+                    )),
+                    _ => unimplemented!(),
+                }
+            }
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        let querier = deps.as_ref().querier;
+
+        let swap = |offer: &AssetInfo, ask: &AssetInfo| SwapOperation::AstroSwap {
+            offer_asset_info: offer.clone(),
+            ask_asset_info: ask.clone(),
+        };
+
+        assert_eq!(
+            assert_venue_route(&querier, &config, &pair_venue(), &[swap(&uluna, &ukrw)]).unwrap(),
+            vec![Some(PairType::Stable {})]
+        );
+
+        // cannot swap through the pair more than once
+        assert_eq!(
+            assert_venue_route(
+                &querier,
+                &config,
+                &pair_venue(),
+                &[swap(&uluna, &ukrw), swap(&ukrw, &uluna)]
+            )
+            .unwrap_err(),
+            ContractError::InvalidPairVenueRoute {}
+        );
+
+        // cannot swap assets of another pair
+        assert_eq!(
+            assert_venue_route(&querier, &config, &pair_venue(), &[swap(&uluna, &ujpy)])
+                .unwrap_err(),
+            ContractError::InvalidPairVenueRoute {}
+        );
+
+        // cannot swap through a pair type that is not allowed
+        config.allowed_pair_types = vec![PairType::Xyk {}];
+        assert_eq!(
+            assert_venue_route(&querier, &config, &pair_venue(), &[swap(&uluna, &ukrw)])
+                .unwrap_err(),
+            ContractError::PairTypeNotAllowed {
+                pair_type: PairType::Stable {}
+            }
+        );
+    }
+
+    #[test]
+    fn does_validate_router_venue_route() {
+        let uluna = AssetInfo::NativeToken {
+            denom: "uluna".to_string(),
+        };
+        let ukrw = AssetInfo::NativeToken {
+            denom: "ukrw".to_string(),
+        };
+        let ujpy = AssetInfo::NativeToken {
+            denom: "ujpy".to_string(),
+        };
+
+        let (mut deps, ..) = mock_instantiate(
+            Addr::unchecked("factory"),
+            Addr::unchecked("router"),
+            vec![],
+            vec![ujpy.clone()],
+        );
+
+        // the router of the other DEX swaps through the pairs of its own factory, which only has a
+        // uluna-ukrw pair
+        let pair_assets = [uluna.clone(), ukrw.clone()];
+        deps.querier.update_wasm(move |query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "dex_router" => {
+                match from_binary(msg).unwrap() {
+                    RouterQueryMsg::Config {} => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&RouterConfigResponse {
+                            astroport_factory: "dex_factory".to_string(),
+                        })
+                        .unwrap(),
+                    )),
+                    _ => unimplemented!(),
+                }
+            }
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "dex_factory" => {
+                let asset_infos = match from_binary(msg).unwrap() {
+                    FactoryQueryMsg::Pair { asset_infos } => asset_infos,
+                    _ => unimplemented!(),
+                };
+                match pair_assets.contains(&asset_infos[0]) && pair_assets.contains(&asset_infos[1])
+                {
+                    true => SystemResult::Ok(ContractResult::Ok(
+                        to_binary(&PairInfo {
+                            asset_infos,
+                            contract_addr: Addr::unchecked("dex_pair"),
+                            liquidity_token: Addr::unchecked("lp_token"),
+                            pair_type: PairType::Xyk {},
+                        })
+                        .unwrap(),
+                    )),
+                    false => SystemResult::Ok(ContractResult::Err("Pair not found".to_string())),
+                }
+            }
+            _ => SystemResult::Err(SystemError::Unknown {}),
+        });
+
+        let mut config = CONFIG.load(&deps.storage).unwrap();
+        let querier = deps.as_ref().querier;
+
+        let venue = SwapVenue {
+            name: "dex".to_string(),
+            venue_type: SwapVenueType::Router {},
+            contract_addr: Addr::unchecked("dex_router"),
+        };
+        let swap = |offer: &AssetInfo, ask: &AssetInfo| SwapOperation::AstroSwap {
+            offer_asset_info: offer.clone(),
+            ask_asset_info: ask.clone(),
+        };
+
+        assert_eq!(
+            assert_venue_route(&querier, &config, &venue, &[swap(&uluna, &ukrw)]).unwrap(),
+            vec![Some(PairType::Xyk {})]
+        );
+
+        // cannot swap through a pair the other DEX does not have
+        assert_eq!(
+            assert_venue_route(
+                &querier,
+                &config,
+                &venue,
+                &[swap(&uluna, &ujpy), swap(&ujpy, &ukrw)]
+            )
+            .unwrap_err(),
+            ContractError::NonexistentPair {
+                offer: uluna.clone(),
+                ask: ujpy.clone()
+            }
+        );
+
+        // an Astroport router other than the configured one swaps through the pairs of its own
+        // factory as well
+        let astroport_venue = SwapVenue {
+            venue_type: SwapVenueType::AstroportRouter {},
+            ..venue.clone()
+        };
+        assert_eq!(
+            venue_factory(&querier, &config, &astroport_venue).unwrap(),
+            Some(Addr::unchecked("dex_factory"))
+        );
+        assert_eq!(
+            assert_venue_route(
+                &querier,
+                &config,
+                &astroport_venue,
+                &[swap(&uluna, &ujpy), swap(&ujpy, &ukrw)]
+            )
+            .unwrap_err(),
+            ContractError::NonexistentPair {
+                offer: uluna.clone(),
+                ask: ujpy
+            }
+        );
+
+        // cannot swap through a pair type that is not allowed
+        config.allowed_pair_types = vec![PairType::Stable {}];
+        assert_eq!(
+            assert_venue_route(&querier, &config, &venue, &[swap(&uluna, &ukrw)]).unwrap_err(),
+            ContractError::PairTypeNotAllowed {
+                pair_type: PairType::Xyk {}
+            }
+        );
+    }
+}
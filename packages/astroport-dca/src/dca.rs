@@ -1,14 +1,18 @@
+use std::fmt;
+
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use astroport::{
     asset::{Asset, AssetInfo},
+    factory::PairType,
     router::SwapOperation,
 };
 
-use cosmwasm_std::{Decimal, Uint128};
+use cosmwasm_std::{Addr, Decimal, StdResult, Uint128};
 
-/// Describes information about a DCA order
+/// Describes information about a DCA order. The options added since version 1.0 default to being
+/// unset, so that the orders stored by earlier versions still load
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct DcaInfo {
     /// The ID of the order
@@ -24,34 +28,288 @@ pub struct DcaInfo {
     pub last_purchase: u64,
     /// The amount of `initial_asset` to spend each DCA purchase
     pub dca_amount: Uint128,
+    /// The referrer of the order, who receives a share of each DCA purchase
+    #[serde(default)]
+    pub referral: Option<Referral>,
+    /// The executor who has the exclusive right to perform DCA purchases for the order for
+    /// `exclusivity_seconds` after each purchase becomes due
+    #[serde(default)]
+    pub preferred_executor: Option<Addr>,
+    /// The time in seconds after each purchase becomes due during which only the
+    /// `preferred_executor` can perform it
+    #[serde(default)]
+    pub exclusivity_seconds: u64,
+    /// Whether DCA purchases for the order can only be performed with the canonical route
+    /// registered for its assets
+    #[serde(default)]
+    pub require_canonical_route: bool,
+    /// The maximum price impact of each DCA purchase, which overrides the maximum price impact in
+    /// the configuration of the user
+    #[serde(default)]
+    pub max_price_impact: Option<Decimal>,
+    /// The refundable deposit paid when the order was created, which is returned when the order is
+    /// cancelled or completed
+    #[serde(default)]
+    pub deposit: Option<Asset>,
+}
+
+impl DcaInfo {
+    /// Returns the time at which the next DCA purchase becomes due, saturating at [`u64::MAX`] for
+    /// orders with very large intervals
+    pub fn next_purchase(&self) -> u64 {
+        self.last_purchase.saturating_add(self.interval)
+    }
+
+    /// Returns the time at which the exclusivity period of the `preferred_executor` for the next
+    /// DCA purchase ends, saturating at [`u64::MAX`]
+    pub fn exclusivity_end(&self) -> u64 {
+        self.next_purchase()
+            .saturating_add(self.exclusivity_seconds)
+    }
+
+    /// Returns the executor who has the exclusive right to perform the next DCA purchase at the
+    /// time `now`, or [`None`] if any keeper can perform it once it is due
+    pub fn exclusive_executor(&self, now: u64) -> Option<&Addr> {
+        self.preferred_executor
+            .as_ref()
+            .filter(|_| now < self.exclusivity_end())
+    }
+}
+
+/// Describes the referrer of a DCA order and the share of each DCA purchase they receive
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Referral {
+    /// The address of the referrer
+    pub referrer: Addr,
+    /// The share of each DCA purchase paid to the referrer in basis points
+    pub fee_bps: u16,
+}
+
+/// Describes a whitelisted asset that can be used for bot tips, along with the tip paid in it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeAsset {
+    /// The asset that the tip is paid in
+    pub info: AssetInfo,
+    /// The flat tip paid for each DCA purchase, regardless of the amount of hops performed
+    pub base_fee: Uint128,
+    /// The tip paid for each hop performed in a DCA purchase
+    pub per_hop_fee: Uint128,
+    /// An optional additional tip paid for each DCA purchase that is performed as part of a
+    /// batched execution
+    pub batch_surcharge: Option<Uint128>,
+}
+
+impl FeeAsset {
+    /// Calculates the full tip for a DCA purchase performed with `hops` hops, which is
+    /// `base_fee + per_hop_fee * hops`
+    pub fn tip(&self, hops: u32) -> StdResult<Uint128> {
+        Ok(self
+            .per_hop_fee
+            .checked_mul(Uint128::from(hops))?
+            .checked_add(self.base_fee)?)
+    }
+
+    /// Calculates the full tip for a DCA purchase performed with `hops` hops as part of a batched
+    /// execution, which also includes the `batch_surcharge`
+    pub fn batch_tip(&self, hops: u32) -> StdResult<Uint128> {
+        Ok(self
+            .tip(hops)?
+            .checked_add(self.batch_surcharge.unwrap_or_default())?)
+    }
+}
+
+/// Describes the permissioned keeper mode, where only keepers that have bonded an asset can perform
+/// DCA purchases
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct KeeperConfig {
+    /// Whether only registered keepers can perform DCA purchases
+    pub enabled: bool,
+    /// The whitelisted fee asset and amount a keeper needs to bond to register
+    pub bond: Asset,
+    /// The fraction below the TWAP reference at which a DCA purchase can be challenged to slash
+    /// the bond of the keeper who performed it
+    pub slash_threshold: Decimal,
+    /// The time in seconds after a DCA purchase in which it can be challenged, which is also the
+    /// time a keeper has to wait after unbonding to claim their bond
+    pub challenge_period: u64,
+}
+
+/// Describes the default maximum spread for DCA purchases swapping through pairs of a pair type
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PairTypeSpread {
+    /// The Astroport pair type
+    pub pair_type: PairType,
+    /// The default maximum spread when swapping through pairs of the pair type
+    pub max_spread: Decimal,
+}
+
+/// Describes the interface of a swap venue, which determines the messages used to perform and
+/// simulate the swaps of a hop route through it
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapVenueType {
+    /// An Astroport router, which can perform hop routes through the Astroport pairs
+    AstroportRouter {},
+    /// A single Astroport pair, which can only perform a hop route of a single swap through it
+    AstroportPair {},
+    /// The router of another DEX with the same swap interface as the Astroport router, which
+    /// performs hop routes through the pairs of that DEX
+    Router {},
+}
+
+/// Describes a whitelisted swap venue that DCA purchases can be performed through
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SwapVenue {
+    /// The unique name of the venue, which bots specify to perform a DCA purchase through it
+    pub name: String,
+    /// The interface of the venue
+    pub venue_type: SwapVenueType,
+    /// The address of the venue contract
+    pub contract_addr: Addr,
+}
+
+impl SwapVenue {
+    /// Checks if the venue swaps through Astroport pairs
+    pub fn is_astroport(&self) -> bool {
+        self.venue_type != SwapVenueType::Router {}
+    }
+}
+
+/// Describes the assets that can be used on one side of DCA orders
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedAssets {
+    /// Any asset can be used
+    All {},
+    /// Only the listed assets can be used
+    Only { assets: Vec<AssetInfo> },
+}
+
+impl AllowedAssets {
+    /// Checks if `asset` can be used
+    pub fn allows(&self, asset: &AssetInfo) -> bool {
+        match self {
+            AllowedAssets::All {} => true,
+            AllowedAssets::Only { assets } => assets.contains(asset),
+        }
+    }
+}
+
+impl Default for AllowedAssets {
+    fn default() -> Self {
+        AllowedAssets::All {}
+    }
+}
+
+/// Describes a part of the contract that can be paused in an emergency
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum PauseScope {
+    /// Performing DCA purchases, both single and batched
+    Purchases {},
+    /// Creating and modifying DCA orders
+    OrderCreation {},
+    /// Depositing bot tips
+    TipDeposits {},
+}
+
+impl fmt::Display for PauseScope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PauseScope::Purchases {} => write!(f, "purchases"),
+            PauseScope::OrderCreation {} => write!(f, "order creation"),
+            PauseScope::TipDeposits {} => write!(f, "tip deposits"),
+        }
+    }
+}
+
+/// Describes a DCA purchase performed as part of a batched execution
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct BatchPurchase {
+    /// The user the DCA purchase is performed for
+    pub user: String,
+    /// The ID of the DCA order of the user
+    pub id: u64,
+    /// The hop route to swap through, which is discovered by the contract if not specified
+    pub hops: Option<Vec<SwapOperation>>,
+    /// The tip redeemed for the DCA purchase
+    pub fee_redeem: Vec<Asset>,
 }
 
 /// Describes the parameters used for creating a contract
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct InstantiateMsg {
+    /// The owner of the contract, who can update its configuration
+    pub owner: String,
+    /// The guardian of the contract, who can only pause it in an emergency
+    pub guardian: Option<String>,
+    /// The delay in seconds before configuration updates can be executed, defaulting to 1 day
+    pub config_timelock: Option<u64>,
     /// The maximum amount of hops to perform from `initial_asset` to `target_asset` when DCAing if
     /// the user does not specify a custom max hop amount
     pub max_hops: u32,
     /// The whitelisted tokens that can be used in a DCA hop route
     pub whitelisted_tokens: Vec<AssetInfo>,
-    /// The whitelisted tokens that can be used for bot tips, along with the fee for each hop
-    pub whitelisted_fee_assets: Vec<Asset>,
+    /// The whitelisted tokens that can be used for bot tips, along with the tip paid in each
+    pub whitelisted_fee_assets: Vec<FeeAsset>,
     /// The default for the maximum amount of spread in a swap
     pub max_spread: String,
     /// The address of the Astroport factory contract
     pub factory_addr: String,
     /// The address of the Astroport router contract
     pub router_addr: String,
+    /// The fraction of each DCA purchase taken as a protocol fee, defaulting to no protocol fee
+    pub protocol_fee: Option<Decimal>,
+    /// The address that receives the protocol fee
+    pub fee_collector: Option<String>,
+    /// The maximum referral fee in basis points that can be set on a DCA order
+    pub max_referral_fee_bps: u16,
+    /// The permissioned keeper mode configuration, if keepers can register
+    pub keeper_config: Option<KeeperConfig>,
+    /// The Astroport pair types that can be swapped through in a DCA hop route, defaulting to the
+    /// xyk and stable pair types
+    pub allowed_pair_types: Option<Vec<PairType>>,
+    /// The default maximum spread for each pair type, which overrides `max_spread` for hop routes
+    /// swapping through pairs of that type
+    pub pair_type_max_spreads: Option<Vec<PairTypeSpread>>,
+    /// The whitelisted swap venues that DCA purchases can be performed through besides the
+    /// Astroport router at `router_addr`
+    pub venues: Option<Vec<SwapVenue>>,
+    /// The assets that can be deposited as the initial asset of DCA orders, defaulting to all
+    pub allowed_initial_assets: Option<AllowedAssets>,
+    /// The assets that can be purchased as the target asset of DCA orders, defaulting to all
+    pub allowed_target_assets: Option<AllowedAssets>,
+    /// The minimum DCA amount of orders in each initial asset, where assets without a minimum can
+    /// be purchased in any amount
+    pub min_dca_amounts: Option<Vec<Asset>>,
+    /// The maximum amount of DCA orders a user can have at once, defaulting to 50
+    pub max_orders_per_user: Option<u32>,
+    /// The refundable deposit paid in a whitelisted fee asset for each DCA order, if any
+    pub order_deposit: Option<Asset>,
 }
 
 /// This structure describes the execute messages available in the contract
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 #[serde(rename_all = "snake_case")]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     /// Add top-up for bots to perform DCA requests with the specified `assets` sent in the tx
     AddBotTip { assets: Vec<Asset> },
+    /// Queues adding `tokens` to the whitelisted tokens that can be used in a DCA hop route, which
+    /// can be executed with `ExecutePendingConfig` once the configuration timelock has passed,
+    /// unless another configuration update is already pending
+    AddWhitelistedTokens { tokens: Vec<AssetInfo> },
     /// Cancels a DCA order given the id of the order, returning any native asset back to the user
     CancelDcaOrder { id: u64 },
+    /// Cancels the configuration update queued with `UpdateConfig`
+    CancelPendingConfig {},
+    /// Challenges the DCA purchase `id` performed by a registered keeper, slashing the keepers bond
+    /// in favor of the user if the purchase returned far less than the TWAP reference
+    ChallengeExecution { id: u64 },
+    /// Claims the bond of a keeper that has unbonded once the challenge period has passed
+    ClaimKeeperBond {},
+    /// Claims the ownership of the contract as the proposed new owner
+    ClaimOwnership {},
     /// Creates a new DCA order where `dca_amount` of token `initial_asset` will purchase
     /// `target_asset` every `interval`
     ///
@@ -60,13 +318,49 @@ pub enum ExecuteMsg {
     ///
     /// DCA purchases can start from `first_purchase` if specified, otherwise as soon as the DCA
     /// order is made
+    ///
+    /// If `referrer` is specified, `referral_fee_bps` of each DCA purchase is paid to them in
+    /// `initial_asset`
+    ///
+    /// If `preferred_executor` is specified, only they can perform each DCA purchase for
+    /// `exclusivity_seconds` after it becomes due
+    ///
+    /// If `require_canonical_route` is set, DCA purchases can only be performed with the canonical
+    /// route registered for `initial_asset` and `target_asset`
+    ///
+    /// If `max_price_impact` is specified, DCA purchases fail if their simulated return is further
+    /// below the spot price of the pools than this fraction
     CreateDcaOrder {
         initial_asset: Asset,
         target_asset: AssetInfo,
         interval: u64,
         dca_amount: Uint128,
         first_purchase: Option<u64>,
+        /// The referrer of the order, such as the front-end the order was created through
+        referrer: Option<String>,
+        /// The share of each DCA purchase paid to `referrer` in basis points
+        referral_fee_bps: Option<u16>,
+        /// The executor who has the exclusive right to perform DCA purchases, such as the bot of
+        /// the user
+        preferred_executor: Option<String>,
+        /// The time in seconds after each purchase becomes due during which only
+        /// `preferred_executor` can perform it, which cannot exceed `interval`
+        exclusivity_seconds: Option<u64>,
+        /// Whether DCA purchases can only be performed with the canonical route, defaulting to
+        /// false
+        require_canonical_route: Option<bool>,
+        /// The maximum price impact of each DCA purchase, overriding the one of the user
+        max_price_impact: Option<Decimal>,
     },
+    /// Removes the pending proposal to transfer the ownership of the contract
+    DropOwnershipProposal {},
+    /// Cancels all DCA orders of the sender and returns every asset the contract holds for them,
+    /// which are the native initial assets, the order deposits and the native tip balance. This
+    /// only depends on the state of the sender, so it can be used while the contract is paused
+    EmergencyWithdrawAll {},
+    /// Applies the configuration update queued with `UpdateConfig` once its activation time has
+    /// passed
+    ExecutePendingConfig {},
     /// Modifies an existing DCA order, allowing the user to change certain parameters
     ModifyDcaOrder {
         id: u64,
@@ -76,31 +370,113 @@ pub enum ExecuteMsg {
         new_dca_amount: Uint128,
         new_first_purchase: Option<u64>,
     },
+    /// Pauses `scope` of the contract, which can be called by the owner or the guardian
+    ///
+    /// Cancelling DCA orders and withdrawing bot tips cannot be paused
+    Pause { scope: PauseScope },
     /// Performs a DCA purchase for a specified user given a hop route and requested fee redemption
+    ///
+    /// The tip paid for a purchase is `base_fee + per_hop_fee * hops` in each fee asset, and
+    /// `fee_redeem` can split the tip between several fee assets, as long as the redeemed
+    /// fractions of each tip do not exceed the full tip together
+    ///
+    /// If `hops` is not specified, the contract discovers the route with the best return through
+    /// the Astroport pairs between the whitelisted tokens itself
+    ///
+    /// The hop route is performed through the whitelisted swap venue named `venue`, or through the
+    /// Astroport router if no venue is specified
     PerformDcaPurchase {
         user: String,
         id: u64,
-        hops: Vec<SwapOperation>,
+        hops: Option<Vec<SwapOperation>>,
         fee_redeem: Vec<Asset>,
+        venue: Option<String>,
     },
-    /// Updates the configuration of the contract
+    /// Performs several DCA purchases in a batched execution through the Astroport router, where
+    /// the tip paid for each purchase also includes the `batch_surcharge` of each fee asset
+    ///
+    /// DCA purchases with the same initial asset, target asset and hop route are swapped together
+    /// in a single swap, of which each user receives a share proportional to the amount they
+    /// swapped
+    PerformDcaPurchases { purchases: Vec<BatchPurchase> },
+    /// Proposes `owner` as the new owner of the contract, who can claim the ownership within
+    /// `expires_in` seconds
+    ProposeNewOwner { owner: String, expires_in: u64 },
+    /// Queues an update of the configuration of the contract, which can be executed with
+    /// `ExecutePendingConfig` once the configuration timelock has passed, unless another update is
+    /// already pending
     UpdateConfig {
         /// The new maximum amount of hops to perform from `initial_asset` to `target_asset` when
         /// performing DCA purchases if the user does not specify a custom max hop amount
         max_hops: Option<u32>,
-        /// The new whitelisted tokens that can be used in a DCA hop route
-        whitelisted_tokens: Option<Vec<AssetInfo>>,
-        /// The whitelisted tokens that can be used for bot tips, along with the fee for each hop
-        whitelisted_fee_assets: Option<Vec<Asset>>,
         /// The new maximum spread for DCA purchases
         max_spread: Option<Decimal>,
+        /// The new fraction of each DCA purchase taken as a protocol fee
+        protocol_fee: Option<Decimal>,
+        /// The new address that receives the protocol fee
+        fee_collector: Option<String>,
+        /// The new maximum referral fee in basis points that can be set on a DCA order
+        max_referral_fee_bps: Option<u16>,
+        /// The new permissioned keeper mode configuration
+        keeper_config: Option<KeeperConfig>,
+        /// The new Astroport pair types that can be swapped through in a DCA hop route
+        allowed_pair_types: Option<Vec<PairType>>,
+        /// The new default maximum spread for each pair type
+        pair_type_max_spreads: Option<Vec<PairTypeSpread>>,
+        /// The new whitelisted swap venues
+        venues: Option<Vec<SwapVenue>>,
+        /// The new guardian of the contract
+        guardian: Option<String>,
+        /// The new delay in seconds before configuration updates can be executed
+        config_timelock: Option<u64>,
+        /// The new address of the Astroport factory contract
+        factory_addr: Option<String>,
+        /// The new address of the Astroport router contract, which has to use the factory
+        router_addr: Option<String>,
+        /// The new assets that can be deposited as the initial asset of DCA orders
+        allowed_initial_assets: Option<AllowedAssets>,
+        /// The new assets that can be purchased as the target asset of DCA orders
+        allowed_target_assets: Option<AllowedAssets>,
+        /// The new minimum DCA amount of orders in each initial asset
+        min_dca_amounts: Option<Vec<Asset>>,
+        /// The new maximum amount of DCA orders a user can have at once
+        max_orders_per_user: Option<u32>,
+        /// The new refundable deposit for each DCA order, where a zero amount disables the deposit
+        order_deposit: Option<Asset>,
+    },
+    /// Registers the sender as a keeper, bonding the keeper bond set in the configuration
+    ///
+    /// If the bond is a cw20 token, the keeper needs to have increased the allowance prior to
+    /// calling this execution
+    RegisterKeeper {},
+    /// Removes `asset` from the whitelisted fee assets, keeping existing tip balances in it
+    /// withdrawable
+    RemoveFeeAsset { asset: AssetInfo },
+    /// Removes the canonical route from `from` to `to`
+    RemoveRoute { from: AssetInfo, to: AssetInfo },
+    /// Removes `tokens` from the whitelisted tokens that can be used in a DCA hop route
+    RemoveWhitelistedTokens { tokens: Vec<AssetInfo> },
+    /// Whitelists `fee_asset` for bot tips, replacing the tip paid in it if already whitelisted
+    SetFeeAsset { fee_asset: FeeAsset },
+    /// Registers `hops` as the canonical route from `from` to `to`, which orders can require DCA
+    /// purchases to be performed with
+    SetRoute {
+        from: AssetInfo,
+        to: AssetInfo,
+        hops: Vec<SwapOperation>,
     },
+    /// Starts unbonding the bond of the sender, who can no longer perform DCA purchases
+    UnbondKeeper {},
+    /// Unpauses `scope` of the contract, which can only be called by the owner
+    Unpause { scope: PauseScope },
     /// Update the configuration for a user
     UpdateUserConfig {
         /// The maximum amount of hops per swap
         max_hops: Option<u32>,
         /// The maximum spread per token when performing DCA purchases
         max_spread: Option<Decimal>,
+        /// The maximum price impact of DCA purchases compared to the spot price of the pools
+        max_price_impact: Option<Decimal>,
     },
     /// Withdraws the `assets` amount of a users bot tip from the contract.
     Withdraw { assets: Vec<Asset> },
@@ -114,14 +490,81 @@ pub enum QueryMsg {
     UserDcaOrders { user: String },
     /// Returns information about the contract configuration in a [`Config`] object.
     Config {},
+    /// Returns the paused scopes of the contract in a [`Vec<PauseScope>`] object.
+    Paused {},
+    /// Returns the configuration update queued by the owner in a [`Option<PendingConfig>`] object.
+    PendingConfig {},
     /// Returns the users current configuration as a [`UserConfig`] object.
     UserConfig { user: String },
+    /// Returns the full tip a bot would receive for performing the DCA purchase `id` of `user`
+    /// with `hops` hops, for each whitelisted fee asset the user has a large enough tip balance
+    /// for, in a [`Vec<Asset>`] object.
+    EstimateTip { user: String, id: u64, hops: u32 },
+    /// Returns the volume originated and the fees earned by a referrer in a [`ReferrerStats`]
+    /// object.
+    ReferrerStats { referrer: String },
+    /// Returns the registration of a keeper in a [`Option<Keeper>`] object.
+    Keeper { keeper: String },
+    /// Returns the record of a DCA purchase performed by a registered keeper in a
+    /// [`KeeperExecution`] object.
+    KeeperExecution { id: u64 },
+    /// Returns the amount of DCA purchases performed and the tips earned by an address in a
+    /// [`KeeperStats`] object.
+    KeeperStats { keeper: String },
+    /// Returns the statistics of every address that has performed DCA purchases, ordered by
+    /// address, in a [`Vec<KeeperInfo>`] object.
+    Keepers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// Returns the canonical route from `from` to `to` in a [`Option<Vec<SwapOperation>>`] object.
+    Route { from: AssetInfo, to: AssetInfo },
+    /// Returns the DCA purchases performed for the DCA order `id` of `user` in a
+    /// [`Vec<PurchaseRecord>`] object.
+    PurchaseHistory { user: String, id: u64 },
+    /// Dry runs the DCA purchase `id` of `user` as if it were performed by `executor`, performing
+    /// every validation of [`ExecuteMsg::PerformDcaPurchase`], and returns its expected outcome in
+    /// a [`SimulateDcaPurchaseResponse`] object, or the error the purchase would fail with.
+    SimulateDcaPurchase {
+        user: String,
+        id: u64,
+        hops: Option<Vec<SwapOperation>>,
+        fee_redeem: Vec<Asset>,
+        venue: Option<String>,
+        /// The address that would perform the DCA purchase, such as the keeper
+        executor: String,
+    },
 }
 
-/// This structure describes a migration message.
-/// We currently take no arguments for migrations.
+/// Describes the expected outcome of a DCA purchase for a SimulateDcaPurchase query
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
-pub struct MigrateMsg {}
+pub struct SimulateDcaPurchaseResponse {
+    /// The asset that would be swapped, after the protocol and referral fees were taken
+    pub offer_asset: Asset,
+    /// The hop route that would be swapped through
+    pub hops: Vec<SwapOperation>,
+    /// The simulated amount of the target asset the user would receive
+    pub return_amount: Uint128,
+    /// The tips that would be paid to the executor
+    pub tips: Vec<Asset>,
+    /// The DCA order after the purchase, or [`None`] if the purchase would complete the order
+    pub order: Option<DcaInfo>,
+}
+
+/// This structure describes a migration message, with optional parameters that are set in the
+/// configuration once the state of the contract was migrated.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct MigrateMsg {
+    /// The new owner of the contract, where migrating from a version without an owner defaults it
+    /// to the owner of the factory
+    pub owner: Option<String>,
+    /// The new guardian of the contract
+    pub guardian: Option<String>,
+    /// The new delay in seconds before configuration updates can be executed
+    pub config_timelock: Option<u64>,
+    /// The new maximum amount of DCA orders a user can have at once
+    pub max_orders_per_user: Option<u32>,
+}
 
 /// Describes information for a UserDcaOrders query
 ///
@@ -134,4 +577,7 @@ pub struct MigrateMsg {}
 pub struct DcaQueryInfo {
     pub token_allowance: Uint128,
     pub order: DcaInfo,
+    /// The executor who has the exclusive right to perform the next DCA purchase at the current
+    /// time, or [`None`] if any keeper can perform it once it is due
+    pub exclusive_executor: Option<Addr>,
 }